@@ -1 +1,3 @@
-pub mod msg;
\ No newline at end of file
+pub mod msg;
+#[cfg(feature = "mt")]
+pub mod multitest;
@@ -1,7 +1,18 @@
 use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Uint128;
 
+// set by the membership contract as ProposeMember response data once the candidate's proxy exists
 #[cw_serde]
 pub struct ProposeMemberData {
     pub owner_addr: String,
     pub proxy_addr: String,
+    #[serde(default)]
+    pub member_id: Option<u64>, // as echoed back by the proxy's instantiate data
+}
+
+// set by the distribution contract as withdraw response data, one entry per paid asset
+#[cw_serde]
+pub struct WithdrawResponse {
+    pub amount: Uint128,
+    pub denom: String, // token contract address for cw20
 }
@@ -0,0 +1,109 @@
+use std::marker::PhantomData;
+
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    Addr, Api, CanonicalAddr, OwnedDeps, RecoverPubkeyError, StdError, StdResult, VerificationError,
+};
+use cw_multi_test::{AppBuilder, BankKeeper};
+
+// the MockApi of cosmwasm 2 only takes bech32 addresses, this one also takes the plain
+// lowercase names the tests use ("owner", "donor1"), contracts still get bech32 addresses
+#[derive(Clone, Copy, Default)]
+pub struct PlainApi(MockApi);
+
+pub type App = cw_multi_test::App<BankKeeper, PlainApi>;
+
+pub fn mock_app() -> App {
+    AppBuilder::new()
+        .with_api(PlainApi::default())
+        .build(|_, _, _| {})
+}
+
+// cosmwasm's mock_dependencies with PlainApi, for unit tests
+pub fn mock_dependencies() -> OwnedDeps<MockStorage, PlainApi, MockQuerier> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: PlainApi::default(),
+        querier: MockQuerier::default(),
+        custom_query_type: PhantomData,
+    }
+}
+
+// marks a canonical plain name, no contract address hashes to this
+const PLAIN: &[u8] = b"plain:";
+
+impl Api for PlainApi {
+    fn addr_validate(&self, input: &str) -> StdResult<Addr> {
+        let canonical = self.addr_canonicalize(input)?;
+        let normalized = self.addr_humanize(&canonical)?;
+        if input != normalized.as_str() {
+            return Err(StdError::generic_err(
+                "Invalid input: address not normalized",
+            ));
+        }
+        Ok(normalized)
+    }
+
+    fn addr_canonicalize(&self, input: &str) -> StdResult<CanonicalAddr> {
+        if let Ok(canonical) = self.0.addr_canonicalize(input) {
+            return Ok(canonical);
+        }
+        if input.len() < 3 {
+            return Err(StdError::generic_err(
+                "Invalid input: human address too short for this mock implementation (must be >= 3).",
+            ));
+        }
+        Ok([PLAIN, input.to_lowercase().as_bytes()].concat().into())
+    }
+
+    fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+        match canonical.as_slice().strip_prefix(PLAIN) {
+            Some(plain) => String::from_utf8(plain.to_vec())
+                .map(Addr::unchecked)
+                .map_err(StdError::invalid_utf8),
+            None => self.0.addr_humanize(canonical),
+        }
+    }
+
+    fn secp256k1_verify(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        self.0.secp256k1_verify(message_hash, signature, public_key)
+    }
+
+    fn secp256k1_recover_pubkey(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        recovery_param: u8,
+    ) -> Result<Vec<u8>, RecoverPubkeyError> {
+        self.0
+            .secp256k1_recover_pubkey(message_hash, signature, recovery_param)
+    }
+
+    fn ed25519_verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        self.0.ed25519_verify(message, signature, public_key)
+    }
+
+    fn ed25519_batch_verify(
+        &self,
+        messages: &[&[u8]],
+        signatures: &[&[u8]],
+        public_keys: &[&[u8]],
+    ) -> Result<bool, VerificationError> {
+        self.0
+            .ed25519_batch_verify(messages, signatures, public_keys)
+    }
+
+    fn debug(&self, message: &str) {
+        self.0.debug(message)
+    }
+}
@@ -0,0 +1,49 @@
+use cosmwasm_std::{
+    from_json, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+};
+
+use crate::error::ContractError;
+use crate::msg::{Cw20HookMsg, ExecMsg, InstantiateMsg, QueryMsg};
+use crate::state::{CW20_PREFIX, MEMBERSHIP, TOTAL_WEIGHT};
+
+mod exec;
+mod query;
+
+pub fn instantiate(deps: DepsMut, msg: InstantiateMsg) -> Result<Response, ContractError> {
+    let membership = deps.api.addr_validate(&msg.membership)?;
+    MEMBERSHIP.save(deps.storage, &membership)?;
+    TOTAL_WEIGHT.save(deps.storage, &0)?;
+    Ok(Response::new())
+}
+
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecMsg,
+) -> Result<Response, ContractError> {
+    use ExecMsg::*;
+
+    match msg {
+        Distribute {} => exec::distribute(deps, info),
+        Withdraw { weight, diff } => exec::withdraw(deps, info, weight, diff),
+        // info.sender is the token contract here
+        Receive(msg) => match from_json(&msg.msg)? {
+            Cw20HookMsg::Distribute {} => {
+                let asset = format!("{CW20_PREFIX}{}", info.sender);
+                exec::distribute_asset(deps, &asset, msg.amount)
+            }
+        },
+    }
+}
+
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    use QueryMsg::*;
+
+    match msg {
+        TotalWeight {} => to_json_binary(&query::total_weight(deps)?),
+        Weight { proxy } => to_json_binary(&query::weight(deps, proxy)?),
+        Undistributed { denom } => to_json_binary(&query::undistributed(deps, denom)?),
+        Withdrawable { proxy, denom } => to_json_binary(&query::withdrawable(deps, proxy, denom)?),
+    }
+}
@@ -0,0 +1,163 @@
+use common::msg::WithdrawResponse;
+use cosmwasm_std::{
+    ensure, to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal256, DepsMut, Int128,
+    MessageInfo, Order, Response, StdResult, Storage, Uint128, Uint256, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_utils::PaymentError;
+
+use crate::error::ContractError;
+use crate::msg::{IsMemberResponse, MembershipQueryMsg};
+use crate::state::{
+    CW20_PREFIX, MEMBERSHIP, PAID_POINTS, POINTS_PER_WEIGHT, TOTAL_WEIGHT, UNDISTRIBUTED, WEIGHTS,
+};
+
+pub fn distribute(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure!(!info.funds.is_empty(), PaymentError::NoFunds {});
+
+    for coin in &info.funds {
+        add_points(deps.storage, &coin.denom, coin.amount)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "distribute")
+        .add_attribute("sender", info.sender.as_str()))
+}
+
+pub fn distribute_asset(
+    deps: DepsMut,
+    asset: &str,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    add_points(deps.storage, asset, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "distribute")
+        .add_attribute("asset", asset)
+        .add_attribute("amount", amount.to_string()))
+}
+
+// funds received while nobody has weight wait for the next distribution
+fn add_points(storage: &mut dyn Storage, asset: &str, amount: Uint128) -> StdResult<()> {
+    let total_weight = TOTAL_WEIGHT.load(storage)?;
+    let amount = amount + UNDISTRIBUTED.may_load(storage, asset)?.unwrap_or_default();
+
+    if total_weight == 0 {
+        return UNDISTRIBUTED.save(storage, asset, &amount);
+    }
+
+    UNDISTRIBUTED.remove(storage, asset);
+    let points = Decimal256::from_ratio(amount, total_weight);
+    POINTS_PER_WEIGHT.update(storage, asset, |ppw| -> StdResult<_> {
+        Ok(ppw.unwrap_or_default() + points)
+    })?;
+    Ok(())
+}
+
+// the share is settled with the weight the proxy had so far, only then the weight moves
+pub fn withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    weight: u64,
+    diff: Int128,
+) -> Result<Response, ContractError> {
+    let proxy = info.sender;
+    let membership = MEMBERSHIP.load(deps.storage)?;
+    let resp: IsMemberResponse = deps.querier.query_wasm_smart(
+        membership,
+        &MembershipQueryMsg::IsMember {
+            addr: proxy.to_string(),
+        },
+    )?;
+    ensure!(resp.is_member, ContractError::Unauthorized);
+
+    // a proxy seen for the first time (or switched over to this contract) brings its weight along
+    let old_weight = WEIGHTS.may_load(deps.storage, &proxy)?;
+    if let Some(registered) = old_weight {
+        ensure!(
+            weight == registered,
+            ContractError::WeightMismatch {
+                reported: weight,
+                registered
+            }
+        );
+    }
+    let old_weight = old_weight.unwrap_or_default();
+
+    let new_weight = Int128::from(weight)
+        .checked_add(diff)
+        .map_err(|_| ContractError::WeightOverflow)?;
+    ensure!(!new_weight.is_negative(), ContractError::NegativeWeight);
+    let new_weight = u64::try_from(new_weight.i128()).map_err(|_| ContractError::WeightOverflow)?;
+
+    let owed = settle(deps.storage, &proxy)?;
+
+    TOTAL_WEIGHT.update(deps.storage, |total| {
+        total
+            .checked_sub(old_weight)
+            .and_then(|total| total.checked_add(new_weight))
+            .ok_or(ContractError::WeightOverflow)
+    })?;
+    WEIGHTS.save(deps.storage, &proxy, &new_weight)?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let mut withdrawn = vec![];
+    for (asset, amount) in owed {
+        let denom = match asset.strip_prefix(CW20_PREFIX) {
+            Some(token) => {
+                msgs.push(
+                    WasmMsg::Execute {
+                        contract_addr: token.to_owned(),
+                        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                            recipient: proxy.to_string(),
+                            amount,
+                        })?,
+                        funds: vec![],
+                    }
+                    .into(),
+                );
+                token.to_owned()
+            }
+            None => {
+                msgs.push(
+                    BankMsg::Send {
+                        to_address: proxy.to_string(),
+                        amount: vec![Coin::new(amount.u128(), &asset)],
+                    }
+                    .into(),
+                );
+                asset
+            }
+        };
+        withdrawn.push(WithdrawResponse { amount, denom });
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "withdraw")
+        .add_attribute("proxy", proxy.as_str())
+        .add_attribute("weight", new_weight.to_string())
+        .set_data(to_json_binary(&withdrawn)?))
+}
+
+// everything distributed since the proxy's last withdraw, per asset
+fn settle(storage: &mut dyn Storage, proxy: &Addr) -> StdResult<Vec<(String, Uint128)>> {
+    let weight = WEIGHTS.may_load(storage, proxy)?.unwrap_or_default();
+    let points: Vec<(String, Decimal256)> = POINTS_PER_WEIGHT
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    let mut owed = vec![];
+    for (asset, ppw) in points {
+        let paid = PAID_POINTS
+            .may_load(storage, (proxy, &asset))?
+            .unwrap_or_default();
+        PAID_POINTS.save(storage, (proxy, &asset), &ppw)?;
+
+        let amount = Uint128::try_from(Uint256::from(weight).mul_floor(ppw - paid))?;
+        if !amount.is_zero() {
+            owed.push((asset, amount));
+        }
+    }
+    Ok(owed)
+}
@@ -0,0 +1,36 @@
+use cosmwasm_std::{Deps, StdResult, Uint128, Uint256};
+
+use crate::msg::{UndistributedResponse, WeightResponse, WithdrawableResponse};
+use crate::state::{PAID_POINTS, POINTS_PER_WEIGHT, TOTAL_WEIGHT, UNDISTRIBUTED, WEIGHTS};
+
+pub fn total_weight(deps: Deps) -> StdResult<WeightResponse> {
+    let weight = TOTAL_WEIGHT.load(deps.storage)?;
+    Ok(WeightResponse { weight })
+}
+
+pub fn weight(deps: Deps, proxy: String) -> StdResult<WeightResponse> {
+    let proxy = deps.api.addr_validate(&proxy)?;
+    let weight = WEIGHTS.may_load(deps.storage, &proxy)?.unwrap_or_default();
+    Ok(WeightResponse { weight })
+}
+
+pub fn undistributed(deps: Deps, denom: String) -> StdResult<UndistributedResponse> {
+    let amount = UNDISTRIBUTED
+        .may_load(deps.storage, &denom)?
+        .unwrap_or_default();
+    Ok(UndistributedResponse { amount })
+}
+
+pub fn withdrawable(deps: Deps, proxy: String, denom: String) -> StdResult<WithdrawableResponse> {
+    let proxy = deps.api.addr_validate(&proxy)?;
+    let weight = WEIGHTS.may_load(deps.storage, &proxy)?.unwrap_or_default();
+    let ppw = POINTS_PER_WEIGHT
+        .may_load(deps.storage, &denom)?
+        .unwrap_or_default();
+    let paid = PAID_POINTS
+        .may_load(deps.storage, (&proxy, &denom))?
+        .unwrap_or_default();
+
+    let amount = Uint128::try_from(Uint256::from(weight).mul_floor(ppw - paid))?;
+    Ok(WithdrawableResponse { amount })
+}
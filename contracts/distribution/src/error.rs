@@ -0,0 +1,19 @@
+use cosmwasm_std::StdError;
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    StdError(#[from] StdError),
+    #[error("{0}")]
+    PaymentError(#[from] PaymentError),
+    #[error("Unauthorized")]
+    Unauthorized,
+    #[error("Weight {reported} doesn't match the registered weight {registered}")]
+    WeightMismatch { reported: u64, registered: u64 },
+    #[error("Weight can't go below zero")]
+    NegativeWeight,
+    #[error("Weight doesn't fit in u64")]
+    WeightOverflow,
+}
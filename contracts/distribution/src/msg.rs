@@ -0,0 +1,67 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Int128, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    // only proxies registered there can withdraw and move weight
+    pub membership: String,
+}
+
+#[cw_serde]
+pub enum ExecMsg {
+    // shares the attached funds between proxies by weight
+    Distribute {},
+    // sent by a proxy: pays out its share so far, then moves its weight by diff
+    // weight has to be what the proxy has here, unless it has nothing registered yet
+    Withdraw { weight: u64, diff: Int128 },
+    Receive(Cw20ReceiveMsg),
+}
+
+// messages embedded in Cw20ReceiveMsg::msg
+#[cw_serde]
+pub enum Cw20HookMsg {
+    Distribute {},
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(WeightResponse)]
+    TotalWeight {},
+    #[returns(WeightResponse)]
+    Weight { proxy: String },
+    // funds received while nobody had any weight, shared with the next distribution
+    // cw20 tokens are asked for as "cw20:" + token address
+    #[returns(UndistributedResponse)]
+    Undistributed { denom: String },
+    // what the next withdraw of the proxy pays out, before its weight changes
+    #[returns(WithdrawableResponse)]
+    Withdrawable { proxy: String, denom: String },
+}
+
+#[cw_serde]
+pub struct WeightResponse {
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub struct UndistributedResponse {
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct WithdrawableResponse {
+    pub amount: Uint128,
+}
+
+// local copy of the membership contract's query, to check who is a proxy
+#[cw_serde]
+pub enum MembershipQueryMsg {
+    IsMember { addr: String },
+}
+
+#[cw_serde]
+pub struct IsMemberResponse {
+    pub is_member: bool,
+}
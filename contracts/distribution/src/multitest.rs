@@ -0,0 +1,146 @@
+use anyhow::Result as AnyResult;
+use common::msg::WithdrawResponse;
+use common::multitest::App;
+use cosmwasm_std::{from_json, Addr, Coin, Int128};
+use cw_multi_test::{ContractWrapper, Executor};
+
+use crate::msg::{
+    ExecMsg, InstantiateMsg, QueryMsg, UndistributedResponse, WeightResponse, WithdrawableResponse,
+};
+use crate::{execute, instantiate, query};
+
+pub mod membership_mock;
+#[cfg(test)]
+mod test;
+
+#[derive(Clone, Copy, Debug)]
+pub struct CodeId(u64);
+
+impl CodeId {
+    pub fn store_code(app: &mut App) -> Self {
+        let contract = ContractWrapper::new(execute, instantiate, query);
+
+        CodeId(app.store_code(Box::new(contract)))
+    }
+
+    #[track_caller]
+    pub fn instantiate(
+        self,
+        app: &mut App,
+        sender: &str,
+        membership: &Addr,
+        label: &str,
+    ) -> AnyResult<Contract> {
+        Contract::instantiate(app, self, sender, membership, label)
+    }
+}
+
+impl From<CodeId> for u64 {
+    fn from(value: CodeId) -> Self {
+        value.0
+    }
+}
+
+#[derive(Debug)]
+pub struct Contract(Addr);
+
+impl Contract {
+    pub fn from_addr(addr: Addr) -> Self {
+        Self(addr)
+    }
+
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+
+    #[track_caller]
+    pub fn instantiate(
+        app: &mut App,
+        code_id: CodeId,
+        sender: &str,
+        membership: &Addr,
+        label: &str,
+    ) -> AnyResult<Self> {
+        app.instantiate_contract(
+            code_id.0,
+            Addr::unchecked(sender),
+            &InstantiateMsg {
+                membership: membership.to_string(),
+            },
+            &[],
+            label,
+            None,
+        )
+        .map(Self)
+    }
+
+    #[track_caller]
+    pub fn distribute(&self, app: &mut App, sender: &str, funds: &[Coin]) -> AnyResult<()> {
+        app.execute_contract(
+            Addr::unchecked(sender),
+            self.0.clone(),
+            &ExecMsg::Distribute {},
+            funds,
+        )?;
+        Ok(())
+    }
+
+    // returns what the contract reported as paid out
+    #[track_caller]
+    pub fn withdraw(
+        &self,
+        app: &mut App,
+        sender: &str,
+        weight: u64,
+        diff: i128,
+    ) -> AnyResult<Vec<WithdrawResponse>> {
+        let resp = app.execute_contract(
+            Addr::unchecked(sender),
+            self.0.clone(),
+            &ExecMsg::Withdraw {
+                weight,
+                diff: Int128::new(diff),
+            },
+            &[],
+        )?;
+        Ok(from_json(resp.data.unwrap_or_default())?)
+    }
+
+    pub fn total_weight(&self, app: &App) -> AnyResult<u64> {
+        let resp: WeightResponse = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::TotalWeight {})?;
+        Ok(resp.weight)
+    }
+
+    pub fn weight(&self, app: &App, proxy: &str) -> AnyResult<u64> {
+        let resp: WeightResponse = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Weight {
+                proxy: proxy.to_owned(),
+            },
+        )?;
+        Ok(resp.weight)
+    }
+
+    pub fn withdrawable(&self, app: &App, proxy: &str, denom: &str) -> AnyResult<u128> {
+        let resp: WithdrawableResponse = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Withdrawable {
+                proxy: proxy.to_owned(),
+                denom: denom.to_owned(),
+            },
+        )?;
+        Ok(resp.amount.u128())
+    }
+
+    pub fn undistributed(&self, app: &App, denom: &str) -> AnyResult<u128> {
+        let resp: UndistributedResponse = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Undistributed {
+                denom: denom.to_owned(),
+            },
+        )?;
+        Ok(resp.amount.u128())
+    }
+}
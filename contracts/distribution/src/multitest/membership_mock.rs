@@ -0,0 +1,78 @@
+use anyhow::Result as AnyResult;
+use common::multitest::App;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw_multi_test::{ContractWrapper, Executor};
+use cw_storage_plus::Map;
+
+use crate::msg::{IsMemberResponse, MembershipQueryMsg};
+
+// stand-in for the membership contract, answers IsMember for whatever was added to it
+
+#[cw_serde]
+pub enum ExecMsg {
+    AddMember { addr: String },
+}
+
+const MEMBERS: Map<&str, Empty> = Map::new("members");
+
+fn instantiate(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: ExecMsg) -> StdResult<Response> {
+    match msg {
+        ExecMsg::AddMember { addr } => {
+            MEMBERS.save(deps.storage, &addr, &Empty {})?;
+            Ok(Response::new())
+        }
+    }
+}
+
+fn query(deps: Deps, _env: Env, msg: MembershipQueryMsg) -> StdResult<Binary> {
+    match msg {
+        MembershipQueryMsg::IsMember { addr } => to_json_binary(&IsMemberResponse {
+            is_member: MEMBERS.has(deps.storage, &addr),
+        }),
+    }
+}
+
+#[derive(Debug)]
+pub struct MembershipMock(Addr);
+
+impl MembershipMock {
+    #[track_caller]
+    pub fn instantiate(app: &mut App) -> AnyResult<Self> {
+        let contract = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(contract));
+
+        app.instantiate_contract(
+            code_id,
+            Addr::unchecked("membership owner"),
+            &Empty {},
+            &[],
+            "Membership mock",
+            None,
+        )
+        .map(Self)
+    }
+
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+
+    #[track_caller]
+    pub fn add_member(&self, app: &mut App, addr: &str) -> AnyResult<()> {
+        app.execute_contract(
+            Addr::unchecked("membership owner"),
+            self.0.clone(),
+            &ExecMsg::AddMember {
+                addr: addr.to_owned(),
+            },
+            &[],
+        )?;
+        Ok(())
+    }
+}
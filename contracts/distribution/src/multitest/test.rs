@@ -0,0 +1,201 @@
+use common::msg::WithdrawResponse;
+use common::multitest::{mock_app, App};
+use cosmwasm_std::{coins, Addr, Uint128};
+
+use crate::error::ContractError;
+use crate::multitest::membership_mock::MembershipMock;
+use crate::multitest::{CodeId, Contract};
+
+const DENOM: &str = "ORAI";
+const DONOR: &str = "donor";
+
+fn app_with_donor(amount: u128) -> App {
+    let mut app = mock_app();
+    app.init_modules(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked(DONOR), coins(amount, DENOM))
+            .unwrap();
+    });
+    app
+}
+
+// a distribution whose membership knows the given proxies
+#[track_caller]
+fn instantiate_distribution(app: &mut App, proxies: &[&str]) -> Contract {
+    let membership = MembershipMock::instantiate(app).unwrap();
+    for proxy in proxies {
+        membership.add_member(app, proxy).unwrap();
+    }
+    CodeId::store_code(app)
+        .instantiate(app, "owner", membership.addr(), "Distribution")
+        .unwrap()
+}
+
+#[test]
+fn shared_by_weight() {
+    let mut app = app_with_donor(300);
+    let distribution = instantiate_distribution(&mut app, &["proxy1", "proxy2"]);
+
+    distribution.withdraw(&mut app, "proxy1", 0, 1).unwrap();
+    distribution.withdraw(&mut app, "proxy2", 0, 2).unwrap();
+    assert_eq!(distribution.total_weight(&app).unwrap(), 3);
+
+    distribution
+        .distribute(&mut app, DONOR, &coins(300, DENOM))
+        .unwrap();
+
+    assert_eq!(
+        distribution.withdrawable(&app, "proxy2", DENOM).unwrap(),
+        200
+    );
+    let withdrawn = distribution.withdraw(&mut app, "proxy2", 2, 0).unwrap();
+    assert_eq!(distribution.withdrawable(&app, "proxy2", DENOM).unwrap(), 0);
+    assert_eq!(
+        withdrawn,
+        vec![WithdrawResponse {
+            amount: Uint128::new(200),
+            denom: DENOM.to_owned(),
+        }]
+    );
+    assert_eq!(
+        app.wrap()
+            .query_balance("proxy2", DENOM)
+            .unwrap()
+            .amount
+            .u128(),
+        200
+    );
+
+    // nothing new since the last withdraw
+    let withdrawn = distribution.withdraw(&mut app, "proxy2", 2, 0).unwrap();
+    assert!(withdrawn.is_empty());
+
+    let withdrawn = distribution.withdraw(&mut app, "proxy1", 1, -1).unwrap();
+    assert_eq!(withdrawn[0].amount.u128(), 100);
+    assert_eq!(distribution.weight(&app, "proxy1").unwrap(), 0);
+    assert_eq!(distribution.total_weight(&app).unwrap(), 2);
+}
+
+#[test]
+fn weight_change_applies_to_later_distributions() {
+    let mut app = app_with_donor(200);
+    let distribution = instantiate_distribution(&mut app, &["proxy1", "proxy2"]);
+
+    distribution.withdraw(&mut app, "proxy1", 0, 1).unwrap();
+    distribution.withdraw(&mut app, "proxy2", 0, 1).unwrap();
+    distribution
+        .distribute(&mut app, DONOR, &coins(100, DENOM))
+        .unwrap();
+
+    // proxy1 still gets its half of the first distribution
+    let withdrawn = distribution.withdraw(&mut app, "proxy1", 1, 2).unwrap();
+    assert_eq!(withdrawn[0].amount.u128(), 50);
+
+    distribution
+        .distribute(&mut app, DONOR, &coins(100, DENOM))
+        .unwrap();
+
+    let withdrawn = distribution.withdraw(&mut app, "proxy1", 3, 0).unwrap();
+    assert_eq!(withdrawn[0].amount.u128(), 75);
+    let withdrawn = distribution.withdraw(&mut app, "proxy2", 1, 0).unwrap();
+    assert_eq!(withdrawn[0].amount.u128(), 75);
+}
+
+#[test]
+fn undistributed_until_someone_has_weight() {
+    let mut app = app_with_donor(150);
+    let distribution = instantiate_distribution(&mut app, &["proxy1", "proxy2"]);
+
+    distribution
+        .distribute(&mut app, DONOR, &coins(100, DENOM))
+        .unwrap();
+    assert_eq!(distribution.undistributed(&app, DENOM).unwrap(), 100);
+
+    distribution.withdraw(&mut app, "proxy1", 0, 1).unwrap();
+    // the next distribution brings the leftovers along
+    distribution
+        .distribute(&mut app, DONOR, &coins(50, DENOM))
+        .unwrap();
+    assert_eq!(distribution.undistributed(&app, DENOM).unwrap(), 0);
+
+    let withdrawn = distribution.withdraw(&mut app, "proxy1", 1, 0).unwrap();
+    assert_eq!(withdrawn[0].amount.u128(), 150);
+}
+
+#[test]
+fn negative_weight() {
+    let mut app = mock_app();
+    let distribution = instantiate_distribution(&mut app, &["proxy1", "proxy2"]);
+
+    let err = distribution
+        .withdraw(&mut app, "proxy1", 1, -2)
+        .unwrap_err();
+    assert_eq!(ContractError::NegativeWeight, err.downcast().unwrap());
+}
+
+#[test]
+fn weight_overflow() {
+    let mut app = mock_app();
+    let distribution = instantiate_distribution(&mut app, &["proxy1", "proxy2"]);
+
+    let err = distribution
+        .withdraw(&mut app, "proxy1", u64::MAX, 1)
+        .unwrap_err();
+    assert_eq!(ContractError::WeightOverflow, err.downcast().unwrap());
+
+    let err = distribution
+        .withdraw(&mut app, "proxy1", u64::MAX, i128::MAX)
+        .unwrap_err();
+    assert_eq!(ContractError::WeightOverflow, err.downcast().unwrap());
+
+    distribution
+        .withdraw(&mut app, "proxy1", u64::MAX, 0)
+        .unwrap();
+    assert_eq!(distribution.weight(&app, "proxy1").unwrap(), u64::MAX);
+
+    // the total can't take another proxy on top
+    let err = distribution.withdraw(&mut app, "proxy2", 0, 1).unwrap_err();
+    assert_eq!(ContractError::WeightOverflow, err.downcast().unwrap());
+    assert_eq!(distribution.total_weight(&app).unwrap(), u64::MAX);
+}
+
+#[test]
+fn only_members_withdraw() {
+    let mut app = mock_app();
+    let distribution = instantiate_distribution(&mut app, &["proxy1"]);
+
+    let err = distribution
+        .withdraw(&mut app, "stranger", 0, 1_000_000)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+    assert_eq!(distribution.total_weight(&app).unwrap(), 0);
+
+    distribution.withdraw(&mut app, "proxy1", 0, 1).unwrap();
+    assert_eq!(distribution.total_weight(&app).unwrap(), 1);
+}
+
+#[test]
+fn reported_weight_must_match() {
+    let mut app = mock_app();
+    let distribution = instantiate_distribution(&mut app, &["proxy1"]);
+
+    // the first withdraw registers whatever weight the proxy has
+    distribution.withdraw(&mut app, "proxy1", 5, 0).unwrap();
+    assert_eq!(distribution.weight(&app, "proxy1").unwrap(), 5);
+
+    let err = distribution
+        .withdraw(&mut app, "proxy1", 1000, 0)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::WeightMismatch {
+            reported: 1000,
+            registered: 5
+        },
+        err.downcast().unwrap()
+    );
+    assert_eq!(distribution.total_weight(&app).unwrap(), 5);
+
+    distribution.withdraw(&mut app, "proxy1", 5, 2).unwrap();
+    assert_eq!(distribution.weight(&app, "proxy1").unwrap(), 7);
+}
@@ -0,0 +1,15 @@
+use cosmwasm_std::{Addr, Decimal256, Uint128};
+use cw_storage_plus::{Item, Map};
+
+// assets are keyed by native denom, cw20 tokens by "cw20:" + token address
+
+pub const CW20_PREFIX: &str = "cw20:";
+
+pub const MEMBERSHIP: Item<Addr> = Item::new("membership");
+pub const TOTAL_WEIGHT: Item<u64> = Item::new("total_weight");
+pub const WEIGHTS: Map<&Addr, u64> = Map::new("weights");
+// funds per weight unit distributed so far, only ever grows
+pub const POINTS_PER_WEIGHT: Map<&str, Decimal256> = Map::new("points_per_weight");
+// POINTS_PER_WEIGHT at the proxy's last withdraw, everything above it is owed to the proxy
+pub const PAID_POINTS: Map<(&Addr, &str), Decimal256> = Map::new("paid_points");
+pub const UNDISTRIBUTED: Map<&str, Uint128> = Map::new("undistributed");
@@ -1,9 +1,11 @@
 use std::vec;
 
 use cosmwasm_std::{
-    ensure, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
-    StdResult, SubMsg, WasmMsg,
+    ensure, to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, QuerierWrapper,
+    Reply, Response, StdResult, SubMsg, Uint128, WasmMsg,
 };
+use cw_ownable::Ownership;
+use cw_utils::Duration;
 
 mod exec;
 mod query;
@@ -11,18 +13,20 @@ mod reply;
 
 use crate::error::ContractError;
 use crate::msg::{ExecMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, AWAITING_INITIAL_RESPS, CONFIG};
+use crate::state::{Config, CONFIG, INITIAL_MEMBERS};
 
 // Get instantiate msg of proxy contract
-use proxy::msg::InstantiateMsg as ProxyInstantiateMsg;
+use distribution::msg::InstantiateMsg as DistributionInstantiateMsg;
+use proxy::msg::{DecayFunds, InstantiateMsg as ProxyInstantiateMsg};
 
 const INITIAL_PROXY_INSTANTIATION_REPLY_ID: u64 = 1;
 const PROXY_INSTANTIATION_REPLY_ID: u64 = 2;
+const DISTRIBUTION_INSTANTIATION_REPLY_ID: u64 = 3;
 
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
+    _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     ensure!(
@@ -41,35 +45,89 @@ pub fn instantiate(
         direct_part: msg.direct_part,
         halftime: msg.halftime,
         proxy_code_id: msg.proxy_code_id,
-        distribution_contract: Addr::unchecked(""), // cause we don't have distribution contract yet
+        distribution_contract: Addr::unchecked(""), // set once the distribution contract is instantiated
         minimal_acceptances: msg.minimal_acceptance,
     };
 
     CONFIG.save(deps.storage, &config)?;
 
-    let proxy_instantiate_msgs: Vec<_> = msg
+    // validate address
+    let initial_members = msg
         .initial_members
+        .iter()
+        .map(|member| deps.api.addr_validate(member))
+        .collect::<StdResult<Vec<_>>>()?;
+    INITIAL_MEMBERS.save(deps.storage, &initial_members)?;
+
+    // the proxies need the distribution contract's address, so they are only instantiated in its reply
+    let distribution_init_msg = DistributionInstantiateMsg {
+        membership: env.contract.address.to_string(),
+    };
+    let msg = WasmMsg::Instantiate {
+        admin: Some(env.contract.address.to_string()),
+        code_id: msg.distribution_code_id,
+        msg: to_json_binary(&distribution_init_msg)?,
+        funds: vec![],
+        label: "Distribution".to_owned(),
+    };
+    let msg = SubMsg::reply_on_success(msg, DISTRIBUTION_INSTANTIATION_REPLY_ID);
+
+    Ok(Response::new().add_submessage(msg))
+}
+
+// one proxy per initial member, all sharing the distribution contract
+fn initial_proxy_msgs(
+    env: &Env,
+    config: &Config,
+    initial_members: Vec<Addr>,
+) -> Result<Vec<SubMsg>, ContractError> {
+    initial_members
         .into_iter()
-        .map(|member| -> Result<_, ContractError> {
+        .enumerate()
+        .map(|(member_id, addr)| -> Result<_, ContractError> {
             // can use ? operator here because return Result<Response, ContractError>
 
-            // validate address
-            let addr = deps.api.addr_validate(&member)?;
-
             let proxy_init_msg = ProxyInstantiateMsg {
                 owner: addr.to_string(),
-                weight: msg.starting_weight,
-                denom: msg.denom.clone(),
-                direct_part: msg.direct_part,
-                distribution_contract: "".to_owned(), // cause we don't have distribution contract yet
+                weight: config.starting_weight,
+                accepted_denoms: vec![config.denom.clone()],
+                direct_part: config.direct_part,
+                distribution_contracts: vec![(
+                    config.distribution_contract.to_string(),
+                    Decimal::one(),
+                )],
                 membership_contract: env.contract.address.to_string(),
-                halftime: msg.halftime,
+                halftime: Duration::Time(config.halftime),
+                cw20_address: None,
+                min_donation: Uint128::zero(),
+                direct_beneficiary: None,
+                goal: None,
+                protocol_fee: Decimal::zero(),
+                treasury: None,
+                tiers: vec![],
+                bucket_width: None,
+                matching_ratio: None,
+                decay: None,
+                referral_bps: 0,
+                burn_part: Decimal::zero(),
+                withdraw_cooldown: 0,
+                auto_withdraw_threshold: None,
+                keeper_fee_bps: 0,
+                max_weight: None,
+                decay_funds: DecayFunds::KeepOnProxy,
+                approvers: vec![],
+                threshold: 0,
+                proposal_duration: None,
+                skip_contract_check: false,
+                metadata: None,
+                member_id: Some(member_id as u64),
+                instantiated_by: Some(env.contract.address.to_string()),
             };
 
             // blockchain will instantiate proxy contract with below information
             let msg = WasmMsg::Instantiate {
                 admin: Some(env.contract.address.to_string()),
-                code_id: msg.proxy_code_id,
+                code_id: config.proxy_code_id,
                 // this one will go to entry point of proxy contract
                 msg: to_json_binary(&proxy_init_msg)?, // to_binary deprecated
                 funds: vec![],
@@ -81,15 +139,7 @@ pub fn instantiate(
 
             Ok(msg)
         })
-        .collect::<Result<_, _>>()?;
-
-    AWAITING_INITIAL_RESPS.save(deps.storage, &(proxy_instantiate_msgs.len() as _))?;
-
-    let resp = Response::new().add_submessages(proxy_instantiate_msgs);
-    // these submessages provide reply to reply entry point of membership contract with INITIAL_PROXY_INSTANTIATION_REPLY_ID
-    // and membership contract will have reply handler for those replies
-
-    Ok(resp)
+        .collect()
 }
 
 pub fn execute(
@@ -105,16 +155,28 @@ pub fn execute(
 
 pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
     match reply.id {
+        DISTRIBUTION_INSTANTIATION_REPLY_ID => {
+            reply::distribution_instantiated(deps, env, reply.result.into_result())
+        }
         INITIAL_PROXY_INSTANTIATION_REPLY_ID => {
-            reply::initial_proxy_instantiated(deps, reply.result.into_result())
+            reply::initial_proxy_instantiated(deps, env, reply.result.into_result())
+        }
+        PROXY_INSTANTIATION_REPLY_ID => {
+            reply::proxy_instantiated(deps, env, reply.result.into_result())
         }
-        PROXY_INSTANTIATION_REPLY_ID => reply::proxy_instantiated(deps, reply.result.into_result()),
         id => Err(ContractError::UnrecognizedReplyId(id)),
     }
 }
 
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::IsMember { addr } => to_json_binary(&query::is_member(deps, addr)?),
     }
 }
+
+// None once the proxy owner renounced
+fn proxy_owner(querier: &QuerierWrapper, proxy: &Addr) -> StdResult<Option<Addr>> {
+    let ownership: Ownership<Addr> =
+        querier.query_wasm_smart(proxy, &proxy::msg::QueryMsg::Ownership {})?;
+    Ok(ownership.owner)
+}
@@ -1,15 +1,16 @@
 use cosmwasm_std::{
-    ensure, to_json_binary, Addr, DepsMut, Empty, Env, MessageInfo, Order, Response, SubMsg,
-    WasmMsg,
+    ensure, to_json_binary, Decimal, DepsMut, Empty, Env, MessageInfo, Order, Response, SubMsg,
+    Uint128, WasmMsg,
 };
+use cw_utils::Duration;
 
 use crate::{
     contract::PROXY_INSTANTIATION_REPLY_ID,
     error::ContractError,
-    state::{CONFIG, MEMBERS, PROPOSALS, VOTES},
+    state::{CONFIG, MEMBERS, NEXT_MEMBER_ID, PROPOSALS, VOTES},
 };
 
-use proxy::msg::InstantiateMsg as ProxyInstantiateMsg;
+use proxy::msg::{DecayFunds, InstantiateMsg as ProxyInstantiateMsg};
 
 pub fn propose_member(
     deps: DepsMut,
@@ -30,8 +31,8 @@ pub fn propose_member(
     // just think it like a normal for loop
     for member in MEMBERS.range(deps.storage, None, None, Order::Ascending) {
         let (member, _) = member?; // get proxy contract address
-        let owner = proxy::state::OWNER.query(&deps.querier, member)?; // get the owner of the proxy contract
-        ensure!(owner != addr, ContractError::AlreadyAMember);
+        let owner = super::proxy_owner(&deps.querier, &member)?; // get the owner of the proxy contract
+        ensure!(owner != Some(addr.clone()), ContractError::AlreadyAMember);
     }
 
     // check if the sender has already voted for this new member
@@ -69,14 +70,42 @@ pub fn propose_member(
     // what happen if not delete?
     PROPOSALS.remove(deps.storage, &addr);
 
+    // the proxy echoes it back in its instantiate data
+    let member_id = NEXT_MEMBER_ID.load(deps.storage)?;
+    NEXT_MEMBER_ID.save(deps.storage, &(member_id + 1))?;
+
     let proxy_init_msg = ProxyInstantiateMsg {
         owner: addr.to_string(),
         weight: config.starting_weight,
-        denom: config.denom,
+        accepted_denoms: vec![config.denom],
         direct_part: config.direct_part,
-        distribution_contract: config.distribution_contract.into_string(),
+        distribution_contracts: vec![(config.distribution_contract.into_string(), Decimal::one())],
         membership_contract: env.contract.address.to_string(),
-        halftime: config.halftime,
+        halftime: Duration::Time(config.halftime),
+        cw20_address: None,
+        min_donation: Uint128::zero(),
+        direct_beneficiary: None,
+        goal: None,
+        protocol_fee: Decimal::zero(),
+        treasury: None,
+        tiers: vec![],
+        bucket_width: None,
+        matching_ratio: None,
+        decay: None,
+        referral_bps: 0,
+        burn_part: Decimal::zero(),
+        withdraw_cooldown: 0,
+        auto_withdraw_threshold: None,
+        keeper_fee_bps: 0,
+        max_weight: None,
+        decay_funds: DecayFunds::KeepOnProxy,
+        approvers: vec![],
+        threshold: 0,
+        proposal_duration: None,
+        skip_contract_check: false,
+        metadata: None,
+        member_id: Some(member_id),
+        instantiated_by: Some(env.contract.address.to_string()),
     };
 
     let proxy_init_msg = WasmMsg::Instantiate {
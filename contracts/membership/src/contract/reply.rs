@@ -1,32 +1,66 @@
 use common::msg::ProposeMemberData;
 use cosmwasm_std::{
-    to_json_binary, Addr, DepsMut, Empty, Order, Response, StdError, StdResult, SubMsgResponse,
+    ensure, from_json, to_json_binary, Addr, Binary, DepsMut, Empty, Env, Order, Response,
+    StdError, StdResult, SubMsgResponse,
 };
 use cw_utils::parse_instantiate_response_data;
 
 use crate::{
     error::ContractError,
     msg::InstantiationData,
-    state::{AWAITING_INITIAL_RESPS, MEMBERS},
+    state::{AWAITING_INITIAL_RESPS, CONFIG, INITIAL_MEMBERS, MEMBERS, MEMBER_IDS, NEXT_MEMBER_ID},
 };
+use proxy::msg::ProxyInstantiateResponse;
 
-// summarize: we will have the proxy contract address from the reply and store it into MEMBERS
-pub fn initial_proxy_instantiated(
+// the distribution contract is there, now the initial proxies can point to it
+pub fn distribution_instantiated(
     deps: DepsMut,
+    env: Env,
     reply: Result<SubMsgResponse, String>,
 ) -> Result<Response, ContractError> {
     let response = reply.map_err(StdError::generic_err)?;
-    let data = response.data.ok_or(ContractError::MissingData)?; // CosmWasm executor uses this data field to add information of created proxy contract
+    let data = response_data(response).ok_or(ContractError::MissingData)?;
     let response = parse_instantiate_response_data(&data)?;
-    let proxy_addr = Addr::unchecked(response.contract_address);
-    MEMBERS.save(deps.storage, &proxy_addr, &cosmwasm_std::Empty {})?;
+    let distribution = deps.api.addr_validate(&response.contract_address)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.distribution_contract = distribution.clone();
+    CONFIG.save(deps.storage, &config)?;
+
+    let initial_members = INITIAL_MEMBERS.load(deps.storage)?;
+    INITIAL_MEMBERS.remove(deps.storage);
+    let proxy_instantiate_msgs = super::initial_proxy_msgs(&env, &config, initial_members)?;
+
+    AWAITING_INITIAL_RESPS.save(deps.storage, &(proxy_instantiate_msgs.len() as _))?;
+    NEXT_MEMBER_ID.save(deps.storage, &(proxy_instantiate_msgs.len() as _))?;
+
+    let resp = Response::new()
+        .add_submessages(proxy_instantiate_msgs)
+        .add_attribute("distribution_addr", distribution.as_str());
+    // these submessages provide reply to reply entry point of membership contract with INITIAL_PROXY_INSTANTIATION_REPLY_ID
+    // and membership contract will have reply handler for those replies
+
+    Ok(resp)
+}
+
+// summarize: we will have the proxy contract address from the reply and store it into MEMBERS
+pub fn initial_proxy_instantiated(
+    mut deps: DepsMut,
+    env: Env,
+    reply: Result<SubMsgResponse, String>,
+) -> Result<Response, ContractError> {
+    let response = reply.map_err(StdError::generic_err)?;
+    let data = response_data(response).ok_or(ContractError::MissingData)?; // CosmWasm executor uses this response to add information of created proxy contract
+    let (proxy_addr, member_id) = register_proxy(deps.branch(), &env, &data)?;
 
     // means we have one less reply to wait for
     let awaiting = AWAITING_INITIAL_RESPS.load(deps.storage)? - 1;
     if awaiting > 0 {
         AWAITING_INITIAL_RESPS.save(deps.storage, &awaiting)?; // github repo saved 0 which is wrong and be fixed in the next commit
 
-        let resp = Response::new().add_attribute("proxy_addr", proxy_addr);
+        let resp = Response::new()
+            .add_attribute("proxy_addr", proxy_addr)
+            .add_attribute("member_id", member_id_attr(member_id));
 
         return Ok(resp);
     }
@@ -38,9 +72,11 @@ pub fn initial_proxy_instantiated(
         .map(|member| -> StdResult<_> {
             let (member, _) = member?;
             // there is no way to update other contract's state but we can query it from another contract
-            let owner = proxy::state::OWNER.query(&deps.querier, member.clone())?;
+            // empty for a proxy whose owner renounced
+            let owner = super::proxy_owner(&deps.querier, &member)?;
             let data = ProposeMemberData {
-                owner_addr: owner.into(),
+                owner_addr: owner.map(String::from).unwrap_or_default(),
+                member_id: MEMBER_IDS.may_load(deps.storage, &member)?,
                 proxy_addr: member.into(),
             };
             Ok(data)
@@ -50,32 +86,80 @@ pub fn initial_proxy_instantiated(
     let data = InstantiationData { members };
     let resp = Response::new()
         .add_attribute("proxy addr", proxy_addr.as_str())
+        .add_attribute("member_id", member_id_attr(member_id))
         .set_data(to_json_binary(&data)?);
 
     Ok(resp)
 }
 
 pub fn proxy_instantiated(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: Env,
     reply: Result<SubMsgResponse, String>,
 ) -> Result<Response, ContractError> {
     let response = reply.map_err(StdError::generic_err)?;
-    let data = response.data.ok_or(ContractError::MissingData)?;
-    let response = parse_instantiate_response_data(&data)?;
-    let addr = Addr::unchecked(response.contract_address); // proxy contract address
-
-    let owner = proxy::state::OWNER.query(&deps.querier, addr.clone())?;
+    let data = response_data(response).ok_or(ContractError::MissingData)?;
+    let (addr, member_id) = register_proxy(deps.branch(), &env, &data)?; // proxy contract address
 
-    MEMBERS.save(deps.storage, &addr, &Empty {})?;
+    // just instantiated, the owner can't have renounced yet
+    let owner = super::proxy_owner(&deps.querier, &addr)?
+        .map(String::from)
+        .unwrap_or_default();
 
     let data = ProposeMemberData {
-        owner_addr: owner.into(),
+        owner_addr: owner,
         proxy_addr: addr.to_string(),
+        member_id,
     };
 
     let resp = Response::new()
         .add_attribute("proxy_addr", addr.as_str())
+        .add_attribute("member_id", member_id_attr(member_id))
         .set_data(to_json_binary(&data)?);
 
     Ok(resp)
 }
+
+// records the new proxy as a member, with the id it echoed back in its instantiate data
+fn register_proxy(
+    deps: DepsMut,
+    env: &Env,
+    data: &Binary,
+) -> Result<(Addr, Option<u64>), ContractError> {
+    let response = parse_instantiate_response_data(data)?;
+    let proxy_addr = Addr::unchecked(response.contract_address);
+
+    // older proxy codes set no data
+    let proxy_data: Option<ProxyInstantiateResponse> = response.data.map(from_json).transpose()?;
+    let member_id = match proxy_data {
+        Some(proxy_data) => {
+            ensure!(
+                proxy_data.instantiated_by.as_ref() == Some(&env.contract.address),
+                ContractError::UnexpectedInstantiator {
+                    proxy: proxy_addr.to_string()
+                }
+            );
+            proxy_data.member_id
+        }
+        None => None,
+    };
+
+    MEMBERS.save(deps.storage, &proxy_addr, &Empty {})?;
+    if let Some(member_id) = member_id {
+        MEMBER_IDS.save(deps.storage, &proxy_addr, &member_id)?;
+    }
+    Ok((proxy_addr, member_id))
+}
+
+fn member_id_attr(member_id: Option<u64>) -> String {
+    member_id.map(|id| id.to_string()).unwrap_or_default()
+}
+
+// since CosmWasm 2 the instantiate response is in msg_responses, data is deprecated
+fn response_data(response: SubMsgResponse) -> Option<Binary> {
+    response
+        .msg_responses
+        .into_iter()
+        .next()
+        .map(|msg| msg.value)
+}
@@ -20,6 +20,8 @@ pub enum ContractError {
     UnrecognizedReplyId(u64),
     #[error("Already voted on this proposal")]
     AlreadyVoted,
+    #[error("Proxy {proxy} was not instantiated by this contract")]
+    UnexpectedInstantiator { proxy: String },
     #[error("Cannot propose a member")]
     AlreadyAMember,
 }
@@ -1,7 +1,8 @@
 use anyhow::Result as AnyResult;
 use common::msg::ProposeMemberData;
+use common::multitest::App;
 use cosmwasm_std::{from_json, to_json_binary, Addr, Decimal, WasmMsg};
-use cw_multi_test::{App, ContractWrapper, Executor};
+use cw_multi_test::{ContractWrapper, Executor};
 use cw_utils::{parse_execute_response_data, parse_instantiate_response_data};
 
 use crate::{
@@ -22,6 +23,7 @@ impl CodeId {
     }
 
     #[track_caller]
+    #[allow(clippy::too_many_arguments)]
     pub fn instantiate(
         self,
         app: &mut App,
@@ -32,7 +34,7 @@ impl CodeId {
         halftime: u64,
         minimal_acceptance: u64,
         proxy_code_id: proxy::multitest::CodeId,
-        //distribution_code_id: distribution::multitest::CodeId,
+        distribution_code_id: distribution::multitest::CodeId,
         initial_members: &[&str],
         label: &str,
     ) -> AnyResult<(Contract, InstantiationData)> {
@@ -46,6 +48,7 @@ impl CodeId {
             halftime,
             minimal_acceptance,
             proxy_code_id,
+            distribution_code_id,
             initial_members,
             label,
         )
@@ -67,6 +70,7 @@ impl Contract {
     }
 
     #[track_caller]
+    #[allow(clippy::too_many_arguments)]
     pub fn instantiate(
         app: &mut App,
         code_id: CodeId,
@@ -78,7 +82,7 @@ impl Contract {
         halftime: u64,
         minimal_acceptance: u64,
         proxy_code_id: proxy::multitest::CodeId,
-        //distribution_code_id: distribution::multitest::CodeId,
+        distribution_code_id: distribution::multitest::CodeId,
         initial_members: &[&str],
         label: &str,
     ) -> AnyResult<(Self, InstantiationData)> {
@@ -88,7 +92,7 @@ impl Contract {
             direct_part,
             halftime,
             proxy_code_id: proxy_code_id.into(), // can use this because From<CodeId> for u64
-            distribution_code_id: 0,             // cause we don't have distribution contract yet
+            distribution_code_id: distribution_code_id.into(),
             minimal_acceptance,
             initial_members: initial_members // need to find out this
                 .iter()
@@ -112,7 +116,7 @@ impl Contract {
         let data = parse_instantiate_response_data(resp.data.unwrap_or_default().as_slice())?;
 
         let contract = Self(Addr::unchecked(data.contract_address));
-        let data = from_json(&data.data.unwrap_or_default())?;
+        let data = from_json(data.data.unwrap_or_default())?;
 
         Ok((contract, data))
     }
@@ -137,7 +141,7 @@ impl Contract {
         // this is my code
         let data = parse_execute_response_data(resp.data.unwrap_or_default().as_slice())?;
 
-        let data = from_json(&data.data.unwrap_or_default())?;
+        let data = from_json(data.data.unwrap_or_default())?;
 
         // this is code from repo
         // resp.data
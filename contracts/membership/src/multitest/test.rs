@@ -1,17 +1,19 @@
 use std::collections::HashMap;
 
+use common::multitest::mock_app;
 use cosmwasm_std::Addr;
 use cosmwasm_std::Decimal;
-use cw_multi_test::App;
 
 use crate::multitest::CodeId as MembershipId;
+use distribution::multitest::CodeId as DistributionId;
 use proxy::multitest::CodeId as ProxyId;
 
+use proxy::msg::ProposedMember;
 use proxy::multitest::Contract as ProxyContract;
 
 #[test]
 pub fn adding_member() {
-    let mut app = App::default(); // blockchain
+    let mut app = mock_app(); // blockchain
 
     let denom = "ORAI";
 
@@ -21,6 +23,7 @@ pub fn adding_member() {
 
     // deploy code to blockchain => get code_id
     let proxy_code_id = ProxyId::store_code(&mut app);
+    let distribution_code_id = DistributionId::store_code(&mut app);
     let membership_code_id = MembershipId::store_code(&mut app);
 
     // the reason to have the contract instantiate fn in the CodeId Wrapper
@@ -35,12 +38,22 @@ pub fn adding_member() {
             3600 * 24 * 30, // 30 days => update weight
             2,
             proxy_code_id,
+            distribution_code_id,
             &initial_members,
             "Membership",
         )
         .unwrap(); // so remember if can not use ? operator, use unwrap() instead
                    // but priority is ? operator
 
+    // ids assigned by membership, echoed back in each proxy's instantiate data
+    let mut member_ids: Vec<_> = instantiation_data
+        .members
+        .iter()
+        .map(|member| member.member_id)
+        .collect();
+    member_ids.sort();
+    assert_eq!(member_ids, vec![Some(0), Some(1)]);
+
     let proxies: HashMap<_, _> = instantiation_data
         .members
         .into_iter()
@@ -55,6 +68,24 @@ pub fn adding_member() {
 
     // 2 initial proxy contracts created
     assert_eq!(proxies.len(), 2);
+    // both withdraw from the distribution contract the membership instantiated
+    let distribution = proxies[initial_members[0]]
+        .config(&app)
+        .unwrap()
+        .distribution_contracts;
+    assert_eq!(distribution.len(), 1);
+    assert_eq!(
+        proxies[initial_members[1]]
+            .config(&app)
+            .unwrap()
+            .distribution_contracts,
+        distribution
+    );
+    let info = app
+        .wrap()
+        .query_wasm_contract_info(&distribution[0].0)
+        .unwrap();
+    assert_eq!(info.code_id, u64::from(distribution_code_id));
     assert!(
         membership_contract
             .is_member(&app, proxies[initial_members[0]].addr().as_str()) // initial_members[0] is key => return the Proxy Wrapper then call .addr() to get the address
@@ -81,6 +112,25 @@ pub fn adding_member() {
     let data = data.unwrap();
 
     assert_eq!(data.owner_addr, candidate);
+    assert_eq!(data.member_id, Some(2));
+
+    // only the proposal that got the proxy created records it
+    assert_eq!(
+        proxies[initial_members[0]].proposed_members(&app).unwrap(),
+        vec![]
+    );
+    assert_eq!(
+        proxies[initial_members[1]].proposed_members(&app).unwrap(),
+        vec![ProposedMember {
+            candidate: Addr::unchecked(candidate),
+            proxy: Addr::unchecked(&data.proxy_addr),
+        }]
+    );
+    let candidate_proxy = ProxyContract::from_addr(Addr::unchecked(&data.proxy_addr));
+    assert_eq!(
+        candidate_proxy.owner(&app).unwrap(),
+        Some(Addr::unchecked(candidate))
+    );
 
     assert!(
         membership_contract
@@ -21,4 +21,9 @@ pub const PROPOSALS: Map<&Addr, u64> = Map::new("proposals");
 pub const VOTES: Map<(&Addr, &Addr), Empty> = Map::new("votes");
 // member in system, candidate want to join group
 
+// instantiate msg's members, waiting for the distribution contract before their proxies are created
+pub const INITIAL_MEMBERS: Item<Vec<Addr>> = Item::new("initial_members");
 pub const AWAITING_INITIAL_RESPS: Item<u64> = Item::new("awaiting_initial_resps");
+
+pub const NEXT_MEMBER_ID: Item<u64> = Item::new("next_member_id");
+pub const MEMBER_IDS: Map<&Addr, u64> = Map::new("member_ids"); // proxy contract, its member id
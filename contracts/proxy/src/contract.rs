@@ -1,50 +1,547 @@
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    ensure, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+    ensure, to_json_binary, Addr, Binary, BlockInfo, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, QuerierWrapper, Reply, Response, StdResult, Storage, Timestamp, Uint128,
 };
 
+use cw2::{get_contract_version, set_contract_version};
+use cw_ownable::{get_ownership, initialize_owner, update_ownership, Action};
+use cw_storage_plus::{Index, Item, Map};
+use cw_utils::{Duration, Expiration};
+use semver::Version;
+
 use crate::error::ContractError;
-use crate::msg::{ExecMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, CONFIG, DONATIONS, HALFTIME, LAST_UPDATED, OWNER, WEIGHT};
+use crate::msg::{
+    DecayFunds, ExecMsg, InstantiateMsg, ProxyInstantiateResponse, QueryMsg, SudoMsg,
+};
+use crate::state::{
+    CloseReason, Config, State, UpdatePolicy, CONFIG, DONORS, MATCHING_POOL, NEXT_DONATION_ID,
+    NEXT_WITHDRAWAL_ID, STATE, UNIQUE_DONORS, WEIGHT_HISTORY,
+};
 
 mod exec;
 mod query;
 mod reply;
+mod sudo;
 
 const WITHDRAW_REPLY_ID: u64 = 1;
 const PROPOSE_MEMBER_REPLY_ID: u64 = 2;
+const HOOK_REPLY_ID: u64 = 3;
+const DECAY_WITHDRAW_REPLY_ID: u64 = 4;
+const RECEIPT_REPLY_ID: u64 = 5;
+
+const DEFAULT_BUCKET_WIDTH: u64 = 86400;
+const DEFAULT_PROPOSAL_DURATION: u64 = 7 * 86400;
+
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    validate_parts(msg.direct_part, msg.protocol_fee, msg.burn_part)?;
     ensure!(
-        Decimal::zero() <= msg.direct_part && msg.direct_part <= Decimal::percent(100),
-        ContractError::InalidDirectPart
+        msg.protocol_fee.is_zero() || msg.treasury.is_some(),
+        ContractError::MissingTreasury
+    );
+
+    ensure!(
+        !msg.accepted_denoms.is_empty(),
+        ContractError::NoAcceptedDenoms
+    );
+    for (idx, denom) in msg.accepted_denoms.iter().enumerate() {
+        validate_denom(denom)?;
+        ensure!(
+            !msg.accepted_denoms[..idx].contains(denom),
+            ContractError::DuplicatedDenom(denom.clone())
+        );
+    }
+
+    let weights_sum: Decimal = msg
+        .distribution_contracts
+        .iter()
+        .map(|(_, weight)| weight)
+        .sum();
+    ensure!(
+        weights_sum == Decimal::one(),
+        ContractError::InvalidDistributionWeights
+    );
+    ensure!(
+        msg.tiers.windows(2).all(|pair| pair[0].0 < pair[1].0),
+        ContractError::UnsortedTiers
+    );
+
+    let bucket_width = msg.bucket_width.unwrap_or(DEFAULT_BUCKET_WIDTH);
+    ensure!(bucket_width > 0, ContractError::InvalidBucketWidth);
+
+    ensure!(
+        valid_halftime(&msg.halftime),
+        ContractError::InvalidHalftime
+    );
+    let decay = msg.decay.unwrap_or(Decimal::percent(50));
+    ensure!(valid_decay(decay), ContractError::InvalidDecay);
+
+    ensure!(
+        msg.referral_bps <= 10_000,
+        ContractError::InvalidReferralBps
+    );
+    ensure!(
+        msg.keeper_fee_bps <= 10_000,
+        ContractError::InvalidKeeperFee
     );
 
     let owner = deps.api.addr_validate(&msg.owner)?;
-    // let distribution_contract = deps.api.addr_validate(&msg.distribution_contract)?;
-    let distribution_contract = Addr::unchecked(msg.distribution_contract);
+    let distribution_contracts: Vec<_> = msg
+        .distribution_contracts
+        .into_iter()
+        .map(|(addr, weight)| Ok((deps.api.addr_validate(&addr)?, weight)))
+        .collect::<StdResult<_>>()?;
     let membership_contract = deps.api.addr_validate(&msg.membership_contract)?;
+    ensure!(
+        distribution_contracts
+            .iter()
+            .all(|(addr, _)| *addr != membership_contract),
+        ContractError::DistributionIsMembership
+    );
+    let instantiated_by = msg
+        .instantiated_by
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    // an account would only fail later, deep in the donation submessages
+    if !msg.skip_contract_check {
+        for (addr, _) in &distribution_contracts {
+            ensure_contract(&deps.querier, "distribution", addr)?;
+        }
+        ensure_contract(&deps.querier, "membership", &membership_contract)?;
+    }
+    let cw20_address = msg
+        .cw20_address
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let treasury = msg
+        .treasury
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let direct_beneficiary = msg
+        .direct_beneficiary
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let approvers = msg
+        .approvers
+        .iter()
+        .map(|addr| deps.api.addr_validate(addr))
+        .collect::<StdResult<Vec<_>>>()?;
+    validate_approvers(&approvers, msg.threshold)?;
+    exec::validate_metadata(&msg.metadata)?;
+    let proposal_duration = msg.proposal_duration.unwrap_or(DEFAULT_PROPOSAL_DURATION);
+    ensure!(
+        proposal_duration > 0,
+        ContractError::InvalidProposalDuration
+    );
 
-    OWNER.save(deps.storage, &owner)?;
-    WEIGHT.save(deps.storage, &msg.weight)?;
-    DONATIONS.save(deps.storage, &0)?;
-    CONFIG.save(
+    initialize_owner(deps.storage, deps.api, Some(owner.as_str()))?;
+    STATE.save(
         deps.storage,
-        &Config {
-            denom: msg.denom,
-            direct_part: msg.direct_part,
-            distribution_contract,
-            membership_contract,
-            is_closed: false,
+        &State {
+            weight: msg.weight,
+            epoch_donations: 0,
+            lifetime_donations: 0,
+            total_donated: Uint128::zero(),
+            halftime: msg.halftime,
+            last_updated: exec::block_point(&msg.halftime, &env.block),
         },
     )?;
-    HALFTIME.save(deps.storage, &msg.halftime)?;
-    LAST_UPDATED.save(deps.storage, &env.block.time.seconds())?;
+    WEIGHT_HISTORY.save(deps.storage, env.block.height, &msg.weight)?;
+    NEXT_DONATION_ID.save(deps.storage, &0)?;
+    NEXT_WITHDRAWAL_ID.save(deps.storage, &0)?;
+    UNIQUE_DONORS.save(deps.storage, &0)?;
+    MATCHING_POOL.save(deps.storage, &Uint128::zero())?;
+    let config = Config {
+        accepted_denoms: msg.accepted_denoms,
+        direct_part: msg.direct_part,
+        distribution_contracts,
+        membership_contract,
+        is_closed: false,
+        cw20_address,
+        min_donation: msg.min_donation,
+        direct_beneficiary,
+        goal: msg.goal,
+        protocol_fee: msg.protocol_fee,
+        treasury,
+        tiers: msg.tiers,
+        bucket_width,
+        matching_ratio: msg.matching_ratio.unwrap_or(Decimal::one()),
+        referral_bps: msg.referral_bps,
+        burn_part: msg.burn_part,
+        hook: None,
+        withdraw_cooldown: msg.withdraw_cooldown,
+        auto_withdraw_threshold: msg.auto_withdraw_threshold,
+        keeper_fee_bps: msg.keeper_fee_bps,
+        update_policy: UpdatePolicy::Anyone,
+        decay,
+        max_weight: msg.max_weight,
+        decay_funds: msg.decay_funds,
+        close_reason: None,
+        close_note: None,
+        closed_at: None,
+        approvers,
+        threshold: msg.threshold,
+        proposal_duration,
+        metadata: msg.metadata,
+        instantiated_by,
+        receipt_nft: None,
+        spend_limit: None,
+        skip_contract_check: msg.skip_contract_check,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let distribution_contracts: Vec<_> = config
+        .distribution_contracts
+        .iter()
+        .map(|(addr, weight)| format!("{}:{}", addr, weight))
+        .collect();
+    let resp = Response::new()
+        .add_attribute("action", "instantiate")
+        .add_attribute("contract_name", CONTRACT_NAME)
+        .add_attribute("contract_version", CONTRACT_VERSION)
+        .add_attribute("owner", owner.as_str())
+        .add_attribute("weight", msg.weight.to_string())
+        .add_attribute("accepted_denoms", config.accepted_denoms.join(","))
+        .add_attribute("direct_part", config.direct_part.to_string())
+        .add_attribute("distribution_contracts", distribution_contracts.join(","))
+        .add_attribute("membership_contract", config.membership_contract.as_str())
+        .add_attribute("halftime", msg.halftime.to_string())
+        .add_attribute("decay", config.decay.to_string())
+        .set_data(to_json_binary(&ProxyInstantiateResponse {
+            member_id: msg.member_id,
+            instantiated_by: config.instantiated_by,
+        })?);
+    Ok(resp)
+}
+
+// the protocol fee and the burn part are both taken next to the direct part
+pub(crate) fn validate_parts(
+    direct_part: Decimal,
+    protocol_fee: Decimal,
+    burn_part: Decimal,
+) -> Result<(), ContractError> {
+    ensure!(
+        Decimal::zero() <= direct_part && direct_part <= Decimal::percent(100),
+        ContractError::InalidDirectPart
+    );
+    ensure!(
+        direct_part + protocol_fee <= Decimal::percent(100),
+        ContractError::InvalidProtocolFee
+    );
+    ensure!(
+        direct_part + burn_part <= Decimal::percent(100),
+        ContractError::InvalidBurnPart
+    );
+    Ok(())
+}
 
-    Ok(Response::new())
+pub(crate) fn ensure_contract(
+    querier: &QuerierWrapper,
+    which: &str,
+    addr: &Addr,
+) -> Result<(), ContractError> {
+    querier
+        .query_wasm_contract_info(addr)
+        .map_err(|_| ContractError::NotAContract {
+            which: which.to_owned(),
+            addr: addr.to_string(),
+        })?;
+    Ok(())
+}
+
+// no approvers means no threshold, otherwise the approvers have to be able to reach it
+fn validate_approvers(approvers: &[Addr], threshold: u32) -> Result<(), ContractError> {
+    for (idx, approver) in approvers.iter().enumerate() {
+        ensure!(
+            !approvers[..idx].contains(approver),
+            ContractError::DuplicateApprover(approver.to_string())
+        );
+    }
+
+    let valid = match approvers.len() {
+        0 => threshold == 0,
+        len => threshold > 0 && threshold as usize <= len,
+    };
+    ensure!(
+        valid,
+        ContractError::InvalidThreshold {
+            threshold,
+            approvers: approvers.len() as u32,
+        }
+    );
+    Ok(())
+}
+
+const MAX_DENOM_LEN: usize = 128;
+
+// must_pay compares denoms as they are, a malformed one could never be paid
+fn validate_denom(denom: &str) -> Result<(), ContractError> {
+    ensure!(!denom.is_empty(), ContractError::EmptyDenom);
+    ensure!(
+        denom.len() <= MAX_DENOM_LEN,
+        ContractError::DenomTooLong(denom.to_owned())
+    );
+    ensure!(
+        !denom.chars().any(char::is_whitespace),
+        ContractError::DenomWithWhitespace(denom.to_owned())
+    );
+    Ok(())
+}
+
+fn parse_version(version: &str) -> Result<Version, ContractError> {
+    version
+        .parse()
+        .map_err(|_| ContractError::InvalidVersion(version.to_owned()))
+}
+
+// proxies from before cw2 are converted from the baseline layout first
+pub fn migrate(mut deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let current = parse_version(CONTRACT_VERSION)?;
+    let version = match get_contract_version(deps.storage) {
+        Ok(stored) => {
+            ensure!(
+                stored.contract == CONTRACT_NAME,
+                ContractError::WrongContract {
+                    contract: stored.contract
+                }
+            );
+            let version = parse_version(&stored.version)?;
+            ensure!(
+                version <= current,
+                ContractError::Downgrade {
+                    stored: stored.version,
+                    current: CONTRACT_VERSION.to_owned(),
+                }
+            );
+            version
+        }
+        Err(_) => {
+            migrate_baseline(deps.storage, &env.block)?;
+            Version::new(0, 0, 0)
+        }
+    };
+    migrate_from(deps.branch(), &env.block, &version)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("contract_name", CONTRACT_NAME)
+        .add_attribute("contract_version", CONTRACT_VERSION)
+        .add_attribute("previous_version", version.to_string()); // 0.0.0 before cw2
+    Ok(resp)
+}
+
+// state transitions from the stored version, oldest first, each one bringing the state a version up
+fn migrate_from(
+    mut deps: DepsMut,
+    block: &BlockInfo,
+    version: &Version,
+) -> Result<(), ContractError> {
+    if *version < Version::new(0, 2, 0) {
+        // 0.1.0 => 0.2.0, nothing changed in the state
+    }
+    if *version < Version::new(0, 3, 0) {
+        // 0.2.0 => 0.3.0, the owner moved to cw_ownable
+        migrate_owner(deps.branch(), block)?;
+    }
+    if *version < Version::new(0, 4, 0) {
+        // 0.3.0 => 0.4.0, lifetime donations counted apart from the epoch ones
+        // seeded with the current epoch counter
+        let donations = LEGACY_DONATIONS.load(deps.storage)?;
+        LEGACY_LIFETIME_DONATIONS.save(deps.storage, &donations)?;
+    }
+    if *version < Version::new(0, 5, 0) {
+        // 0.4.0 => 0.5.0, the counters gathered in one item
+        migrate_state(deps.storage)?;
+    }
+    if *version < Version::new(0, 6, 0) {
+        // 0.5.0 => 0.6.0, the leaderboard moved to an index of the donors map
+        migrate_donor_index(deps.storage)?;
+    }
+    Ok(())
+}
+
+// a single denom and distribution contract, everything added since gets its instantiate default
+#[cw_serde]
+struct BaselineConfig {
+    denom: String,
+    direct_part: Decimal,
+    distribution_contract: Addr,
+    membership_contract: Addr,
+    is_closed: bool,
+}
+
+const BASELINE_CONFIG: Item<BaselineConfig> = Item::new("config");
+const BASELINE_HALFTIME: Item<u64> = Item::new("halftime");
+const BASELINE_LAST_UPDATED: Item<u64> = Item::new("last_updated");
+// receiver and amount only, a leftover one would block every withdraw
+const BASELINE_PENDING_WITHDRAWAL: Item<Empty> = Item::new("pending_withdrawal");
+
+// brings a proxy without cw2 info to the layout of 0.1.0, halftime and last_updated were plain seconds
+fn migrate_baseline(storage: &mut dyn Storage, block: &BlockInfo) -> StdResult<()> {
+    let baseline = BASELINE_CONFIG.load(storage)?;
+    let config = Config {
+        accepted_denoms: vec![baseline.denom],
+        direct_part: baseline.direct_part,
+        distribution_contracts: vec![(baseline.distribution_contract, Decimal::one())],
+        membership_contract: baseline.membership_contract,
+        is_closed: baseline.is_closed,
+        cw20_address: None,
+        min_donation: Uint128::zero(),
+        direct_beneficiary: None,
+        goal: None,
+        protocol_fee: Decimal::zero(),
+        treasury: None,
+        tiers: vec![],
+        bucket_width: DEFAULT_BUCKET_WIDTH,
+        matching_ratio: Decimal::one(),
+        referral_bps: 0,
+        burn_part: Decimal::zero(),
+        hook: None,
+        withdraw_cooldown: 0,
+        auto_withdraw_threshold: None,
+        keeper_fee_bps: 0,
+        update_policy: UpdatePolicy::Anyone,
+        decay: Decimal::percent(50),
+        max_weight: None,
+        decay_funds: DecayFunds::default(),
+        // only the owner could close it
+        close_reason: baseline.is_closed.then_some(CloseReason::Owner),
+        close_note: None,
+        closed_at: None,
+        approvers: vec![],
+        threshold: 0,
+        proposal_duration: DEFAULT_PROPOSAL_DURATION,
+        metadata: None,
+        instantiated_by: None,
+        receipt_nft: None,
+        spend_limit: None,
+        skip_contract_check: false,
+    };
+    CONFIG.save(storage, &config)?;
+
+    let halftime = BASELINE_HALFTIME.load(storage)?;
+    let last_updated = BASELINE_LAST_UPDATED.load(storage)?;
+    LEGACY_HALFTIME.save(storage, &Duration::Time(halftime))?;
+    LEGACY_LAST_UPDATED.save(
+        storage,
+        &Expiration::AtTime(Timestamp::from_seconds(last_updated)),
+    )?;
+    BASELINE_PENDING_WITHDRAWAL.remove(storage);
+
+    // nothing was counted before, the history starts at the migration
+    let weight = LEGACY_WEIGHT.load(storage)?;
+    WEIGHT_HISTORY.save(storage, block.height, &weight)?;
+    NEXT_DONATION_ID.save(storage, &0)?;
+    NEXT_WITHDRAWAL_ID.save(storage, &0)?;
+    UNIQUE_DONORS.save(storage, &0)?;
+    MATCHING_POOL.save(storage, &Uint128::zero())?;
+    Ok(())
+}
+
+const LEGACY_WEIGHT: Item<u64> = Item::new("weight");
+const LEGACY_DONATIONS: Item<u64> = Item::new("donations");
+const LEGACY_LIFETIME_DONATIONS: Item<u64> = Item::new("lifetime_donations");
+const LEGACY_TOTAL_DONATED: Item<Uint128> = Item::new("total_donated");
+const LEGACY_HALFTIME: Item<Duration> = Item::new("halftime");
+const LEGACY_LAST_UPDATED: Item<Expiration> = Item::new("last_updated");
+
+fn migrate_state(storage: &mut dyn Storage) -> StdResult<()> {
+    let state = State {
+        weight: LEGACY_WEIGHT.load(storage)?,
+        epoch_donations: LEGACY_DONATIONS.load(storage)?,
+        lifetime_donations: LEGACY_LIFETIME_DONATIONS.load(storage)?,
+        // not counted before cw2
+        total_donated: LEGACY_TOTAL_DONATED.may_load(storage)?.unwrap_or_default(),
+        halftime: LEGACY_HALFTIME.load(storage)?,
+        last_updated: LEGACY_LAST_UPDATED.load(storage)?,
+    };
+    STATE.save(storage, &state)?;
+
+    LEGACY_WEIGHT.remove(storage);
+    LEGACY_DONATIONS.remove(storage);
+    LEGACY_LIFETIME_DONATIONS.remove(storage);
+    LEGACY_TOTAL_DONATED.remove(storage);
+    LEGACY_HALFTIME.remove(storage);
+    LEGACY_LAST_UPDATED.remove(storage);
+    Ok(())
+}
+
+const LEGACY_DONORS_BY_AMOUNT: Map<(u128, &Addr), Empty> = Map::new("donors_by_amount");
+
+fn migrate_donor_index(storage: &mut dyn Storage) -> StdResult<()> {
+    let donors = DONORS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (addr, donor) in donors {
+        DONORS.idx.amount.save(storage, addr.as_bytes(), &donor)?;
+    }
+    LEGACY_DONORS_BY_AMOUNT.clear(storage);
+    Ok(())
+}
+
+#[cw_serde]
+struct LegacyPendingOwner {
+    new_owner: Addr,
+    expiry: Option<u64>,
+}
+
+const LEGACY_OWNER: Item<Addr> = Item::new("owner");
+const LEGACY_RENOUNCED: Item<bool> = Item::new("renounced");
+const LEGACY_PENDING_OWNER: Item<LegacyPendingOwner> = Item::new("pending_owner");
+
+fn migrate_owner(mut deps: DepsMut, block: &BlockInfo) -> Result<(), ContractError> {
+    let owner = LEGACY_OWNER.load(deps.storage)?;
+    let renounced = LEGACY_RENOUNCED.may_load(deps.storage)?.unwrap_or(false);
+    let pending = LEGACY_PENDING_OWNER.may_load(deps.storage)?;
+
+    if renounced {
+        // the renounced owner kept the direct part, cw_ownable forgets it
+        CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+            config.direct_beneficiary.get_or_insert(owner);
+            Ok(config)
+        })?;
+        initialize_owner(deps.storage, deps.api, None)?;
+    } else {
+        initialize_owner(deps.storage, deps.api, Some(owner.as_str()))?;
+        if let Some(pending) = pending {
+            let action = Action::TransferOwnership {
+                new_owner: pending.new_owner.into(),
+                expiry: pending
+                    .expiry
+                    .map(|expiry| Expiration::AtTime(Timestamp::from_seconds(expiry))),
+            };
+            update_ownership(deps.branch(), block, &owner, action)?;
+        }
+    }
+
+    LEGACY_OWNER.remove(deps.storage);
+    LEGACY_RENOUNCED.remove(deps.storage);
+    LEGACY_PENDING_OWNER.remove(deps.storage);
+    Ok(())
+}
+
+const MAX_HALFTIME: u64 = 10 * 365 * 86400;
+const MAX_HALFTIME_BLOCKS: u64 = MAX_HALFTIME / 5; // about ten years of 5 second blocks
+
+pub(crate) fn valid_halftime(halftime: &Duration) -> bool {
+    match *halftime {
+        Duration::Time(seconds) => seconds > 0 && seconds <= MAX_HALFTIME,
+        Duration::Height(blocks) => blocks > 0 && blocks <= MAX_HALFTIME_BLOCKS,
+    }
+}
+
+// zero would never decay, above one would go negative
+pub(crate) fn valid_decay(decay: Decimal) -> bool {
+    !decay.is_zero() && decay <= Decimal::one()
 }
 
 pub fn execute(
@@ -55,23 +552,174 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     use ExecMsg::*;
 
+    if !matches!(msg, Pause {} | Unpause {}) {
+        exec::ensure_not_paused(deps.storage)?;
+    }
+
     match msg {
-        Donate {} => exec::donate(deps, info),
-        Withdraw { receiver, amount } => exec::withdraw(deps, info, env, receiver, amount),
-        Close {} => exec::close(deps, info),
+        Donate { memo, referrer } => exec::donate(deps, env, info, memo, referrer),
+        DonateFor { on_behalf_of } => exec::donate_for(deps, env, info, on_behalf_of),
+        DonateRemote {
+            remote_donor,
+            remote_chain,
+        } => exec::donate_remote(deps, env, info, remote_donor, remote_chain),
+        Withdraw {
+            receiver,
+            amount,
+            denom,
+            receiver_msg,
+            ibc,
+        } => exec::withdraw(deps, info, env, receiver, amount, denom, receiver_msg, ibc),
+        WithdrawSplit {
+            receivers,
+            amount,
+            denom,
+        } => exec::withdraw_split(deps, info, env, receivers, amount, denom),
+        WithdrawDirect { receiver } => exec::withdraw_direct(deps, env, info, receiver),
+        WithdrawAll { receiver } => exec::withdraw_all(deps, env, info, receiver),
+        ProposeWithdraw { receiver, amount } => {
+            exec::propose_withdraw(deps, env, info, receiver, amount)
+        }
+        ApproveWithdraw { id } => exec::approve_withdraw(deps, env, info, id),
+        WithdrawStreamed {
+            receiver,
+            duration_seconds,
+        } => exec::withdraw_streamed(deps, info, env, receiver, duration_seconds),
+        ClaimStream {} => exec::claim_stream(deps, env),
+        Close { reason } => exec::close(deps, env, info, reason),
+        Reopen {} => exec::reopen(deps, info),
         ProposeMember { addr } => exec::propose_member(deps, info, addr),
         UpdateWeight {} => exec::update_weight(deps, env, info),
+        Receive(msg) => exec::receive(deps, env, info, msg),
+        SetMinDonation { min_donation } => exec::set_min_donation(deps, info, min_donation),
+        SetDirectBeneficiary { addr } => exec::set_direct_beneficiary(deps, info, addr),
+        Block { addr } => exec::block(deps, info, addr),
+        Unblock { addr } => exec::unblock(deps, info, addr),
+        FundMatching {} => exec::fund_matching(deps, info),
+        WithdrawMatching {} => exec::withdraw_matching(deps, env, info),
+        SetHook { addr } => exec::set_hook(deps, info, addr),
+        SetReceiptNft { receipt } => exec::set_receipt_nft(deps, info, receipt),
+        SetMetadata { metadata } => exec::set_metadata(deps, info, metadata),
+        SetWithdrawCooldown { cooldown } => exec::set_withdraw_cooldown(deps, info, cooldown),
+        SetSpendLimit { limit } => exec::set_spend_limit(deps, info, limit),
+        GrantOperator {
+            addr,
+            can_withdraw,
+            can_propose,
+        } => exec::grant_operator(deps, info, addr, can_withdraw, can_propose),
+        RevokeOperator { addr } => exec::revoke_operator(deps, info, addr),
+        Crank {} => exec::crank(deps, env, info),
+        SetAutoWithdrawThreshold { threshold } => {
+            exec::set_auto_withdraw_threshold(deps, info, threshold)
+        }
+        SetKeeperFee { keeper_fee_bps } => exec::set_keeper_fee(deps, info, keeper_fee_bps),
+        SetHalftime { halftime } => exec::set_halftime(deps, env, info, halftime),
+        SetDecay { decay } => exec::set_decay(deps, info, decay),
+        SetDirectPart { value } => exec::set_direct_part(deps, info, value),
+        SetDistributionContract { addr } => exec::set_distribution_contract(deps, env, info, addr),
+        SetUpdatePolicy { policy } => exec::set_update_policy(deps, info, policy),
+        UpdateOwnership(action) => exec::update_ownership(deps, env, info, action),
+        CancelOwnershipTransfer {} => exec::cancel_ownership_transfer(deps, info),
+        RenounceOwnership { confirm } => exec::renounce_ownership(deps, env, info, confirm),
+        Pause {} => exec::pause(deps, info),
+        Unpause {} => exec::unpause(deps, info),
+    }
+}
+
+// only the chain itself can send these, so there is no sender to check
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    use SudoMsg::*;
+
+    match msg {
+        SetDirectPart { value } => sudo::set_direct_part(deps, value),
+        SetHalftime { value } => sudo::set_halftime(deps, env, value),
+        ForceClose {} => sudo::force_close(deps, env),
     }
 }
 
 pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
     match reply.id {
-        WITHDRAW_REPLY_ID => reply::withdraw(deps, env),
-        PROPOSE_MEMBER_REPLY_ID => reply::propose_member(reply.result.into_result()),
+        WITHDRAW_REPLY_ID => reply::withdraw(deps, env, reply.payload, reply.result.into_result()),
+        PROPOSE_MEMBER_REPLY_ID => {
+            reply::propose_member(deps, reply.payload, reply.result.into_result())
+        }
+        HOOK_REPLY_ID => reply::hook_failed(reply.result.into_result()),
+        DECAY_WITHDRAW_REPLY_ID => reply::decay_withdraw(deps, reply.result.into_result()),
+        RECEIPT_REPLY_ID => reply::receipt_failed(reply.result.into_result()),
         id => Err(ContractError::UnrecognizedReplyId(id)),
     }
 }
 
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    Ok(Binary::default())
+    use QueryMsg::*;
+
+    match msg {
+        Config {} => to_json_binary(&query::config(deps)?),
+        Owner {} => to_json_binary(&query::owner(deps)?),
+        Weight {} => to_json_binary(&query::weight(deps)?),
+        Donations {} => to_json_binary(&query::donations(deps)?),
+        Halftime {} => to_json_binary(&query::halftime(deps)?),
+        LastUpdated {} => to_json_binary(&query::last_updated(deps)?),
+        TotalDonated {} => to_json_binary(&query::total_donated(deps)?),
+        Donor { addr } => to_json_binary(&query::donor(deps, addr)?),
+        Stats {} => to_json_binary(&query::stats(deps)?),
+        DailyStats { from_day, to_day } => {
+            to_json_binary(&query::daily_stats(deps, from_day, to_day)?)
+        }
+        LargestDonation {} => to_json_binary(&query::largest_donation(deps)?),
+        GoalProgress {} => to_json_binary(&query::goal_progress(deps)?),
+        DonorTier { addr } => to_json_binary(&query::donor_tier(deps, addr)?),
+        ReferrerStats { addr } => to_json_binary(&query::referrer_stats(deps, addr)?),
+        SimulateDonation { amount } => to_json_binary(&query::simulate_donation(deps, amount)?),
+        Donors {
+            start_after,
+            limit,
+            inactive_since,
+        } => to_json_binary(&query::donors(deps, start_after, limit, inactive_since)?),
+        Referrers { start_after, limit } => {
+            to_json_binary(&query::referrers(deps, start_after, limit)?)
+        }
+        IsBlocked { addr } => to_json_binary(&query::is_blocked(deps, addr)?),
+        Blocklist { start_after, limit } => {
+            to_json_binary(&query::blocklist(deps, start_after, limit)?)
+        }
+        RemoteDonors { start_after, limit } => {
+            to_json_binary(&query::remote_donors(deps, start_after, limit)?)
+        }
+        TopDonors { start_after, limit } => {
+            to_json_binary(&query::top_donors(deps, start_after, limit)?)
+        }
+        DonationHistory { start_after, limit } => {
+            to_json_binary(&query::donation_history(deps, start_after, limit)?)
+        }
+        Operators { start_after, limit } => {
+            to_json_binary(&query::operators(deps, start_after, limit)?)
+        }
+        WithdrawalHistory { start_after, limit } => {
+            to_json_binary(&query::withdrawal_history(deps, start_after, limit)?)
+        }
+        EstimatedWithdrawable { denom } => {
+            to_json_binary(&query::estimated_withdrawable(deps, env, denom)?)
+        }
+        PendingWithdrawal {} => to_json_binary(&query::pending_withdrawal(deps)?),
+        Stream {} => to_json_binary(&query::stream(deps, env)?),
+        NextUpdate {} => to_json_binary(&query::next_update(deps, env)?),
+        ContractVersion {} => to_json_binary(&get_contract_version(deps.storage)?),
+        Balances {} => to_json_binary(&query::balances(deps, env)?),
+        ProposedMembers { start_after, limit } => {
+            to_json_binary(&query::proposed_members(deps, start_after, limit)?)
+        }
+        Metadata {} => to_json_binary(&query::metadata(deps)?),
+        Status {} => to_json_binary(&query::status(deps)?),
+        Paused {} => to_json_binary(&query::paused(deps)?),
+        Ownership {} => to_json_binary(&get_ownership(deps.storage)?),
+        WeightAt { height } => to_json_binary(&query::weight_at(deps, height)?),
+        WeightHistory { start_after, limit } => {
+            to_json_binary(&query::weight_history(deps, start_after, limit)?)
+        }
+        WithdrawProposal { id } => to_json_binary(&query::withdraw_proposal(deps, env, id)?),
+        WithdrawProposals { start_after, limit } => {
+            to_json_binary(&query::withdraw_proposals(deps, env, start_after, limit)?)
+        }
+    }
 }
@@ -1,101 +1,2277 @@
 use cosmwasm_std::{
-    coins, ensure, to_json_binary, DepsMut, Env, MessageInfo, Response, StdResult, SubMsg, Uint128,
-    WasmMsg,
+    coin, coins, ensure, from_json, to_json_binary, Addr, BankMsg, Binary, BlockInfo, Coin,
+    Decimal, DepsMut, Empty, Env, Event, Int128, MessageInfo, Order, QuerierWrapper, Response,
+    StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
-use cw_utils::must_pay;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_ownable::{Action, OwnershipError};
+use cw_utils::{must_pay, Duration, Expiration, PaymentError};
 
-use crate::contract::{PROPOSE_MEMBER_REPLY_ID, WITHDRAW_REPLY_ID};
+use crate::contract::{
+    ensure_contract, valid_decay, valid_halftime, validate_parts, DECAY_WITHDRAW_REPLY_ID,
+    HOOK_REPLY_ID, PROPOSE_MEMBER_REPLY_ID, RECEIPT_REPLY_ID, WITHDRAW_REPLY_ID,
+};
 use crate::error::ContractError;
-use crate::msg::{DistribtionExecMsg, MembershipExecMsg};
+use crate::msg::{
+    Cw20HookMsg, Cw721ExecMsg, DecayFunds, DistribtionExecMsg, DistributionQueryMsg, IbcWithdraw,
+    MembershipExecMsg, ProxyHookMsg, ProxyMetadata, ReceiptConfigMsg, UpdatePolicyMsg,
+    WithdrawableResponse,
+};
 use crate::state::{
-    WithdrawalData, CONFIG, DONATIONS, HALFTIME, LAST_UPDATED, OWNER, PENDING_WITHDRAWAL, WEIGHT,
+    BucketStats, CloseReason, Config, DonationRecord, OperatorPerms, ReceiptConfig, SpendWindow,
+    State, Stream, UpdatePolicy, WithdrawProposal, WithdrawalData, BLOCKLIST, CONFIG, DAILY_STATS,
+    DECAY_TARGETS, DONATION_HISTORY, DONORS, DUST, LARGEST_DONATION, LAST_WITHDRAWN, MATCHING_POOL,
+    NEXT_DONATION_ID, NEXT_PROPOSAL_ID, OPERATORS, PAUSED, PENDING_WITHDRAWAL, REFERRERS,
+    REMOTE_DONORS, RETAINED, SPEND_WINDOWS, STATE, STREAM, UNIQUE_DONORS, WEIGHTED_TARGETS,
+    WEIGHT_HISTORY, WITHDRAW_PROPOSALS,
 };
 
-pub fn donate(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    let amount = must_pay(&info, &config.denom)?;
+const MAX_MEMO_LEN: usize = 256;
+const MAX_CLOSE_NOTE_LEN: usize = 256;
+const MAX_NAME_LEN: usize = 64;
+const MAX_DESCRIPTION_LEN: usize = 512;
+const MAX_URL_LEN: usize = 256;
+const MAX_RECEIVERS: usize = 10;
+const MAX_DECAY_PERIODS: u64 = 32;
+
+pub fn donate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    memo: Option<String>,
+    referrer: Option<String>,
+) -> Result<Response, ContractError> {
+    let donor = info.sender.clone();
+    donate_native(deps, env, info, donor, memo, referrer)
+}
+
+pub fn donate_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    on_behalf_of: String,
+) -> Result<Response, ContractError> {
+    let donor = deps.api.addr_validate(&on_behalf_of)?;
+    ensure!(donor != env.contract.address, ContractError::DonateForProxy);
+    // the payer can't get around the blocklist by donating for someone else
+    ensure!(
+        !BLOCKLIST.has(deps.storage, &info.sender),
+        ContractError::DonorBlocked
+    );
+
+    donate_native(deps, env, info, donor, None, None)
+}
+
+// remote strings are shown to supporters and used as storage keys, so they are kept plain
+const MAX_REMOTE_DONOR_LEN: usize = 128;
+const MAX_REMOTE_CHAIN_LEN: usize = 64;
+
+pub fn donate_remote(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    remote_donor: String,
+    remote_chain: String,
+) -> Result<Response, ContractError> {
+    validate_remote("donor", &remote_donor, MAX_REMOTE_DONOR_LEN)?;
+    validate_remote("chain", &remote_chain, MAX_REMOTE_CHAIN_LEN)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let sender = info.sender.clone();
+    // the same coin donate_native takes, it fails first on anything wrong with the funds
+    let funds = split_funds(&info.funds, &config.accepted_denoms);
+    let resp = donate_native(deps.branch(), env.clone(), info, sender.clone(), None, None)?;
+    let (coin, _) = funds?;
+
+    let key = (remote_chain.as_str(), remote_donor.as_str());
+    let mut remote = REMOTE_DONORS
+        .may_load(deps.storage, key)?
+        .unwrap_or_default();
+    remote.total = remote
+        .total
+        .checked_add(coin.amount)
+        .map_err(StdError::from)?;
+    remote.count = increment(remote.count)?;
+    remote.last_donated = env.block.time.seconds();
+    REMOTE_DONORS.save(deps.storage, key, &remote)?;
+
+    let resp = resp
+        .add_attribute("hook_sender", sender.as_str())
+        .add_attribute("remote_chain", remote_chain)
+        .add_attribute("remote_donor", remote_donor);
+    Ok(resp)
+}
+
+fn validate_remote(field: &str, value: &str, max: usize) -> Result<(), ContractError> {
+    ensure!(
+        value.len() <= max,
+        ContractError::RemoteFieldTooLong {
+            field: field.to_owned(),
+            max,
+            len: value.len(),
+        }
+    );
+    ensure!(
+        !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/')),
+        ContractError::InvalidRemoteField {
+            field: field.to_owned()
+        }
+    );
+    Ok(())
+}
+
+// funds always come from info.sender, the donation is credited to donor
+fn donate_native(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    donor: Addr,
+    memo: Option<String>,
+    referrer: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(!config.is_closed, ContractError::ContractClosed);
+    ensure!(
+        !BLOCKLIST.has(deps.storage, &donor),
+        ContractError::DonorBlocked
+    );
+    validate_memo(&memo)?;
+    let decay = decay_weight(deps.storage, &env)?;
+
+    let referrer = referrer
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    ensure!(
+        referrer.as_ref() != Some(&donor),
+        ContractError::SelfReferral
+    );
+
+    let (coin, refunds) = split_funds(&info.funds, &config.accepted_denoms)?;
+    let amount = coin.amount;
+    ensure_min_donation(&config, amount)?;
+    let plan = plan_donation(deps.storage, &config, &coin.denom, amount, true)?;
+    save_plan(deps.storage, &coin.denom, &plan)?;
+    let DonationPlan {
+        mut split,
+        dust_carried,
+        matched,
+    } = plan;
+
+    // referral is cut out of the direct part only, never out of the distribution
+    let referral = match &referrer {
+        Some(referrer) => {
+            let referral = split.direct.mul_floor(Decimal::bps(config.referral_bps));
+            split.direct -= referral;
+            record_referral(deps.storage, referrer, amount, referral)?;
+            referral
+        }
+        None => Uint128::zero(),
+    };
+
+    let mut distribution_msgs = vec![];
+    for (target, share) in distribution_shares(&config, split.to_distribute) {
+        // 2 var with same name not a problem because the
+        // WasmMsg will create first then assign to distribution_msg later
+        let distribution_msg = DistribtionExecMsg::Distribute {};
+        let distribution_msg = WasmMsg::Execute {
+            contract_addr: target.to_string(),
+            msg: to_json_binary(&distribution_msg)?,
+            funds: coins(share.u128(), &coin.denom), // forward in the denom received
+        };
+        distribution_msgs.push(distribution_msg);
+        mark_weighted(deps.storage, &target, share)?;
+    }
+
+    let beneficiary = match &config.direct_beneficiary {
+        Some(beneficiary) => beneficiary.clone(),
+        None => payee(deps.storage, &config)?,
+    };
+    let (donation_id, tier) = record_donation(
+        deps.storage,
+        &env,
+        &config,
+        &donor,
+        amount,
+        &coin.denom,
+        memo,
+    )?;
+    let goal_reached = close_on_goal(deps.storage, &env, config.goal)?;
+
+    let mut resp = with_auto_decay(Response::new(), decay);
+    if !split.burn.is_zero() {
+        let burn_msg = BankMsg::Burn {
+            amount: coins(split.burn.u128(), &coin.denom),
+        };
+        resp = resp.add_message(burn_msg);
+    }
+
+    let hook_msg = hook_msg(&config, &donor, amount, &coin.denom)?;
+    let receipt_msg = receipt_msg(&config, &donor, amount, donation_id)?;
+    let event = donation_event(&donor, amount, &coin.denom, &split, donation_id);
+    let mut resp = resp
+        .add_messages(distribution_msgs)
+        .add_event(event)
+        .add_attribute("action", "donate")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("donor", donor.as_str())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("denom", &coin.denom)
+        .add_attribute("protocol_fee", split.fee.to_string())
+        .add_attribute("burned", split.burn.to_string())
+        .add_attribute("direct_amount", split.direct.to_string())
+        .add_attribute("to_distribute", split.to_distribute.to_string())
+        .add_attribute("dust_carried", dust_carried.to_string())
+        .add_attribute("matched", matched.to_string());
+
+    if let Some(referrer) = referrer {
+        resp = resp
+            .add_attribute("referrer", referrer.as_str())
+            .add_attribute("referral", referral.to_string());
+
+        if !referral.is_zero() {
+            let referral_msg = BankMsg::Send {
+                to_address: referrer.into_string(),
+                amount: coins(referral.u128(), &coin.denom),
+            };
+            resp = resp.add_message(referral_msg);
+        }
+    }
+
+    // bank module rejects empty coins, so nothing to send => no message
+    if let (false, Some(treasury)) = (split.fee.is_zero(), &config.treasury) {
+        let fee_msg = BankMsg::Send {
+            to_address: treasury.to_string(),
+            amount: coins(split.fee.u128(), &coin.denom),
+        };
+        resp = resp.add_message(fee_msg);
+    }
+
+    if !split.direct.is_zero() {
+        let direct_msg = BankMsg::Send {
+            to_address: beneficiary.into_string(),
+            amount: coins(split.direct.u128(), &coin.denom),
+        };
+        resp = resp.add_message(direct_msg);
+    }
+
+    // anything else attached is sent back to the donor
+    if !refunds.is_empty() {
+        let refunded: Vec<_> = refunds.iter().map(|coin| coin.denom.as_str()).collect();
+        resp = resp.add_attribute("refunded", refunded.join(","));
+
+        let refund_msg = BankMsg::Send {
+            to_address: info.sender.into_string(),
+            amount: refunds,
+        };
+        resp = resp.add_message(refund_msg);
+    }
+
+    if let Some(tier) = tier {
+        resp = resp.add_attribute("tier", tier);
+    }
+
+    if let Some(hook_msg) = hook_msg {
+        resp = resp.add_submessage(hook_msg);
+    }
+
+    if let Some((token_id, receipt_msg)) = receipt_msg {
+        resp = resp
+            .add_submessage(receipt_msg)
+            .add_attribute("token_id", token_id);
+    }
+
+    if goal_reached {
+        resp = resp.add_attribute("goal_reached", "yes");
+    }
+
+    Ok(resp)
+}
+
+pub struct DonationSplit {
+    pub fee: Uint128,
+    pub burn: Uint128,
+    pub direct: Uint128,
+    pub to_distribute: Uint128,
+    pub dust: Uint128,
+}
+
+pub struct DonationPlan {
+    pub split: DonationSplit,
+    pub dust_carried: Uint128,
+    pub matched: Uint128,
+}
+
+// all the donation math, read only so SimulateDonation runs exactly what donate does
+// cw20 donations are never matched
+pub fn plan_donation(
+    storage: &dyn Storage,
+    config: &Config,
+    denom: &str,
+    amount: Uint128,
+    matching: bool,
+) -> StdResult<DonationPlan> {
+    let mut split = split_donation(config, amount);
+
+    // the dust left by the previous donation in this denom is forwarded with this one
+    let dust_carried = DUST.may_load(storage, denom)?.unwrap_or_default();
+    split.to_distribute += dust_carried;
+
+    let matched = if matching {
+        matched_amount(storage, config, denom, amount)?
+    } else {
+        Uint128::zero()
+    };
+    split.to_distribute += matched;
+
+    Ok(DonationPlan {
+        split,
+        dust_carried,
+        matched,
+    })
+}
+
+// keeps this donation's dust and takes the matched funds out of the pool
+fn save_plan(storage: &mut dyn Storage, denom: &str, plan: &DonationPlan) -> StdResult<()> {
+    DUST.save(storage, denom, &plan.split.dust)?;
+    if !plan.matched.is_zero() {
+        MATCHING_POOL.update(storage, |pool| -> StdResult<_> {
+            Ok(pool.checked_sub(plan.matched)?)
+        })?;
+    }
+    Ok(())
+}
+
+// protocol fee is taken first, the rest is split between burn, direct and distribution parts
+// all parts are floored and the leftover is kept as dust, so parts always add up
+fn split_donation(config: &Config, amount: Uint128) -> DonationSplit {
+    let fee = amount.mul_floor(config.protocol_fee);
+    let remainder = amount - fee;
+    let burn = remainder.mul_floor(config.burn_part);
+    let direct = remainder.mul_floor(config.direct_part);
+    let to_distribute = remainder.mul_floor(Decimal::one() - config.direct_part - config.burn_part);
+
+    DonationSplit {
+        fee,
+        burn,
+        direct,
+        to_distribute,
+        dust: remainder - burn - direct - to_distribute,
+    }
+}
+
+// distributable part split by target weights, every share is floored
+// and the rounding remainder goes to the first target so nothing is lost
+// zero shares are dropped, transferring empty funds fails
+fn distribution_shares(config: &Config, to_distribute: Uint128) -> Vec<(Addr, Uint128)> {
+    let mut shares: Vec<_> = config
+        .distribution_contracts
+        .iter()
+        .map(|(target, weight)| (target.clone(), to_distribute.mul_floor(*weight)))
+        .collect();
+
+    let assigned: Uint128 = shares.iter().map(|(_, share)| share).sum();
+    shares[0].1 += to_distribute - assigned;
+    shares.retain(|(_, share)| !share.is_zero());
+    shares
+}
+
+// once a target got something from us, withdraw has to go there as well
+fn mark_weighted(storage: &mut dyn Storage, target: &Addr, share: Uint128) -> StdResult<()> {
+    if !share.is_zero() {
+        WEIGHTED_TARGETS.save(storage, target, &Empty {})?;
+    }
+    Ok(())
+}
+
+// distribution contracts to withdraw from, the first target is used until
+// any got donations so the withdraw reply is always triggered
+pub fn withdraw_targets(storage: &dyn Storage, config: &Config) -> StdResult<Vec<Addr>> {
+    let targets: Vec<_> = WEIGHTED_TARGETS
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    if targets.is_empty() {
+        return Ok(vec![config.distribution_contracts[0].0.clone()]);
+    }
+    Ok(targets)
+}
+
+// matched funds come out of the pool and are distributed along with the donation
+// once the pool runs dry the rest of the donation is simply not matched
+fn matched_amount(
+    storage: &dyn Storage,
+    config: &Config,
+    denom: &str,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    if denom != config.accepted_denoms[0] {
+        return Ok(Uint128::zero());
+    }
+
+    let pool = MATCHING_POOL.load(storage)?;
+    Ok(amount.mul_floor(config.matching_ratio).min(pool))
+}
+
+// picks the first coin of an accepted denom as the donation, the rest is to be refunded
+fn split_funds(
+    funds: &[Coin],
+    accepted_denoms: &[String],
+) -> Result<(Coin, Vec<Coin>), PaymentError> {
+    if funds.is_empty() {
+        return Err(PaymentError::NoFunds {});
+    }
+
+    let idx = funds
+        .iter()
+        .position(|coin| accepted_denoms.contains(&coin.denom))
+        .ok_or_else(|| PaymentError::MissingDenom(accepted_denoms.join(",")))?;
+
+    let mut refunds = funds.to_vec();
+    let donation = refunds.remove(idx);
+    Ok((donation, refunds))
+}
+
+pub fn receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // info.sender is the token contract here, the donor is msg.sender
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        config.cw20_address.as_ref() == Some(&info.sender),
+        ContractError::Unauthorized
+    );
+    ensure!(!config.is_closed, ContractError::ContractClosed);
+
+    let donor = deps.api.addr_validate(&msg.sender)?;
+    ensure!(
+        !BLOCKLIST.has(deps.storage, &donor),
+        ContractError::DonorBlocked
+    );
+
+    match from_json(&msg.msg)? {
+        Cw20HookMsg::Donate { memo } => {
+            donate_cw20(deps, env, config, info.sender, donor, msg.amount, memo)
+        }
+    }
+}
+
+fn donate_cw20(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    token: Addr,
+    donor: Addr,
+    amount: Uint128,
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    validate_memo(&memo)?;
+    let decay = decay_weight(deps.storage, &env)?;
+    ensure_min_donation(&config, amount)?;
+
+    let plan = plan_donation(deps.storage, &config, token.as_str(), amount, false)?;
+    save_plan(deps.storage, token.as_str(), &plan)?;
+    let DonationPlan {
+        split,
+        dust_carried,
+        ..
+    } = plan;
+
+    // same split as donate, but the distribution parts are forwarded with a cw20 Send
+    let mut distribution_msgs = vec![];
+    for (target, share) in distribution_shares(&config, split.to_distribute) {
+        let distribution_msg = Cw20ExecuteMsg::Send {
+            contract: target.to_string(),
+            amount: share,
+            msg: to_json_binary(&DistribtionExecMsg::Distribute {})?,
+        };
+        let distribution_msg = WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_json_binary(&distribution_msg)?,
+            funds: vec![],
+        };
+        distribution_msgs.push(distribution_msg);
+        mark_weighted(deps.storage, &target, share)?;
+    }
+
+    let (donation_id, tier) = record_donation(
+        deps.storage,
+        &env,
+        &config,
+        &donor,
+        amount,
+        token.as_str(),
+        memo,
+    )?;
+    let goal_reached = close_on_goal(deps.storage, &env, config.goal)?;
+
+    let mut resp = with_auto_decay(Response::new(), decay);
+    if !split.burn.is_zero() {
+        let burn_msg = WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Burn { amount: split.burn })?,
+            funds: vec![],
+        };
+        resp = resp.add_message(burn_msg);
+    }
+
+    let hook_msg = hook_msg(&config, &donor, amount, token.as_str())?;
+    let event = donation_event(&donor, amount, token.as_str(), &split, donation_id);
+    let mut resp = resp
+        .add_messages(distribution_msgs)
+        .add_event(event)
+        .add_attribute("action", "donate")
+        .add_attribute("sender", donor.as_str())
+        .add_attribute("token", token.as_str())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("protocol_fee", split.fee.to_string())
+        .add_attribute("burned", split.burn.to_string())
+        .add_attribute("dust_carried", dust_carried.to_string());
+
+    if let (false, Some(treasury)) = (split.fee.is_zero(), &config.treasury) {
+        let fee_msg = Cw20ExecuteMsg::Transfer {
+            recipient: treasury.to_string(),
+            amount: split.fee,
+        };
+        let fee_msg = WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_json_binary(&fee_msg)?,
+            funds: vec![],
+        };
+        resp = resp.add_message(fee_msg);
+    }
+
+    if let Some(tier) = tier {
+        resp = resp.add_attribute("tier", tier);
+    }
+
+    if let Some(hook_msg) = hook_msg {
+        resp = resp.add_submessage(hook_msg);
+    }
+
+    if goal_reached {
+        resp = resp.add_attribute("goal_reached", "yes");
+    }
+
+    Ok(resp)
+}
+
+// fire and forget notification, a broken hook can't block donations
+fn hook_msg(
+    config: &Config,
+    donor: &Addr,
+    amount: Uint128,
+    denom: &str,
+) -> StdResult<Option<SubMsg>> {
+    let Some(hook) = &config.hook else {
+        return Ok(None);
+    };
+
+    let hook_msg = ProxyHookMsg::DonationReceived {
+        donor: donor.to_string(),
+        amount,
+        denom: denom.to_owned(),
+    };
+    let hook_msg = WasmMsg::Execute {
+        contract_addr: hook.to_string(),
+        msg: to_json_binary(&hook_msg)?,
+        funds: vec![],
+    };
+    Ok(Some(SubMsg::reply_on_error(hook_msg, HOOK_REPLY_ID)))
+}
+
+// fire and forget like the hook, a failed mint never fails the donation
+// cw20 donations get no receipt, min_amount is in the native denom
+fn receipt_msg(
+    config: &Config,
+    donor: &Addr,
+    amount: Uint128,
+    donation_id: u64,
+) -> StdResult<Option<(String, SubMsg)>> {
+    let Some(receipt) = &config.receipt_nft else {
+        return Ok(None);
+    };
+    if amount < receipt.min_amount {
+        return Ok(None);
+    }
+
+    let token_id = donation_id.to_string();
+    let mint_msg = Cw721ExecMsg::Mint {
+        token_id: token_id.clone(),
+        owner: donor.to_string(),
+        token_uri: None,
+        extension: None,
+    };
+    let mint_msg = WasmMsg::Execute {
+        contract_addr: receipt.cw721_addr.to_string(),
+        msg: to_json_binary(&mint_msg)?,
+        funds: vec![],
+    };
+    let mint_msg = SubMsg::reply_on_error(mint_msg, RECEIPT_REPLY_ID);
+    Ok(Some((token_id, mint_msg)))
+}
+
+// stable event for indexers, the loose wasm attributes are kept for compatibility
+fn donation_event(
+    donor: &Addr,
+    amount: Uint128,
+    denom: &str,
+    split: &DonationSplit,
+    donation_id: u64,
+) -> Event {
+    Event::new("proxy-donation")
+        .add_attribute("donor", donor.as_str())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("denom", denom)
+        .add_attribute("direct_amount", split.direct.to_string())
+        .add_attribute("distributed_amount", split.to_distribute.to_string())
+        .add_attribute("donation_id", donation_id.to_string())
+}
+
+// failing here fails the whole tx, so the donor keeps the funds
+pub fn ensure_min_donation(config: &Config, amount: Uint128) -> Result<(), ContractError> {
+    ensure!(
+        amount >= config.min_donation,
+        ContractError::DonationTooSmall {
+            min: config.min_donation,
+            got: amount,
+        }
+    );
+    Ok(())
+}
+
+fn validate_memo(memo: &Option<String>) -> Result<(), ContractError> {
+    if let Some(memo) = memo {
+        ensure!(
+            memo.len() <= MAX_MEMO_LEN,
+            ContractError::MemoTooLong {
+                max: MAX_MEMO_LEN,
+                len: memo.len(),
+            }
+        );
+    }
+    Ok(())
+}
+
+pub fn validate_metadata(metadata: &Option<ProxyMetadata>) -> Result<(), ContractError> {
+    let Some(metadata) = metadata else {
+        return Ok(());
+    };
+
+    let fields = [
+        ("name", Some(&metadata.name), MAX_NAME_LEN),
+        (
+            "description",
+            metadata.description.as_ref(),
+            MAX_DESCRIPTION_LEN,
+        ),
+        ("url", metadata.url.as_ref(), MAX_URL_LEN),
+    ];
+    for (field, value, max) in fields {
+        let Some(value) = value else {
+            continue;
+        };
+        ensure!(
+            value.len() <= max,
+            ContractError::MetadataTooLong {
+                field: field.to_owned(),
+                max,
+                len: value.len(),
+            }
+        );
+        ensure!(
+            !value.chars().any(char::is_control),
+            ContractError::MetadataControlChars {
+                field: field.to_owned()
+            }
+        );
+    }
+    Ok(())
+}
+
+// bookkeeping shared by native and cw20 donations
+// returns the id of the donation and the donor's tier after it
+fn record_donation(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    donor: &Addr,
+    amount: Uint128,
+    denom: &str,
+    memo: Option<String>,
+) -> Result<(u64, Option<String>), ContractError> {
+    let mut state = STATE.load(storage)?;
+    state.epoch_donations = increment(state.epoch_donations)?;
+    state.lifetime_donations = increment(state.lifetime_donations)?;
+    state.total_donated = state
+        .total_donated
+        .checked_add(amount)
+        .map_err(StdError::from)?;
+    STATE.save(storage, &state)?;
+
+    let mut donor_info = DONORS.may_load(storage, donor)?.unwrap_or_default();
+    if donor_info.count == 0 {
+        // first time this address shows up
+        UNIQUE_DONORS.update(storage, increment)?;
+    }
+    donor_info.total = donor_info
+        .total
+        .checked_add(amount)
+        .map_err(StdError::from)?;
+    donor_info.count = increment(donor_info.count)?;
+    donor_info.last_donated = Some(env.block.time.seconds());
+    donor_info.tier = tier_of(&config.tiers, donor_info.total);
+    // moves the donor in the leaderboard index too
+    DONORS.save(storage, donor, &donor_info)?;
+
+    let id = NEXT_DONATION_ID.load(storage)?;
+    let record = DonationRecord {
+        donor: donor.clone(),
+        amount,
+        denom: denom.to_owned(),
+        memo,
+        time: env.block.time.seconds(),
+    };
+    DONATION_HISTORY.save(storage, id, &record)?;
+    NEXT_DONATION_ID.save(storage, &increment(id)?)?;
+
+    // strictly greater, so ties keep the earlier record
+    let largest = LARGEST_DONATION.may_load(storage)?;
+    if largest.is_none_or(|(_, largest, _)| amount > largest) {
+        LARGEST_DONATION.save(storage, &(donor.clone(), amount, env.block.time.seconds()))?;
+    }
+
+    let day = env.block.time.seconds() / config.bucket_width;
+    DAILY_STATS.update(storage, day, |bucket| -> Result<_, ContractError> {
+        let mut bucket: BucketStats = bucket.unwrap_or_default();
+        bucket.count = increment(bucket.count)?;
+        bucket.amount = bucket.amount.checked_add(amount).map_err(StdError::from)?;
+        Ok(bucket)
+    })?;
+
+    Ok((id, donor_info.tier))
+}
+
+fn record_referral(
+    storage: &mut dyn Storage,
+    referrer: &Addr,
+    amount: Uint128,
+    earned: Uint128,
+) -> Result<(), ContractError> {
+    let mut referrer_info = REFERRERS.may_load(storage, referrer)?.unwrap_or_default();
+    referrer_info.count = increment(referrer_info.count)?;
+    referrer_info.total = referrer_info
+        .total
+        .checked_add(amount)
+        .map_err(StdError::from)?;
+    referrer_info.earned = referrer_info
+        .earned
+        .checked_add(earned)
+        .map_err(StdError::from)?;
+    REFERRERS.save(storage, referrer, &referrer_info)?;
+    Ok(())
+}
+
+// highest tier whose threshold the total reaches, tiers are sorted ascending
+fn tier_of(tiers: &[(Uint128, String)], total: Uint128) -> Option<String> {
+    tiers
+        .iter()
+        .rev()
+        .find(|(threshold, _)| total >= *threshold)
+        .map(|(_, name)| name.clone())
+}
+
+// closes the proxy once the lifetime total reaches the goal
+// the donation crossing it is still accepted in full
+fn close_on_goal(storage: &mut dyn Storage, env: &Env, goal: Option<Uint128>) -> StdResult<bool> {
+    let Some(goal) = goal else {
+        return Ok(false);
+    };
+
+    if STATE.load(storage)?.total_donated < goal {
+        return Ok(false);
+    }
+
+    CONFIG.update(storage, |mut config| -> StdResult<_> {
+        mark_closed(&mut config, CloseReason::Goal, None, env);
+        Ok(config)
+    })?;
+    Ok(true)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    receiver: Option<String>,
+    amount: Option<Uint128>,
+    denom: Option<String>,
+    receiver_msg: Option<Binary>,
+    ibc: Option<IbcWithdraw>,
+) -> Result<Response, ContractError> {
+    // the owner is not a contract to execute anything on
+    ensure!(
+        receiver_msg.is_none() || receiver.is_some(),
+        ContractError::ReceiverMsgWithoutReceiver
+    );
+    if let Some(ibc) = &ibc {
+        ensure!(
+            receiver.is_none() && receiver_msg.is_none(),
+            ContractError::IbcWithReceiver
+        );
+        validate_ibc(ibc)?;
+    }
+    // still allowed once closed, and as often as the owner likes: closing on the goal leaves the
+    // owner's share in the distribution, each withdraw drains what it owes so far
+    ensure_owner_withdraw(deps.storage, &env, &info)?;
+    // if do not specify receiver => withdraw to owner
+    let receiver = match receiver {
+        Some(addr_str) => deps.api.addr_validate(&addr_str)?,
+        None => payee(deps.storage, &CONFIG.load(deps.storage)?)?,
+    };
+    start_withdraw(
+        deps,
+        info,
+        env,
+        vec![(receiver, Decimal::one())],
+        amount,
+        denom,
+        None,
+        receiver_msg,
+        None,
+        None,
+        ibc,
+    )
+}
+
+pub fn withdraw_split(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    receivers: Vec<(String, Decimal)>,
+    amount: Option<Uint128>,
+    denom: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure!(!receivers.is_empty(), ContractError::NoReceivers);
+    ensure!(
+        receivers.len() <= MAX_RECEIVERS,
+        ContractError::TooManyReceivers { max: MAX_RECEIVERS }
+    );
+    let total_share: Decimal = receivers.iter().map(|(_, share)| share).sum();
+    ensure!(
+        total_share == Decimal::one(),
+        ContractError::InvalidReceiverShares
+    );
+
+    let receivers = receivers
+        .into_iter()
+        .map(|(addr, share)| Ok((deps.api.addr_validate(&addr)?, share)))
+        .collect::<StdResult<_>>()?;
+
+    ensure_owner_withdraw(deps.storage, &env, &info)?;
+    start_withdraw(
+        deps, info, env, receivers, amount, denom, None, None, None, None, None,
+    )
+}
+
+pub fn withdraw_streamed(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    receiver: String,
+    duration_seconds: u64,
+) -> Result<Response, ContractError> {
+    ensure!(duration_seconds > 0, ContractError::InvalidStreamDuration);
+    // the reply sets the end from the same block time
+    env.block
+        .time
+        .seconds()
+        .checked_add(duration_seconds)
+        .ok_or(ContractError::TimeOverflow)?;
+    // the previous stream is removed by its last claim
+    ensure!(!STREAM.exists(deps.storage), ContractError::StreamActive);
+    let receiver = deps.api.addr_validate(&receiver)?;
+
+    ensure_owner_withdraw(deps.storage, &env, &info)?;
+    start_withdraw(
+        deps,
+        info,
+        env,
+        vec![(receiver, Decimal::one())],
+        None,
+        None,
+        None,
+        None,
+        Some(duration_seconds),
+        None,
+        None,
+    )
+}
+
+pub fn claim_stream(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut stream = STREAM
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoActiveStream)?;
+
+    let claimable = vested(&stream, env.block.time.seconds()) - stream.claimed;
+    ensure!(!claimable.is_zero(), ContractError::NothingToWithdraw);
+
+    stream.claimed += claimable;
+    if stream.claimed == stream.total {
+        STREAM.remove(deps.storage);
+    } else {
+        STREAM.save(deps.storage, &stream)?;
+    }
+
+    let bank_msg = BankMsg::Send {
+        to_address: stream.receiver.to_string(),
+        amount: coins(claimable.u128(), &stream.denom),
+    };
+
+    let resp = Response::new()
+        .add_message(bank_msg)
+        .add_attribute("action", "claim_stream")
+        .add_attribute("receiver", stream.receiver.as_str())
+        .add_attribute("amount", claimable.to_string())
+        .add_attribute("claimed", stream.claimed.to_string());
+
+    Ok(resp)
+}
+
+// linear between start and end, the whole total once it's over so rounding never leaves anything behind
+pub fn vested(stream: &Stream, now: u64) -> Uint128 {
+    if now >= stream.end {
+        return stream.total;
+    }
+    let elapsed = now.saturating_sub(stream.start);
+    stream
+        .total
+        .multiply_ratio(elapsed, stream.end - stream.start)
+}
+
+pub fn crank(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // a keeper pays the owner like Withdraw does, so it waits for a proposal as well
+    ensure!(config.approvers.is_empty(), ContractError::ApprovalRequired);
+    let threshold = config
+        .auto_withdraw_threshold
+        .ok_or(ContractError::AutoWithdrawDisabled)?;
+
+    let denom = config.accepted_denoms[0].clone();
+    let mut pending = Uint128::zero();
+    for target in withdraw_targets(deps.storage, &config)? {
+        let query_msg = DistributionQueryMsg::Withdrawable {
+            proxy: env.contract.address.to_string(),
+            denom: denom.clone(),
+        };
+        let resp: WithdrawableResponse = deps.querier.query_wasm_smart(target, &query_msg)?;
+        pending += resp.amount;
+    }
+    ensure!(
+        pending > threshold,
+        ContractError::BelowWithdrawThreshold { pending, threshold }
+    );
+
+    let owner = payee(deps.storage, &config)?;
+    let keeper = info.sender.clone();
+    start_withdraw(
+        deps,
+        info,
+        env,
+        vec![(owner, Decimal::one())],
+        None,
+        Some(denom),
+        Some(keeper),
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+// shared by the withdraw flows, keeper is only set for Crank
+#[allow(clippy::too_many_arguments)]
+fn start_withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    receivers: Vec<(Addr, Decimal)>,
+    amount: Option<Uint128>,
+    denom: Option<String>,
+    keeper: Option<Addr>,
+    receiver_msg: Option<Binary>,
+    stream_duration: Option<u64>,
+    new_distribution: Option<Addr>,
+    ibc: Option<IbcWithdraw>,
+) -> Result<Response, ContractError> {
+    ensure!(
+        amount != Some(Uint128::zero()),
+        ContractError::ZeroWithdrawAmount
+    );
+    // a second withdraw would overwrite the receivers of the first one
+    ensure!(
+        !PENDING_WITHDRAWAL.exists(deps.storage),
+        ContractError::WithdrawalPending
+    );
+
+    let config = CONFIG.load(deps.storage)?;
+    for (receiver, _) in &receivers {
+        ensure_valid_receiver(&env, &config, receiver)?;
+    }
+    let denom = denom.unwrap_or_else(|| config.accepted_denoms[0].clone());
+    ensure!(
+        config.accepted_denoms.contains(&denom),
+        PaymentError::ExtraDenom(denom)
+    );
+    // a closed proxy is frozen, its weight doesn't decay anymore
+    let decay = if config.is_closed {
+        None
+    } else {
+        decay_weight(deps.storage, &env)?
+    };
+
+    let state = STATE.load(deps.storage)?;
+    let weight = state.weight; // involvement
+    let donations = state.epoch_donations;
+    // distribution gets the diff to the capped weight, so both sides agree on it
+    let new_weight = match config.max_weight {
+        Some(max_weight) => donations.min(max_weight),
+        None => donations,
+    };
+    let diff = weight_diff(weight, new_weight);
+
+    let mut event = Event::new("proxy-withdraw");
+    for (receiver, _) in &receivers {
+        event = event.add_attribute("receiver", receiver.as_str());
+    }
+    let mut event = event
+        .add_attribute("denom", &denom)
+        .add_attribute("weight", weight.to_string())
+        .add_attribute("diff", diff.to_string());
+    // no amount => whole balance, known only in the reply
+    if let Some(amount) = amount {
+        event = event.add_attribute("amount", amount.to_string());
+    }
+    if let Some(ibc) = &ibc {
+        event = event
+            .add_attribute("ibc_channel", &ibc.channel_id)
+            .add_attribute("ibc_to_address", &ibc.to_address);
+    }
+
+    let targets = withdraw_targets(deps.storage, &config)?;
+    let balances_before = all_balances(&deps.querier, &env.contract.address)?;
+    let mut withdrawal = WithdrawalData {
+        receivers,
+        amount,
+        denom,
+        pending_replies: targets.len() as u32,
+        received: Uint128::zero(),
+        previous_donations: donations,
+        new_weight,
+        keeper,
+        receiver_msg,
+        stream_duration,
+        balances_before,
+        new_distribution,
+        target: None,
+        ibc,
+    };
+    PENDING_WITHDRAWAL.save(deps.storage, &withdrawal)?;
+
+    let mut withdraw_msgs = vec![];
+    for target in targets {
+        // every reply gets its own copy, counting down so only the last one settles
+        withdrawal.pending_replies -= 1;
+        withdrawal.target = Some(target.clone());
+        let payload = to_json_binary(&withdrawal)?;
+
+        let withdraw_msg = DistribtionExecMsg::Withdraw { weight, diff };
+
+        let withdraw_msg = WasmMsg::Execute {
+            contract_addr: target.into_string(),
+            msg: to_json_binary(&withdraw_msg)?,
+            funds: vec![],
+        };
+
+        // every target reports what it sent back or why it failed, weight is only
+        // committed once the last one succeeded
+        withdraw_msgs
+            .push(SubMsg::reply_always(withdraw_msg, WITHDRAW_REPLY_ID).with_payload(payload));
+    }
+
+    let mut resp = with_auto_decay(Response::new(), decay);
+    if new_weight < donations {
+        resp = resp.add_attribute("weight_capped", new_weight.to_string());
+    }
+    let resp = resp
+        .add_submessages(withdraw_msgs)
+        .add_event(event)
+        .add_attribute("action", "withdraw")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("new weight", weight.to_string());
+
+    Ok(resp)
+}
+
+// funds sent to the proxy itself would just come back as a fake withdrawal,
+// and raw bank funds sent to a distribution contract skip its accounting
+fn ensure_valid_receiver(env: &Env, config: &Config, receiver: &Addr) -> Result<(), ContractError> {
+    ensure!(
+        *receiver != env.contract.address,
+        ContractError::InvalidReceiver
+    );
+    ensure!(
+        !config
+            .distribution_contracts
+            .iter()
+            .any(|(distribution, _)| distribution == receiver),
+        ContractError::InvalidReceiver
+    );
+    Ok(())
+}
+
+// only owner withdraws are rate limited, Crank and the decay in update_weight are not
+fn ensure_owner_withdraw(
+    storage: &dyn Storage,
+    env: &Env,
+    info: &MessageInfo,
+) -> Result<(), ContractError> {
+    ensure_permitted(storage, &info.sender, |perms| perms.can_withdraw)?;
+
+    let config = CONFIG.load(storage)?;
+    ensure!(config.approvers.is_empty(), ContractError::ApprovalRequired);
+    ensure_withdraw_cooldown(storage, env, &config)
+}
+
+fn ensure_withdraw_cooldown(
+    storage: &dyn Storage,
+    env: &Env,
+    config: &Config,
+) -> Result<(), ContractError> {
+    let Some(last_withdrawn) = LAST_WITHDRAWN.may_load(storage)? else {
+        return Ok(());
+    };
+
+    let ready_at = last_withdrawn
+        .checked_add(config.withdraw_cooldown)
+        .ok_or(ContractError::TimeOverflow)?;
+    let now = env.block.time.seconds();
+    ensure!(
+        now >= ready_at,
+        ContractError::WithdrawCooldown {
+            remaining: ready_at - now
+        }
+    );
+    Ok(())
+}
+
+pub fn propose_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receiver: Option<String>,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(!config.approvers.is_empty(), ContractError::NoApprovers);
+    let is_approver = config.approvers.contains(&info.sender);
+    if !is_approver {
+        ensure_permitted(deps.storage, &info.sender, |perms| perms.can_withdraw)?;
+    }
+    ensure!(
+        amount != Some(Uint128::zero()),
+        ContractError::ZeroWithdrawAmount
+    );
+
+    let receiver = match receiver {
+        Some(addr) => deps.api.addr_validate(&addr)?,
+        None => payee(deps.storage, &config)?,
+    };
+    ensure_valid_receiver(&env, &config, &receiver)?;
+
+    let id = NEXT_PROPOSAL_ID.may_load(deps.storage)?.unwrap_or(0);
+    NEXT_PROPOSAL_ID.save(deps.storage, &increment(id)?)?;
+    let proposal = WithdrawProposal {
+        receiver,
+        amount,
+        approvals: vec![],
+        expires: env
+            .block
+            .time
+            .seconds()
+            .checked_add(config.proposal_duration)
+            .ok_or(ContractError::TimeOverflow)?,
+    };
+
+    let resp = if is_approver {
+        add_approval(deps, env, info, &config, id, proposal)?
+    } else {
+        WITHDRAW_PROPOSALS.save(deps.storage, id, &proposal)?;
+        None
+    };
+    let resp = resp
+        .unwrap_or_else(|| Response::new().add_attribute("action", "propose_withdraw"))
+        .add_attribute("proposal_id", id.to_string());
+    Ok(resp)
+}
+
+pub fn approve_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        config.approvers.contains(&info.sender),
+        ContractError::Unauthorized
+    );
+
+    let proposal = WITHDRAW_PROPOSALS
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::ProposalNotFound { id })?;
+    ensure!(
+        env.block.time.seconds() < proposal.expires,
+        ContractError::ProposalExpired { id }
+    );
+    ensure!(
+        !proposal.approvals.contains(&info.sender),
+        ContractError::AlreadyApproved
+    );
+
+    let resp = add_approval(deps, env, info, &config, id, proposal)?
+        .unwrap_or_else(|| Response::new().add_attribute("action", "approve_withdraw"))
+        .add_attribute("proposal_id", id.to_string());
+    Ok(resp)
+}
+
+// the withdraw response once the approval meets the threshold, None while it waits for more
+fn add_approval(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: &Config,
+    id: u64,
+    mut proposal: WithdrawProposal,
+) -> Result<Option<Response>, ContractError> {
+    proposal.approvals.push(info.sender.clone());
+    if (proposal.approvals.len() as u32) < config.threshold {
+        WITHDRAW_PROPOSALS.save(deps.storage, id, &proposal)?;
+        return Ok(None);
+    }
+
+    // a failing withdraw reverts this too, the proposal can be approved again
+    WITHDRAW_PROPOSALS.remove(deps.storage, id);
+    ensure_withdraw_cooldown(deps.storage, &env, config)?;
+    let resp = start_withdraw(
+        deps,
+        info,
+        env,
+        vec![(proposal.receiver, Decimal::one())],
+        proposal.amount,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )?;
+    Ok(Some(resp))
+}
+
+// the membership contract closes the proxies of members voted out, the owner can't reopen those
+pub fn close(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    reason: Option<String>,
+) -> Result<Response, ContractError> {
+    if let Some(reason) = &reason {
+        ensure!(
+            reason.len() <= MAX_CLOSE_NOTE_LEN,
+            ContractError::CloseReasonTooLong {
+                max: MAX_CLOSE_NOTE_LEN,
+                len: reason.len(),
+            }
+        );
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    let (owner, close_reason) = if is_membership(&config, &info.sender) {
+        (payee(deps.storage, &config)?, CloseReason::Membership)
+    } else {
+        let owner = ensure_owner(deps.storage, &info.sender)?;
+        // closing again would let the owner reopen it
+        ensure!(
+            !matches!(
+                config.close_reason,
+                Some(CloseReason::Membership | CloseReason::Governance)
+            ),
+            ContractError::ContractClosed
+        );
+        (owner, CloseReason::Owner)
+    };
+    let closed_by = match close_reason {
+        CloseReason::Membership => "membership",
+        _ => "owner",
+    };
+    // with approvers the owner's share only leaves through a proposal, so an owner close
+    // just stops donations like a governance one and reopening it pays nothing either
+    let pays_out = close_reason == CloseReason::Membership || config.approvers.is_empty();
+
+    mark_closed(&mut config, close_reason, reason, &env);
+    CONFIG.save(deps.storage, &config)?;
+    if !pays_out {
+        let resp = Response::new()
+            .add_attribute("action", "close")
+            .add_attribute("closed_by", closed_by);
+        return Ok(resp);
+    }
+
+    let swept = sweep(deps.branch(), &env, &config)?;
+
+    // nothing left earning shares, no need for a final withdraw
+    let mut state = STATE.load(deps.storage)?;
+    let resp = if state.weight == 0 {
+        Response::new()
+    } else {
+        // new weight is what donations counted, no donations means the weight goes to zero
+        state.epoch_donations = 0;
+        STATE.save(deps.storage, &state)?;
+        start_withdraw(
+            deps,
+            info,
+            env,
+            vec![(owner.clone(), Decimal::one())],
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )?
+    };
+    let mut resp = resp
+        .add_attribute("action", "close")
+        .add_attribute("closed_by", closed_by);
+
+    if !swept.is_empty() {
+        let swept_attr: Vec<_> = swept.iter().map(Coin::to_string).collect();
+        resp = resp.add_attribute("swept", swept_attr.join(","));
+        // sent after the final withdraw, so its reply only sees what the distribution sent
+        let sweep_msg = BankMsg::Send {
+            to_address: owner.into_string(),
+            amount: swept,
+        };
+        resp = resp.add_message(sweep_msg);
+    }
+    Ok(resp)
+}
+
+// closes the owner didn't write a note for get a canonical one
+pub fn mark_closed(config: &mut Config, reason: CloseReason, note: Option<String>, env: &Env) {
+    let canonical = match reason {
+        CloseReason::Owner => None,
+        CloseReason::Goal => Some("goal reached"),
+        CloseReason::Membership => Some("removed by the membership contract"),
+        CloseReason::Governance => Some("closed by governance"),
+    };
+    config.is_closed = true;
+    config.close_reason = Some(reason);
+    config.close_note = note.or(canonical.map(str::to_owned));
+    config.closed_at = Some(env.block.time.seconds());
+}
+
+// nothing is owed to future donations once closed, so dust, the matching pool and retained funds
+// all go back to the owner with anything else held, only the stream and a pending withdrawal keep theirs.
+// what the spend limit holds back is retained for the owner's next withdraw
+fn sweep(deps: DepsMut, env: &Env, config: &Config) -> StdResult<Vec<Coin>> {
+    let now = env.block.time.seconds();
+    let pending = PENDING_WITHDRAWAL.may_load(deps.storage)?;
+    let stream = STREAM.may_load(deps.storage)?;
+
+    let mut swept = vec![];
+    for balance in all_balances(&deps.querier, &env.contract.address)? {
+        let mut reserved = Uint128::zero();
+        if let Some(stream) = stream
+            .as_ref()
+            .filter(|stream| stream.denom == balance.denom)
+        {
+            reserved += stream.total - stream.claimed;
+        }
+        if let Some(pending) = pending
+            .as_ref()
+            .filter(|pending| pending.denom == balance.denom)
+        {
+            reserved += pending.received;
+        }
+
+        DUST.remove(deps.storage, &balance.denom);
+        let owned = balance.amount.saturating_sub(reserved);
+        let amount = spend(deps.storage, config, &balance.denom, now, owned)?;
+        retain(deps.storage, &balance.denom, owned - amount)?;
+        if !amount.is_zero() {
+            swept.push(coin(amount.u128(), balance.denom));
+        }
+    }
+    MATCHING_POOL.save(deps.storage, &Uint128::zero())?;
+
+    Ok(swept)
+}
+
+pub fn reopen(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    ensure!(config.is_closed, ContractError::NotClosed);
+    ensure!(
+        config.close_reason != Some(CloseReason::Membership),
+        ContractError::ClosedByMembership
+    );
+    ensure!(
+        config.close_reason != Some(CloseReason::Governance),
+        ContractError::ClosedByGovernance
+    );
+
+    config.is_closed = false;
+    config.close_reason = None;
+    config.close_note = None;
+    config.closed_at = None;
+    CONFIG.save(deps.storage, &config)?;
+
+    let resp = Response::new().add_attribute("action", "reopen");
+    Ok(resp)
+}
+
+// the owner's share is withdrawn from the current contracts first, the switch happens in the
+// withdraw reply so nothing accrued there is left behind
+pub fn set_distribution_contract(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    let owner = ensure_owner(deps.storage, &info.sender)?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        addr != env.contract.address && addr != config.membership_contract,
+        ContractError::InvalidDistributionContract
+    );
+    // the final withdraw pays the owner
+    ensure!(config.approvers.is_empty(), ContractError::ApprovalRequired);
+    // checked like the ones given at instantiate, an account would only fail the next donation
+    if !config.skip_contract_check {
+        ensure_contract(&deps.querier, "distribution", &addr)?;
+    }
+
+    let old_distribution: Vec<_> = config
+        .distribution_contracts
+        .iter()
+        .map(|(distribution, _)| distribution.as_str())
+        .collect();
+    let old_distribution = old_distribution.join(",");
+
+    let resp = start_withdraw(
+        deps.branch(),
+        info,
+        env,
+        vec![(owner, Decimal::one())],
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(addr.clone()),
+        None,
+    )?;
+
+    let resp = resp
+        .add_attribute("action", "set_distribution_contract")
+        .add_attribute("old_distribution", old_distribution)
+        .add_attribute("new_distribution", addr.as_str());
+    Ok(resp)
+}
+
+pub fn set_min_donation(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_donation: Uint128,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.min_donation = min_donation;
+        Ok(config)
+    })?;
+
+    let resp = Response::new()
+        .add_attribute("action", "set_min_donation")
+        .add_attribute("min_donation", min_donation.to_string());
+    Ok(resp)
+}
+
+pub fn set_direct_beneficiary(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: Option<String>,
+) -> Result<Response, ContractError> {
+    let owner = ensure_owner(deps.storage, &info.sender)?;
+
+    let beneficiary = addr.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+
+    // only affects future donations, nothing already on the contract is moved
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.direct_beneficiary = beneficiary.clone();
+        Ok(config)
+    })?;
+
+    let beneficiary = beneficiary.unwrap_or(owner);
+    let resp = Response::new()
+        .add_attribute("action", "set_direct_beneficiary")
+        .add_attribute("direct_beneficiary", beneficiary.as_str());
+    Ok(resp)
+}
+
+// blocking only refuses future donations, nothing already donated is touched
+pub fn block(deps: DepsMut, info: MessageInfo, addr: String) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    BLOCKLIST.save(deps.storage, &addr, &Empty {})?;
+
+    let resp = Response::new()
+        .add_attribute("action", "block")
+        .add_attribute("addr", addr.as_str());
+    Ok(resp)
+}
+
+pub fn unblock(deps: DepsMut, info: MessageInfo, addr: String) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    BLOCKLIST.remove(deps.storage, &addr);
+
+    let resp = Response::new()
+        .add_attribute("action", "unblock")
+        .add_attribute("addr", addr.as_str());
+    Ok(resp)
+}
+
+pub fn fund_matching(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let amount = must_pay(&info, &config.accepted_denoms[0])?;
+
+    let pool = MATCHING_POOL.update(deps.storage, |pool| -> StdResult<_> {
+        Ok(pool.checked_add(amount)?)
+    })?;
+
+    let resp = Response::new()
+        .add_attribute("action", "fund_matching")
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("pool", pool.to_string());
+    Ok(resp)
+}
+
+// hands whatever is left of the pool back to the owner
+pub fn withdraw_matching(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let owner = ensure_owner(deps.storage, &info.sender)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let denom = &config.accepted_denoms[0];
+    let pool = MATCHING_POOL.load(deps.storage)?;
+    // what the spend limit holds back stays in the pool
+    let amount = spend(deps.storage, &config, denom, env.block.time.seconds(), pool)?;
+    MATCHING_POOL.save(deps.storage, &(pool - amount))?;
+
+    let mut resp = Response::new()
+        .add_attribute("action", "withdraw_matching")
+        .add_attribute("amount", amount.to_string());
+
+    if !amount.is_zero() {
+        let bank_msg = BankMsg::Send {
+            to_address: owner.into_string(),
+            amount: coins(amount.u128(), denom),
+        };
+        resp = resp.add_message(bank_msg);
+    }
+
+    Ok(resp)
+}
+
+// pays out the owner's share sitting on the proxy without asking the distribution contracts
+pub fn withdraw_direct(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let owner = ensure_owner(deps.storage, &info.sender)?;
+
+    let receiver = receiver
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?
+        .unwrap_or(owner);
+
+    let config = CONFIG.load(deps.storage)?;
+    // like Withdraw, the owner's share only leaves through a proposal once approvers are set
+    ensure!(config.approvers.is_empty(), ContractError::ApprovalRequired);
+    ensure_valid_receiver(&env, &config, &receiver)?;
+    let denom = &config.accepted_denoms[0];
+    let balance = deps
+        .querier
+        .query_balance(env.contract.address, denom)?
+        .amount;
+
+    let owned = spendable(deps.storage, &config, denom, balance)?;
+    ensure!(!owned.is_zero(), ContractError::NothingToWithdraw);
+    let amount = spend(
+        deps.storage,
+        &config,
+        denom,
+        env.block.time.seconds(),
+        owned,
+    )?;
+    ensure!(
+        !amount.is_zero(),
+        ContractError::SpendLimitExceeded {
+            remaining: Uint128::zero()
+        }
+    );
+    retain(deps.storage, denom, owned - amount)?;
+
+    let bank_msg = BankMsg::Send {
+        to_address: receiver.to_string(),
+        amount: coins(amount.u128(), denom),
+    };
+
+    let resp = Response::new()
+        .add_message(bank_msg)
+        .add_attribute("action", "withdraw_direct")
+        .add_attribute("receiver", receiver.as_str())
+        .add_attribute("amount", amount.to_string());
+    Ok(resp)
+}
+
+// every denom the proxy holds, in a single bank message
+pub fn withdraw_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let owner = ensure_owner(deps.storage, &info.sender)?;
+
+    let receiver = receiver
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?
+        .unwrap_or(owner);
+
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(config.approvers.is_empty(), ContractError::ApprovalRequired);
+    ensure_valid_receiver(&env, &config, &receiver)?;
+    let mut resp = Response::new()
+        .add_attribute("action", "withdraw_all")
+        .add_attribute("receiver", receiver.as_str());
+
+    let now = env.block.time.seconds();
+    let mut owned_any = false;
+    let mut funds = vec![];
+    for balance in all_balances(&deps.querier, &env.contract.address)? {
+        let owned = spendable(deps.storage, &config, &balance.denom, balance.amount)?;
+        if owned.is_zero() {
+            continue;
+        }
+        owned_any = true;
+        let amount = spend(deps.storage, &config, &balance.denom, now, owned)?;
+        retain(deps.storage, &balance.denom, owned - amount)?;
+        if amount.is_zero() {
+            continue;
+        }
+        resp = resp.add_attribute(&balance.denom, amount.to_string());
+        funds.push(coin(amount.u128(), balance.denom));
+    }
+    ensure!(owned_any, ContractError::NothingToWithdraw);
+    ensure!(
+        !funds.is_empty(),
+        ContractError::SpendLimitExceeded {
+            remaining: Uint128::zero()
+        }
+    );
+
+    let bank_msg = BankMsg::Send {
+        to_address: receiver.into_string(),
+        amount: funds,
+    };
+    Ok(resp.add_message(bank_msg))
+}
+
+// balance left once everything reserved is set aside
+pub fn spendable(
+    storage: &dyn Storage,
+    config: &Config,
+    denom: &str,
+    balance: Uint128,
+) -> StdResult<Uint128> {
+    let reserved = reserved_with_pending(storage, config, denom)?;
+    Ok(balance.saturating_sub(reserved))
+}
+
+// the part of amount the spend limit still allows in the denom's window, counted as spent there,
+// the membership closing the proxy pays the owner out in full
+// by design a payout over the limit is capped rather than rejected: the reply of a withdraw can't
+// fail without reverting what the distribution sent, so the rest goes to RETAINED for a later
+// window, the direct payouts only fail with SpendLimitExceeded once the window has nothing left
+pub fn spend(
+    storage: &mut dyn Storage,
+    config: &Config,
+    denom: &str,
+    now: u64,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    let Some((limit, window)) = config.spend_limit else {
+        return Ok(amount);
+    };
+    if config.close_reason == Some(CloseReason::Membership) {
+        return Ok(amount);
+    }
+
+    let mut spend_window = match SPEND_WINDOWS.may_load(storage, denom)? {
+        Some(spend_window) if now < spend_window.start.saturating_add(window) => spend_window,
+        _ => SpendWindow {
+            start: now,
+            spent: Uint128::zero(),
+        },
+    };
+    let amount = amount.min(limit.saturating_sub(spend_window.spent));
+    spend_window.spent += amount;
+    SPEND_WINDOWS.save(storage, denom, &spend_window)?;
+    Ok(amount)
+}
+
+// the owner's funds a payout held back, the next owner withdraw pays them once a window allows it
+pub fn retain(storage: &mut dyn Storage, denom: &str, amount: Uint128) -> StdResult<()> {
+    if amount.is_zero() {
+        RETAINED.remove(storage, denom);
+        Ok(())
+    } else {
+        RETAINED.save(storage, denom, &amount)
+    }
+}
+
+// the bank query for every denom is deprecated as chains drop it, but the proxy has no other way
+// to find denoms it was sent outside accepted_denoms
+#[allow(deprecated)]
+pub fn all_balances(querier: &QuerierWrapper, addr: &Addr) -> StdResult<Vec<Coin>> {
+    querier.query_all_balances(addr)
+}
+
+// reserved funds and funds already received for a pending withdrawal,
+// which belong to its receivers
+pub fn reserved_with_pending(
+    storage: &dyn Storage,
+    config: &Config,
+    denom: &str,
+) -> StdResult<Uint128> {
+    let mut reserved = reserved_balance(storage, config, denom)?;
+    if let Some(pending) = PENDING_WITHDRAWAL.may_load(storage)? {
+        if pending.denom == denom {
+            reserved += pending.received;
+        }
+    }
+    Ok(reserved)
+}
+
+// dust is owed to the distribution and the matching pool to future donations,
+// neither is part of the owner's share
+pub fn reserved_balance(storage: &dyn Storage, config: &Config, denom: &str) -> StdResult<Uint128> {
+    let mut reserved = DUST.may_load(storage, denom)?.unwrap_or_default();
+    if config.accepted_denoms[0] == denom {
+        reserved += MATCHING_POOL.load(storage)?;
+    }
+    // streamed funds belong to the stream receiver already
+    if let Some(stream) = STREAM.may_load(storage)? {
+        if stream.denom == denom {
+            reserved += stream.total - stream.claimed;
+        }
+    }
+    Ok(reserved)
+}
+
+pub fn set_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let hook = addr.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.hook = hook.clone();
+        Ok(config)
+    })?;
+
+    let mut resp = Response::new().add_attribute("action", "set_hook");
+    if let Some(hook) = hook {
+        resp = resp.add_attribute("hook", hook.as_str());
+    }
+    Ok(resp)
+}
+
+pub fn set_receipt_nft(
+    deps: DepsMut,
+    info: MessageInfo,
+    receipt: Option<ReceiptConfigMsg>,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
 
-    let direct_amount = amount * config.direct_part;
-    let to_distribute = amount - direct_amount;
+    let receipt = receipt
+        .map(|receipt| -> StdResult<_> {
+            Ok(ReceiptConfig {
+                cw721_addr: deps.api.addr_validate(&receipt.cw721_addr)?,
+                min_amount: receipt.min_amount,
+            })
+        })
+        .transpose()?;
 
-    // 2 var with same name not a problem because the
-    // WasmMsg will create first then assign to distribution_msg later
-    let distribution_msg = DistribtionExecMsg::Distribute {};
-    let distribution_msg = WasmMsg::Execute {
-        contract_addr: config.distribution_contract.into_string(),
-        msg: to_json_binary(&distribution_msg)?,
-        funds: coins(to_distribute.u128(), &config.denom),
-    };
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.receipt_nft = receipt.clone();
+        Ok(config)
+    })?;
+
+    let mut resp = Response::new().add_attribute("action", "set_receipt_nft");
+    if let Some(receipt) = receipt {
+        resp = resp
+            .add_attribute("cw721_addr", receipt.cw721_addr.as_str())
+            .add_attribute("min_amount", receipt.min_amount.to_string());
+    }
+    Ok(resp)
+}
+
+pub fn set_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    metadata: Option<ProxyMetadata>,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+    validate_metadata(&metadata)?;
+
+    let mut resp = Response::new().add_attribute("action", "set_metadata");
+    if let Some(metadata) = &metadata {
+        resp = resp.add_attribute("name", &metadata.name);
+    }
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.metadata = metadata;
+        Ok(config)
+    })?;
+    Ok(resp)
+}
+
+pub fn set_withdraw_cooldown(
+    deps: DepsMut,
+    info: MessageInfo,
+    cooldown: u64,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
 
-    DONATIONS.update(deps.storage, |donations| -> StdResult<_> {
-        Ok(donations + 1)
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.withdraw_cooldown = cooldown;
+        Ok(config)
     })?;
 
     let resp = Response::new()
-        .add_message(distribution_msg)
-        .add_attribute("action", "donate")
-        .add_attribute("sender", info.sender.as_str())
-        .add_attribute("amount", amount.to_string());
+        .add_attribute("action", "set_withdraw_cooldown")
+        .add_attribute("cooldown", cooldown.to_string());
+    Ok(resp)
+}
+
+// the window already started keeps counting, only the limit changes
+pub fn set_spend_limit(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<(Uint128, u64)>,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+    if let Some((_, window)) = limit {
+        ensure!(window > 0, ContractError::ZeroSpendWindow);
+    }
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.spend_limit = limit;
+        Ok(config)
+    })?;
 
+    let mut resp = Response::new().add_attribute("action", "set_spend_limit");
+    if let Some((amount, window)) = limit {
+        resp = resp
+            .add_attribute("limit", amount.to_string())
+            .add_attribute("window", window.to_string());
+    }
     Ok(resp)
 }
 
-pub fn withdraw(
+pub fn set_auto_withdraw_threshold(
     deps: DepsMut,
     info: MessageInfo,
+    threshold: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.auto_withdraw_threshold = threshold;
+        Ok(config)
+    })?;
+
+    let mut resp = Response::new().add_attribute("action", "set_auto_withdraw_threshold");
+    if let Some(threshold) = threshold {
+        resp = resp.add_attribute("threshold", threshold.to_string());
+    }
+    Ok(resp)
+}
+
+// last_updated is left alone, so the next update sees the elapsed time in new halftimes,
+// unless the unit changes and the period starts over from this block
+pub fn set_halftime(
+    deps: DepsMut,
     env: Env,
-    receiver: Option<String>,
-    amount: Option<Uint128>,
+    info: MessageInfo,
+    halftime: Duration,
 ) -> Result<Response, ContractError> {
-    let owner = OWNER.load(deps.storage)?;
-    ensure!(owner == info.sender, ContractError::Unauthorized);
+    ensure_owner(deps.storage, &info.sender)?;
+    let old_halftime = replace_halftime(deps.storage, &env, halftime)?;
 
-    let weight = WEIGHT.load(deps.storage)?; // involvement
-    let donations = DONATIONS.load(deps.storage)?;
-    let diff = donations as i64 - weight as i64;
+    let resp = Response::new()
+        .add_attribute("action", "set_halftime")
+        .add_attribute("old_halftime", old_halftime.to_string())
+        .add_attribute("new_halftime", halftime.to_string());
+    Ok(resp)
+}
 
-    WEIGHT.save(deps.storage, &donations)?;
-    DONATIONS.save(deps.storage, &1)?;
-    LAST_UPDATED.save(deps.storage, &env.block.time.seconds())?;
+pub fn set_direct_part(
+    deps: DepsMut,
+    info: MessageInfo,
+    value: Decimal,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+    let old_direct_part = replace_direct_part(deps.storage, value)?;
 
-    let receiver = receiver
-        .map(|addr_str| deps.api.addr_validate(&addr_str))
-        .transpose()?
-        .unwrap_or_else(|| info.sender.clone()); // if do not specify receiver => withdraw to owner
+    let resp = Response::new()
+        .add_attribute("action", "set_direct_part")
+        .add_attribute("old_direct_part", old_direct_part.to_string())
+        .add_attribute("new_direct_part", value.to_string());
+    Ok(resp)
+}
 
-    PENDING_WITHDRAWAL.save(deps.storage, &WithdrawalData { receiver, amount })?;
+// shared with sudo, returns the direct part replaced
+// only donations from now on are split with the new one
+pub fn replace_direct_part(
+    storage: &mut dyn Storage,
+    value: Decimal,
+) -> Result<Decimal, ContractError> {
+    let mut config = CONFIG.load(storage)?;
+    validate_parts(value, config.protocol_fee, config.burn_part)?;
 
-    let withdraw_msg = DistribtionExecMsg::Withdraw { weight, diff };
+    let old_direct_part = config.direct_part;
+    config.direct_part = value;
+    CONFIG.save(storage, &config)?;
+    Ok(old_direct_part)
+}
 
-    let config = CONFIG.load(deps.storage)?;
+// shared with sudo, returns the halftime replaced
+pub fn replace_halftime(
+    storage: &mut dyn Storage,
+    env: &Env,
+    halftime: Duration,
+) -> Result<Duration, ContractError> {
+    ensure!(valid_halftime(&halftime), ContractError::InvalidHalftime);
 
-    let withdraw_msg = WasmMsg::Execute {
-        contract_addr: config.distribution_contract.into_string(),
-        msg: to_json_binary(&withdraw_msg)?,
-        funds: vec![],
+    let mut state = STATE.load(storage)?;
+    let old_halftime = state.halftime;
+    if block_point(&old_halftime, &env.block) != block_point(&halftime, &env.block) {
+        state.last_updated = block_point(&halftime, &env.block);
+    }
+    state.halftime = halftime;
+    STATE.save(storage, &state)?;
+    Ok(old_halftime)
+}
+
+pub fn set_decay(
+    deps: DepsMut,
+    info: MessageInfo,
+    decay: Decimal,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+    ensure!(valid_decay(decay), ContractError::InvalidDecay);
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.decay = decay;
+        Ok(config)
+    })?;
+
+    let resp = Response::new()
+        .add_attribute("action", "set_decay")
+        .add_attribute("decay", decay.to_string());
+    Ok(resp)
+}
+
+pub fn set_update_policy(
+    deps: DepsMut,
+    info: MessageInfo,
+    policy: UpdatePolicyMsg,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let policy = match policy {
+        UpdatePolicyMsg::Owner {} => UpdatePolicy::Owner,
+        UpdatePolicyMsg::Keeper { addr } => UpdatePolicy::Keeper(deps.api.addr_validate(&addr)?),
+        UpdatePolicyMsg::Membership {} => UpdatePolicy::Membership,
+        UpdatePolicyMsg::Anyone {} => UpdatePolicy::Anyone,
     };
 
-    let withdraw_msg = SubMsg::reply_on_success(withdraw_msg, WITHDRAW_REPLY_ID);
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.update_policy = policy;
+        Ok(config)
+    })?;
+
+    let resp = Response::new().add_attribute("action", "set_update_policy");
+    Ok(resp)
+}
+
+pub fn update_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: Action,
+) -> Result<Response, ContractError> {
+    match &action {
+        Action::TransferOwnership {
+            expiry: Some(expiry),
+            ..
+        } => ensure!(
+            !expiry.is_expired(&env.block),
+            ContractError::InvalidOwnershipExpiry
+        ),
+        Action::RenounceOwnership => return Err(ContractError::RenounceNotConfirmed),
+        _ => (),
+    }
+
+    let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
 
     let resp = Response::new()
-        .add_submessage(withdraw_msg)
-        .add_attribute("action", "withdraw")
-        .add_attribute("sender", info.sender.as_str())
-        .add_attribute("new weight", weight.to_string());
+        .add_attribute("action", "update_ownership")
+        .add_attributes(ownership.into_attributes());
+    Ok(resp)
+}
+
+pub fn cancel_ownership_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let owner = ensure_owner(deps.storage, &info.sender)?;
+    let ownership = cw_ownable::get_ownership(deps.storage)?;
+    ensure!(
+        ownership.pending_owner.is_some(),
+        OwnershipError::TransferNotFound
+    );
+
+    // starting over drops the pending transfer
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(owner.as_str()))?;
+
+    let resp = Response::new().add_attribute("action", "cancel_ownership_transfer");
+    Ok(resp)
+}
+
+pub fn renounce_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    confirm: bool,
+) -> Result<Response, ContractError> {
+    let owner = ensure_owner(deps.storage, &info.sender)?;
+    ensure!(confirm, ContractError::RenounceNotConfirmed);
+    ensure!(
+        !PENDING_WITHDRAWAL.exists(deps.storage),
+        ContractError::WithdrawalPending
+    );
+
+    // Crank withdraws to the owner too
+    // the owner keeps receiving the direct part, cw_ownable forgets it
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.auto_withdraw_threshold = None;
+        config.direct_beneficiary.get_or_insert(owner.clone());
+        Ok(config)
+    })?;
+    cw_ownable::update_ownership(deps, &env.block, &owner, Action::RenounceOwnership)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "renounce_ownership")
+        .add_attribute("owner", owner.as_str());
+    Ok(resp)
+}
+
+pub fn ensure_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
+    let paused = PAUSED.may_load(storage)?.unwrap_or(false);
+    ensure!(!paused, ContractError::Paused);
+    Ok(())
+}
+
+pub fn pause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+    ensure_not_paused(deps.storage)?;
+
+    PAUSED.save(deps.storage, &true)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "pause")
+        .add_attribute("sender", info.sender.as_str());
+    Ok(resp)
+}
 
+pub fn unpause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+    let paused = PAUSED.may_load(deps.storage)?.unwrap_or(false);
+    ensure!(paused, ContractError::NotPaused);
+
+    PAUSED.save(deps.storage, &false)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "unpause")
+        .add_attribute("sender", info.sender.as_str());
     Ok(resp)
 }
 
-pub fn close(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    let owner = OWNER.load(deps.storage)?;
-    ensure!(owner == info.sender, ContractError::Unauthorized);
+pub fn set_keeper_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    keeper_fee_bps: u64,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+    ensure!(keeper_fee_bps <= 10_000, ContractError::InvalidKeeperFee);
 
     CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
-        config.is_closed = true;
+        config.keeper_fee_bps = keeper_fee_bps;
         Ok(config)
     })?;
 
-    let resp = Response::new().add_attribute("action", "close");
+    let resp = Response::new()
+        .add_attribute("action", "set_keeper_fee")
+        .add_attribute("keeper_fee_bps", keeper_fee_bps.to_string());
+    Ok(resp)
+}
+
+// nobody passes once the ownership is renounced
+fn ensure_owner(storage: &dyn Storage, sender: &Addr) -> Result<Addr, ContractError> {
+    cw_ownable::assert_owner(storage, sender)?;
+    Ok(sender.clone())
+}
+
+// the remote chain validates the address, only what is surely wrong is refused here
+fn validate_ibc(ibc: &IbcWithdraw) -> Result<(), ContractError> {
+    ensure!(
+        !ibc.channel_id.trim().is_empty(),
+        ContractError::EmptyIbcChannel
+    );
+    ensure!(
+        !ibc.to_address.trim().is_empty(),
+        ContractError::EmptyIbcAddress
+    );
+    ensure!(ibc.timeout_seconds > 0, ContractError::ZeroIbcTimeout);
+    Ok(())
+}
+
+// the membership contract in the config, or the one that spawned this proxy
+fn is_membership(config: &Config, sender: &Addr) -> bool {
+    *sender == config.membership_contract || config.instantiated_by.as_ref() == Some(sender)
+}
+
+// the owner, or whom it left the direct part to when it renounced
+fn payee(storage: &dyn Storage, config: &Config) -> StdResult<Addr> {
+    cw_ownable::get_ownership(storage)?
+        .owner
+        .or_else(|| config.direct_beneficiary.clone())
+        .ok_or_else(|| StdError::generic_err("proxy has no owner"))
+}
+
+// the owner can do anything, operators only what their perms allow
+// operators act for the owner, so they lose their perms once it is renounced
+fn ensure_permitted(
+    storage: &dyn Storage,
+    sender: &Addr,
+    permitted: impl Fn(&OperatorPerms) -> bool,
+) -> Result<(), ContractError> {
+    let owner = cw_ownable::get_ownership(storage)?.owner;
+    let Some(owner) = owner else {
+        return Err(ContractError::Unauthorized);
+    };
+
+    if owner == *sender {
+        return Ok(());
+    }
+
+    let perms = OPERATORS.may_load(storage, sender)?;
+    ensure!(
+        perms.as_ref().is_some_and(permitted),
+        ContractError::Unauthorized
+    );
+    Ok(())
+}
+
+pub fn grant_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+    can_withdraw: bool,
+    can_propose: bool,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let operator = deps.api.addr_validate(&addr)?;
+    OPERATORS.save(
+        deps.storage,
+        &operator,
+        &OperatorPerms {
+            can_withdraw,
+            can_propose,
+        },
+    )?;
+
+    let resp = Response::new()
+        .add_attribute("action", "grant_operator")
+        .add_attribute("operator", operator.as_str())
+        .add_attribute("can_withdraw", can_withdraw.to_string())
+        .add_attribute("can_propose", can_propose.to_string());
+    Ok(resp)
+}
+
+pub fn revoke_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    ensure_owner(deps.storage, &info.sender)?;
+
+    let operator = deps.api.addr_validate(&addr)?;
+    OPERATORS.remove(deps.storage, &operator);
+
+    let resp = Response::new()
+        .add_attribute("action", "revoke_operator")
+        .add_attribute("operator", operator.as_str());
     Ok(resp)
 }
 
@@ -104,11 +2280,16 @@ pub fn propose_member(
     info: MessageInfo,
     addr: String,
 ) -> Result<Response, ContractError> {
-    // check this is send by owner (content creator) of this proxy contract
-    let owner = OWNER.load(deps.storage)?;
-    ensure!(owner == info.sender, ContractError::Unauthorized);
+    // check this is send by owner (content creator) of this proxy contract, or an operator allowed to
+    ensure_permitted(deps.storage, &info.sender, |perms| perms.can_propose)?;
 
+    // a retired creator doesn't onboard anyone anymore
     let config = CONFIG.load(deps.storage)?;
+    ensure!(!config.is_closed, ContractError::ContractClosed);
+
+    // the reply needs the candidate to record the proxy created for them
+    let candidate = deps.api.addr_validate(&addr)?;
+    let payload = to_json_binary(&candidate)?;
 
     let propose_member_msg = MembershipExecMsg::ProposeMember { addr: addr.clone() };
     let propose_member_msg = WasmMsg::Execute {
@@ -116,7 +2297,8 @@ pub fn propose_member(
         msg: to_json_binary(&propose_member_msg)?,
         funds: vec![],
     };
-    let propose_member_msg = SubMsg::reply_on_success(propose_member_msg, PROPOSE_MEMBER_REPLY_ID);
+    let propose_member_msg =
+        SubMsg::reply_always(propose_member_msg, PROPOSE_MEMBER_REPLY_ID).with_payload(payload);
 
     let resp = Response::new()
         .add_submessage(propose_member_msg)
@@ -131,50 +2313,329 @@ pub fn update_weight(
     env: Env,
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
-    let last_updated = LAST_UPDATED.load(deps.storage)?;
-    let halftime = HALFTIME.load(deps.storage)?;
+    // the weight of a closed proxy is frozen
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(!config.is_closed, ContractError::ContractClosed);
+
+    // otherwise anyone could time the decay right after every halftime
+    let allowed = match config.update_policy {
+        UpdatePolicy::Owner => cw_ownable::is_owner(deps.storage, &info.sender)?,
+        UpdatePolicy::Keeper(keeper) => keeper == info.sender,
+        UpdatePolicy::Membership => is_membership(&config, &info.sender),
+        UpdatePolicy::Anyone => true,
+    };
+    ensure!(allowed, ContractError::Unauthorized);
 
     let resp = Response::new()
         .add_attribute("action", "update_weight")
         .add_attribute("sender", info.sender.as_str());
 
+    let Some(decay) = decay_weight(deps.storage, &env)? else {
+        // not yet halftime
+        let resp = resp.add_attribute("performed", "no");
+        return Ok(resp);
+    };
+
+    let resp = resp
+        .add_attribute("performed", "yes")
+        .add_submessages(decay.msgs)
+        .add_events(decay.events)
+        .add_attribute("new weight", decay.new_weight.to_string());
+
+    Ok(resp)
+}
+
+struct WeightDecay {
+    msgs: Vec<SubMsg>,
+    events: Vec<Event>,
+    new_weight: u64,
+}
+
+// shared by update_weight and the lazy decay in donate / withdraw, so they can't drift
+// None if halftime didn't pass yet
+fn decay_weight(
+    storage: &mut dyn Storage,
+    env: &Env,
+) -> Result<Option<WeightDecay>, ContractError> {
+    let mut state = STATE.load(storage)?;
+    let last_updated = state.last_updated;
+    let halftime = state.halftime;
+
     // halftime is the duration to allow perform update_weight
 
     // Example:
     // halftime = 1 day (86400 seconds)
     // current_time = 20/11/2020 00:00:00
     // last_updated = 19/11/2020 00:00:00
-    // if halftime < current_time - last_updated => update_weight
+    // if last_updated + halftime is expired => update_weight
 
-    let elapsed_time = env.block.time.seconds() - last_updated;
-    if halftime > elapsed_time {
-        // not yet halftime
-        let resp = resp.add_attribute("performed", "no");
-        return Ok(resp);
+    if !(last_updated + halftime)?.is_expired(&env.block) {
+        return Ok(None);
     }
 
-    let resp = resp.add_attribute("performed", "yes");
+    let config = CONFIG.load(storage)?;
 
-    let config = CONFIG.load(deps.storage)?;
+    // one decay per halftime passed, so skipping UpdateWeight doesn't pay off
+    let periods = elapsed_periods(&last_updated, &halftime, &env.block).min(MAX_DECAY_PERIODS);
 
-    let weight = WEIGHT.load(deps.storage)?;
-    let diff = -(weight as i64) / 2; // why minus here?
+    let weight = state.weight;
+    let mut decayed_weight = weight;
+    for _ in 0..periods {
+        decayed_weight = decay_once(decayed_weight, config.decay)?;
+    }
+    let diff = weight_diff(weight, decayed_weight);
 
     // when force update => withdraw the share fund into proxy contract
-    let withdraw_msg = DistribtionExecMsg::Withdraw { weight, diff };
-    let withdraw_msg = WasmMsg::Execute {
-        contract_addr: config.distribution_contract.into_string(),
-        msg: to_json_binary(&withdraw_msg)?,
-        funds: vec![],
+    let targets = withdraw_targets(storage, &config)?;
+    let mut msgs = vec![];
+    for target in &targets {
+        let withdraw_msg = DistribtionExecMsg::Withdraw { weight, diff };
+        let withdraw_msg = WasmMsg::Execute {
+            contract_addr: target.to_string(),
+            msg: to_json_binary(&withdraw_msg)?,
+            funds: vec![],
+        };
+        msgs.push(match config.decay_funds {
+            DecayFunds::ReDistribute => {
+                SubMsg::reply_on_success(withdraw_msg, DECAY_WITHDRAW_REPLY_ID)
+            }
+            DecayFunds::KeepOnProxy => SubMsg::new(withdraw_msg),
+        });
+    }
+    if config.decay_funds == DecayFunds::ReDistribute {
+        DECAY_TARGETS.save(storage, &targets)?;
+    }
+
+    let new_weight = apply_diff(weight, diff)?;
+    let weight_event = set_weight(storage, env, &mut state, new_weight, "decay")?;
+    // otherwise every donation after the first halftime would decay again,
+    // the time into the next period still counts, as do periods above the cap
+    state.last_updated = (last_updated + halftime * periods)?;
+    STATE.save(storage, &state)?;
+
+    let event = Event::new("proxy-weight-update")
+        .add_attribute("old_weight", weight.to_string())
+        .add_attribute("decay", config.decay.to_string())
+        .add_attribute("periods", periods.to_string())
+        .add_attribute("new_weight", new_weight.to_string())
+        .add_attribute("diff", diff.to_string());
+
+    Ok(Some(WeightDecay {
+        msgs,
+        events: vec![event, weight_event],
+        new_weight,
+    }))
+}
+
+// rounded down, decay is at most one so the loss never exceeds the weight itself
+fn decay_once(weight: u64, decay: Decimal) -> Result<u64, ContractError> {
+    let lost = Uint128::from(weight).mul_floor(decay);
+    let decayed = Uint128::from(weight)
+        .checked_sub(lost)
+        .map_err(|_| ContractError::WeightOverflow)?;
+    u64::try_from(decayed.u128()).map_err(|_| ContractError::WeightOverflow)
+}
+
+// every counter and id goes up by one through here, a wrapped one would reuse ids
+pub fn increment(counter: u64) -> Result<u64, ContractError> {
+    counter.checked_add(1).ok_or(ContractError::CounterOverflow)
+}
+
+// weights are u64 on both sides, the diff between two of them always fits in i128
+fn weight_diff(old: u64, new: u64) -> Int128 {
+    Int128::from(new) - Int128::from(old)
+}
+
+// errors rather than saturating, a diff that doesn't fit means the caller's math is wrong
+fn apply_diff(weight: u64, diff: Int128) -> Result<u64, ContractError> {
+    let new_weight = Int128::from(weight)
+        .checked_add(diff)
+        .map_err(|_| ContractError::WeightOverflow)?;
+    u64::try_from(new_weight.i128()).map_err(|_| ContractError::WeightOverflow)
+}
+
+// where the block is in the unit of the halftime, time or height
+pub fn block_point(halftime: &Duration, block: &BlockInfo) -> Expiration {
+    match halftime {
+        Duration::Time(_) => Expiration::AtTime(block.time),
+        Duration::Height(_) => Expiration::AtHeight(block.height),
+    }
+}
+
+// whole halftimes since last_updated, SetHalftime keeps both in the same unit
+pub fn elapsed_periods(last_updated: &Expiration, halftime: &Duration, block: &BlockInfo) -> u64 {
+    let (elapsed, period) = match (last_updated, halftime) {
+        (Expiration::AtTime(time), Duration::Time(period)) => {
+            (block.time.seconds().saturating_sub(time.seconds()), *period)
+        }
+        (Expiration::AtHeight(height), Duration::Height(period)) => {
+            (block.height.saturating_sub(*height), *period)
+        }
+        _ => return 0,
     };
+    elapsed.checked_div(period).unwrap_or(1)
+}
 
-    WEIGHT.update(deps.storage, |weight: u64| -> StdResult<_> {
-        Ok((weight as i64 + diff) as u64)
-    })?;
+// every weight change goes through here, so indexers can follow it from the events alone
+// the caller saves the state
+pub fn set_weight(
+    storage: &mut dyn Storage,
+    env: &Env,
+    state: &mut State,
+    new_weight: u64,
+    reason: &str,
+) -> StdResult<Event> {
+    let old_weight = state.weight;
+    state.weight = new_weight;
+    WEIGHT_HISTORY.save(storage, env.block.height, &new_weight)?;
 
-    let resp = resp
-        .add_message(withdraw_msg)
-        .add_attribute("new weight", WEIGHT.load(deps.storage)?.to_string());
+    let event = Event::new("proxy-weight")
+        .add_attribute("old_weight", old_weight.to_string())
+        .add_attribute("new_weight", new_weight.to_string())
+        .add_attribute("reason", reason)
+        .add_attribute("block_time", env.block.time.seconds().to_string());
+    Ok(event)
+}
 
-    Ok(resp)
+// lazy counterpart of update_weight, decay messages go first so the
+// weight is up to date before the rest of the response is processed
+fn with_auto_decay(resp: Response, decay: Option<WeightDecay>) -> Response {
+    match decay {
+        Some(decay) => resp
+            .add_submessages(decay.msgs)
+            .add_events(decay.events)
+            .add_attribute("auto_weight_update", "yes"),
+        None => resp,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::Timestamp;
+
+    use super::*;
+
+    fn test_config() -> Config {
+        Config {
+            accepted_denoms: vec!["ORAI".to_owned()],
+            direct_part: Decimal::percent(10),
+            distribution_contracts: vec![(Addr::unchecked("distribution"), Decimal::one())],
+            membership_contract: Addr::unchecked("membership"),
+            is_closed: false,
+            cw20_address: None,
+            min_donation: Uint128::zero(),
+            direct_beneficiary: None,
+            goal: None,
+            protocol_fee: Decimal::zero(),
+            treasury: None,
+            tiers: vec![],
+            bucket_width: 86400,
+            matching_ratio: Decimal::one(),
+            referral_bps: 0,
+            burn_part: Decimal::zero(),
+            hook: None,
+            withdraw_cooldown: 0,
+            auto_withdraw_threshold: None,
+            keeper_fee_bps: 0,
+            update_policy: UpdatePolicy::Anyone,
+            decay: Decimal::percent(50),
+            max_weight: None,
+            decay_funds: DecayFunds::KeepOnProxy,
+            close_reason: None,
+            close_note: None,
+            closed_at: None,
+            approvers: vec![],
+            threshold: 0,
+            proposal_duration: 0,
+            metadata: None,
+            instantiated_by: None,
+            receipt_nft: None,
+            spend_limit: None,
+            skip_contract_check: false,
+        }
+    }
+
+    #[test]
+    fn apply_diff_below_zero() {
+        assert_eq!(
+            apply_diff(3, Int128::new(-5)),
+            Err(ContractError::WeightOverflow)
+        );
+        assert_eq!(apply_diff(3, Int128::new(-3)), Ok(0));
+    }
+
+    #[test]
+    fn apply_diff_near_max() {
+        assert_eq!(apply_diff(u64::MAX - 1, Int128::new(1)), Ok(u64::MAX));
+        assert_eq!(
+            apply_diff(u64::MAX, Int128::new(1)),
+            Err(ContractError::WeightOverflow)
+        );
+        assert_eq!(apply_diff(u64::MAX, weight_diff(u64::MAX, 0)), Ok(0));
+        assert_eq!(weight_diff(0, u64::MAX), Int128::from(u64::MAX));
+    }
+
+    #[test]
+    fn increment_near_max() {
+        assert_eq!(increment(0), Ok(1));
+        assert_eq!(increment(u64::MAX - 1), Ok(u64::MAX));
+        assert_eq!(increment(u64::MAX), Err(ContractError::CounterOverflow));
+    }
+
+    #[test]
+    fn decay_once_bounds() {
+        assert_eq!(decay_once(u64::MAX, Decimal::one()), Ok(0));
+        assert_eq!(
+            decay_once(u64::MAX, Decimal::percent(50)),
+            Ok(u64::MAX / 2 + 1)
+        );
+        // a single point never decays below one with a half
+        assert_eq!(decay_once(1, Decimal::percent(50)), Ok(1));
+        assert_eq!(decay_once(0, Decimal::one()), Ok(0));
+    }
+
+    #[test]
+    fn donation_counters_at_max() {
+        let mut deps = mock_dependencies();
+        let state = State {
+            weight: 0,
+            epoch_donations: 0,
+            lifetime_donations: u64::MAX,
+            total_donated: Uint128::zero(),
+            halftime: Duration::Time(86400),
+            last_updated: Expiration::AtTime(Timestamp::from_seconds(0)),
+        };
+        STATE.save(&mut deps.storage, &state).unwrap();
+        NEXT_DONATION_ID.save(&mut deps.storage, &0).unwrap();
+        UNIQUE_DONORS.save(&mut deps.storage, &0).unwrap();
+
+        let donor = Addr::unchecked("donor");
+        let err = record_donation(
+            &mut deps.storage,
+            &mock_env(),
+            &test_config(),
+            &donor,
+            Uint128::new(100),
+            "ORAI",
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::CounterOverflow);
+        // fails before anything is saved
+        assert_eq!(STATE.load(&deps.storage).unwrap(), state);
+    }
+
+    #[test]
+    fn withdraw_cooldown_past_max() {
+        let mut deps = mock_dependencies();
+        LAST_WITHDRAWN.save(&mut deps.storage, &1).unwrap();
+        let config = Config {
+            withdraw_cooldown: u64::MAX,
+            ..test_config()
+        };
+
+        let err = ensure_withdraw_cooldown(&deps.storage, &mock_env(), &config).unwrap_err();
+        assert_eq!(err, ContractError::TimeOverflow);
+    }
 }
@@ -0,0 +1,748 @@
+use cosmwasm_std::{coin, ensure, Deps, Env, Order, StdError, StdResult, Uint128};
+use cw_storage_plus::Bound;
+use cw_utils::Expiration;
+
+use super::exec::{
+    all_balances, ensure_min_donation, ensure_not_paused, plan_donation, reserved_with_pending,
+    spendable, vested, withdraw_targets, DonationPlan,
+};
+use crate::error::ContractError;
+use crate::msg::{
+    BalancesResponse, BlocklistResponse, ConfigResponse, DailyBucket, DailyStatsResponse,
+    DenomBalance, DistributionQueryMsg, DonationHistoryResponse, DonationRecordResponse,
+    DonationsResponse, Donor, DonorResponse, DonorTierResponse, DonorsResponse,
+    EstimatedWithdrawableResponse, GoalProgressResponse, HalftimeResponse, IsBlockedResponse,
+    LargestDonationResponse, LastUpdatedResponse, NextUpdateResponse, Operator, OperatorsResponse,
+    OwnerResponse, PausedResponse, PendingWithdrawalResponse, ProposedMember,
+    ProposedMembersResponse, ProxyMetadata, Referrer, ReferrerStatsResponse, ReferrersResponse,
+    RemoteDonor, RemoteDonorsResponse, SimulateDonationResponse, StatsResponse, StatusResponse,
+    StreamResponse, TopDonor, TopDonorsResponse, TotalDonatedResponse, WeightAtResponse,
+    WeightChange, WeightHistoryResponse, WeightResponse, WithdrawProposalResponse,
+    WithdrawProposalsResponse, WithdrawableResponse, WithdrawalHistoryResponse,
+    WithdrawalRecordResponse,
+};
+use crate::state::{
+    State, WithdrawProposal, BLOCKLIST, CONFIG, DAILY_STATS, DONATION_HISTORY, DONORS, DUST,
+    LARGEST_DONATION, OPERATORS, PAUSED, PENDING_WITHDRAWAL, PROPOSED_MEMBERS, REFERRERS,
+    REMOTE_DONORS, RETAINED, STATE, STREAM, UNIQUE_DONORS, WEIGHT_HISTORY, WITHDRAWAL_HISTORY,
+    WITHDRAW_PROPOSALS,
+};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+const MAX_DONORS_LIMIT: u32 = 50;
+const DONORS_SCAN_FACTOR: usize = 10; // donors scanned per one asked for when filtering
+
+pub fn config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        accepted_denoms: config.accepted_denoms,
+        direct_part: config.direct_part,
+        distribution_contracts: config.distribution_contracts,
+        membership_contract: config.membership_contract,
+        is_closed: config.is_closed,
+    })
+}
+
+pub fn owner(deps: Deps) -> StdResult<OwnerResponse> {
+    let owner = cw_ownable::get_ownership(deps.storage)?.owner;
+    Ok(OwnerResponse { owner })
+}
+
+pub fn weight(deps: Deps) -> StdResult<WeightResponse> {
+    let weight = STATE.load(deps.storage)?.weight;
+    Ok(WeightResponse { weight })
+}
+
+pub fn donations(deps: Deps) -> StdResult<DonationsResponse> {
+    let donations = STATE.load(deps.storage)?.epoch_donations;
+    Ok(DonationsResponse { donations })
+}
+
+pub fn halftime(deps: Deps) -> StdResult<HalftimeResponse> {
+    let halftime = STATE.load(deps.storage)?.halftime;
+    Ok(HalftimeResponse { halftime })
+}
+
+pub fn last_updated(deps: Deps) -> StdResult<LastUpdatedResponse> {
+    let last_updated = STATE.load(deps.storage)?.last_updated;
+    Ok(LastUpdatedResponse { last_updated })
+}
+
+pub fn estimated_withdrawable(
+    deps: Deps,
+    env: Env,
+    denom: Option<String>,
+) -> StdResult<EstimatedWithdrawableResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let denom = denom.unwrap_or_else(|| config.accepted_denoms[0].clone());
+
+    let mut from_distribution = Uint128::zero();
+    for target in withdraw_targets(deps.storage, &config)? {
+        let query_msg = DistributionQueryMsg::Withdrawable {
+            proxy: env.contract.address.to_string(),
+            denom: denom.clone(),
+        };
+        let resp: WithdrawableResponse = deps
+            .querier
+            .query_wasm_smart(&target, &query_msg)
+            .map_err(|err| {
+                StdError::generic_err(format!(
+                    "Distribution contract {} can't estimate the withdrawable amount: {}",
+                    target, err
+                ))
+            })?;
+        from_distribution += resp.amount;
+    }
+
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &denom)?
+        .amount;
+    let direct_on_proxy = spendable(deps.storage, &config, &denom, balance)?;
+
+    Ok(EstimatedWithdrawableResponse {
+        from_distribution,
+        direct_on_proxy,
+        total: from_distribution + direct_on_proxy,
+    })
+}
+
+pub fn pending_withdrawal(deps: Deps) -> StdResult<Option<PendingWithdrawalResponse>> {
+    let pending = PENDING_WITHDRAWAL.may_load(deps.storage)?;
+    Ok(pending.map(|pending| PendingWithdrawalResponse {
+        receivers: pending.receivers,
+        amount: pending.amount,
+        denom: pending.denom,
+    }))
+}
+
+pub fn total_donated(deps: Deps) -> StdResult<TotalDonatedResponse> {
+    let total = STATE.load(deps.storage)?.total_donated;
+    Ok(TotalDonatedResponse { total })
+}
+
+pub fn donor(deps: Deps, addr: String) -> StdResult<DonorResponse> {
+    let addr = deps
+        .api
+        .addr_validate(&addr)
+        .map_err(|err| StdError::generic_err(format!("Invalid donor address {}: {}", addr, err)))?;
+    // never donated => zeros rather than an error
+    let donor = DONORS.may_load(deps.storage, &addr)?.unwrap_or_default();
+
+    Ok(DonorResponse {
+        total: donor.total,
+        count: donor.count,
+        last_donated: donor.last_donated,
+    })
+}
+
+pub fn simulate_donation(deps: Deps, amount: Uint128) -> StdResult<SimulateDonationResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let denom = &config.accepted_denoms[0];
+    let DonationPlan {
+        split,
+        dust_carried,
+        matched,
+    } = plan_donation(deps.storage, &config, denom, amount, true)?;
+
+    // same checks as donate, in the same order
+    let rejected = ensure_not_paused(deps.storage)
+        .and_then(|_| {
+            ensure!(!config.is_closed, ContractError::ContractClosed);
+            ensure_min_donation(&config, amount)
+        })
+        .err()
+        .map(|err| err.to_string());
+
+    Ok(SimulateDonationResponse {
+        protocol_fee: split.fee,
+        burned: split.burn,
+        direct: split.direct,
+        to_distribute: split.to_distribute,
+        dust: split.dust,
+        dust_carried,
+        matched,
+        rejected,
+    })
+}
+
+pub fn donors(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    inactive_since: Option<u64>,
+) -> StdResult<DonorsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_DONORS_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| {
+            deps.api.addr_validate(&addr).map_err(|err| {
+                StdError::generic_err(format!("Invalid start_after address {}: {}", addr, err))
+            })
+        })
+        .transpose()?;
+    let min = start_after.as_ref().map(Bound::exclusive);
+
+    // filtered before the limit, the scan stops at the page or at max_scan donors, whichever comes first
+    // donations from before last_donated was kept count as old
+    let max_scan = limit * DONORS_SCAN_FACTOR;
+    let mut donors = vec![];
+    let mut last = start_after.clone();
+    let mut next_start_after = None;
+    for (scanned, donor) in DONORS
+        .range(deps.storage, min, None, Order::Ascending)
+        .enumerate()
+    {
+        // there are donors left for the next page
+        if donors.len() == limit || scanned == max_scan {
+            next_start_after = last;
+            break;
+        }
+        let (addr, donor) = donor?;
+        let inactive =
+            inactive_since.is_none_or(|cutoff| donor.last_donated.is_none_or(|last| last < cutoff));
+        if inactive {
+            donors.push(Donor {
+                addr: addr.clone(),
+                total: donor.total,
+                count: donor.count,
+                last_donated: donor.last_donated,
+            });
+        }
+        last = Some(addr);
+    }
+
+    Ok(DonorsResponse {
+        donors,
+        next_start_after,
+    })
+}
+
+pub fn remote_donors(
+    deps: Deps,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> StdResult<RemoteDonorsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_DONORS_LIMIT) as usize;
+    let min = start_after
+        .as_ref()
+        .map(|(chain, donor)| Bound::exclusive((chain.as_str(), donor.as_str())));
+
+    let donors = REMOTE_DONORS
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|donor| -> StdResult<_> {
+            let ((chain, donor), info) = donor?;
+            Ok(RemoteDonor {
+                chain,
+                donor,
+                total: info.total,
+                count: info.count,
+                last_donated: info.last_donated,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(RemoteDonorsResponse { donors })
+}
+
+pub fn top_donors(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TopDonorsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| -> StdResult<_> {
+            let addr = deps.api.addr_validate(&addr)?;
+            let donor = DONORS.may_load(deps.storage, &addr)?.ok_or_else(|| {
+                StdError::generic_err(format!("Invalid start_after donor {}", addr))
+            })?;
+            Ok((donor.total.u128(), addr))
+        })
+        .transpose()?;
+    // descending, so the page starts below the last donor seen
+    let max = start_after
+        .as_ref()
+        .map(|(total, addr)| Bound::exclusive((*total, addr)));
+
+    let donors = DONORS
+        .idx
+        .amount
+        .range(deps.storage, None, max, Order::Descending)
+        .take(limit)
+        .map(|donor| -> StdResult<_> {
+            let (addr, donor) = donor?;
+            Ok(TopDonor {
+                addr,
+                total: donor.total,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(TopDonorsResponse { donors })
+}
+
+pub fn stats(deps: Deps) -> StdResult<StatsResponse> {
+    let state = STATE.load(deps.storage)?;
+    Ok(StatsResponse {
+        donations: state.epoch_donations,
+        lifetime_donations: state.lifetime_donations,
+        unique_donors: UNIQUE_DONORS.load(deps.storage)?,
+        total_donated: state.total_donated,
+        dust: DUST
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|dust| -> StdResult<_> {
+                let (denom, amount) = dust?;
+                Ok(coin(amount.u128(), denom))
+            })
+            .collect::<StdResult<_>>()?,
+        retained: RETAINED
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|retained| -> StdResult<_> {
+                let (denom, amount) = retained?;
+                Ok(coin(amount.u128(), denom))
+            })
+            .collect::<StdResult<_>>()?,
+    })
+}
+
+pub fn daily_stats(deps: Deps, from_day: u64, to_day: u64) -> StdResult<DailyStatsResponse> {
+    let buckets = DAILY_STATS
+        .range(
+            deps.storage,
+            Some(Bound::inclusive(from_day)),
+            Some(Bound::inclusive(to_day)),
+            Order::Ascending,
+        )
+        .map(|bucket| -> StdResult<_> {
+            let (day, bucket) = bucket?;
+            Ok(DailyBucket {
+                day,
+                count: bucket.count,
+                amount: bucket.amount,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(DailyStatsResponse { buckets })
+}
+
+pub fn largest_donation(deps: Deps) -> StdResult<Option<LargestDonationResponse>> {
+    let largest = LARGEST_DONATION.may_load(deps.storage)?;
+    Ok(
+        largest.map(|(donor, amount, time)| LargestDonationResponse {
+            donor,
+            amount,
+            time,
+        }),
+    )
+}
+
+pub fn goal_progress(deps: Deps) -> StdResult<GoalProgressResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(GoalProgressResponse {
+        raised: STATE.load(deps.storage)?.total_donated,
+        goal: config.goal,
+    })
+}
+
+pub fn donor_tier(deps: Deps, addr: String) -> StdResult<DonorTierResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let donor = DONORS.may_load(deps.storage, &addr)?.unwrap_or_default();
+    Ok(DonorTierResponse { tier: donor.tier })
+}
+
+pub fn referrer_stats(deps: Deps, addr: String) -> StdResult<ReferrerStatsResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let referrer = REFERRERS.may_load(deps.storage, &addr)?.unwrap_or_default();
+
+    Ok(ReferrerStatsResponse {
+        count: referrer.count,
+        total: referrer.total,
+        earned: referrer.earned,
+    })
+}
+
+pub fn referrers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ReferrersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let min = start_after.as_ref().map(Bound::exclusive);
+
+    let referrers = REFERRERS
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|referrer| -> StdResult<_> {
+            let (addr, referrer) = referrer?;
+            Ok(Referrer {
+                addr,
+                count: referrer.count,
+                total: referrer.total,
+                earned: referrer.earned,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(ReferrersResponse { referrers })
+}
+
+pub fn is_blocked(deps: Deps, addr: String) -> StdResult<IsBlockedResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let is_blocked = BLOCKLIST.has(deps.storage, &addr);
+    Ok(IsBlockedResponse { is_blocked })
+}
+
+pub fn blocklist(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BlocklistResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let min = start_after.as_ref().map(Bound::exclusive);
+
+    let blocked = BLOCKLIST
+        .keys(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+
+    Ok(BlocklistResponse { blocked })
+}
+
+pub fn operators(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OperatorsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let min = start_after.as_ref().map(Bound::exclusive);
+
+    let operators = OPERATORS
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|operator| -> StdResult<_> {
+            let (addr, perms) = operator?;
+            Ok(Operator {
+                addr,
+                can_withdraw: perms.can_withdraw,
+                can_propose: perms.can_propose,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(OperatorsResponse { operators })
+}
+
+pub fn donation_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<DonationHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // descending => start_after is the upper bound
+    let max = start_after.map(Bound::exclusive);
+
+    let donations = DONATION_HISTORY
+        .range(deps.storage, None, max, Order::Descending)
+        .take(limit)
+        .map(|record| -> StdResult<_> {
+            let (id, record) = record?;
+            Ok(DonationRecordResponse {
+                id,
+                donor: record.donor,
+                amount: record.amount,
+                denom: record.denom,
+                memo: record.memo,
+                time: record.time,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(DonationHistoryResponse { donations })
+}
+
+pub fn withdrawal_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<WithdrawalHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // descending => start_after is the upper bound
+    let max = start_after.map(Bound::exclusive);
+
+    let withdrawals = WITHDRAWAL_HISTORY
+        .range(deps.storage, None, max, Order::Descending)
+        .take(limit)
+        .map(|record| -> StdResult<_> {
+            let (id, record) = record?;
+            Ok(WithdrawalRecordResponse {
+                id,
+                receiver: record.receiver,
+                amount: record.amount,
+                denom: record.denom,
+                block_time: record.block_time,
+                epoch_donations: record.epoch_donations,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(WithdrawalHistoryResponse { withdrawals })
+}
+
+pub fn stream(deps: Deps, env: Env) -> StdResult<Option<StreamResponse>> {
+    let stream = STREAM.may_load(deps.storage)?;
+    Ok(stream.map(|stream| StreamResponse {
+        claimable: vested(&stream, env.block.time.seconds()) - stream.claimed,
+        receiver: stream.receiver,
+        denom: stream.denom,
+        total: stream.total,
+        claimed: stream.claimed,
+        start: stream.start,
+        end: stream.end,
+    }))
+}
+
+pub fn next_update(deps: Deps, env: Env) -> StdResult<NextUpdateResponse> {
+    let State {
+        last_updated,
+        halftime,
+        ..
+    } = STATE.load(deps.storage)?;
+    let next_eligible_at = (last_updated + halftime)?;
+    let remaining = match next_eligible_at {
+        Expiration::AtTime(time) => time.seconds().saturating_sub(env.block.time.seconds()),
+        Expiration::AtHeight(height) => height.saturating_sub(env.block.height),
+        Expiration::Never {} => 0,
+    };
+    Ok(NextUpdateResponse {
+        last_updated,
+        halftime,
+        next_eligible_at,
+        remaining,
+    })
+}
+
+pub fn balances(deps: Deps, env: Env) -> StdResult<BalancesResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let balances = all_balances(&deps.querier, &env.contract.address)?
+        .into_iter()
+        .map(|balance| -> StdResult<_> {
+            // reservations can't exceed what is actually held
+            let reserved =
+                reserved_with_pending(deps.storage, &config, &balance.denom)?.min(balance.amount);
+            Ok(DenomBalance {
+                denom: balance.denom,
+                total_balance: balance.amount,
+                reserved,
+                spendable: balance.amount - reserved,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(BalancesResponse { balances })
+}
+
+pub fn proposed_members(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ProposedMembersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let min = start_after.as_ref().map(Bound::exclusive);
+
+    let members = PROPOSED_MEMBERS
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|member| -> StdResult<_> {
+            let (candidate, proxy) = member?;
+            Ok(ProposedMember { candidate, proxy })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(ProposedMembersResponse { members })
+}
+
+pub fn metadata(deps: Deps) -> StdResult<Option<ProxyMetadata>> {
+    Ok(CONFIG.load(deps.storage)?.metadata)
+}
+
+pub fn status(deps: Deps) -> StdResult<StatusResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+    Ok(StatusResponse {
+        owner: cw_ownable::get_ownership(deps.storage)?.owner,
+        weight: state.weight,
+        donations: state.epoch_donations,
+        lifetime_donations: state.lifetime_donations,
+        total_donated: state.total_donated,
+        unique_donors: UNIQUE_DONORS.load(deps.storage)?,
+        halftime: state.halftime,
+        last_updated: state.last_updated,
+        is_closed: config.is_closed,
+        reason: config.close_note,
+        closed_at: config.closed_at,
+        paused: PAUSED.may_load(deps.storage)?.unwrap_or(false),
+        withdrawal_pending: PENDING_WITHDRAWAL.exists(deps.storage),
+    })
+}
+
+pub fn paused(deps: Deps) -> StdResult<PausedResponse> {
+    let paused = PAUSED.may_load(deps.storage)?.unwrap_or(false);
+    Ok(PausedResponse { paused })
+}
+
+pub fn weight_at(deps: Deps, height: u64) -> StdResult<WeightAtResponse> {
+    let weight = WEIGHT_HISTORY
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::inclusive(height)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?
+        .map(|(_, weight)| weight);
+    Ok(WeightAtResponse { weight })
+}
+
+pub fn weight_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<WeightHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // descending => start_after is the upper bound
+    let max = start_after.map(Bound::exclusive);
+
+    let weights = WEIGHT_HISTORY
+        .range(deps.storage, None, max, Order::Descending)
+        .take(limit)
+        .map(|change| -> StdResult<_> {
+            let (height, weight) = change?;
+            Ok(WeightChange { height, weight })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(WeightHistoryResponse { weights })
+}
+
+fn proposal_response(env: &Env, id: u64, proposal: WithdrawProposal) -> WithdrawProposalResponse {
+    WithdrawProposalResponse {
+        id,
+        receiver: proposal.receiver,
+        amount: proposal.amount,
+        approvals: proposal.approvals,
+        expires: proposal.expires,
+        expired: env.block.time.seconds() >= proposal.expires,
+    }
+}
+
+pub fn withdraw_proposal(
+    deps: Deps,
+    env: Env,
+    id: u64,
+) -> StdResult<Option<WithdrawProposalResponse>> {
+    let proposal = WITHDRAW_PROPOSALS.may_load(deps.storage, id)?;
+    Ok(proposal.map(|proposal| proposal_response(&env, id, proposal)))
+}
+
+pub fn withdraw_proposals(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<WithdrawProposalsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(Bound::exclusive);
+
+    let proposals = WITHDRAW_PROPOSALS
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|proposal| -> StdResult<_> {
+            let (id, proposal) = proposal?;
+            Ok(proposal_response(&env, id, proposal))
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(WithdrawProposalsResponse { proposals })
+}
+
+#[cfg(test)]
+mod tests {
+    use common::multitest::mock_dependencies;
+    use cosmwasm_std::testing::{message_info, mock_env};
+    use cosmwasm_std::{from_json, Addr, Decimal};
+    use cw_utils::Duration;
+
+    use super::*;
+    use crate::msg::{InstantiateMsg, QueryMsg};
+
+    // every response deserializes from what the entry point returns
+    #[test]
+    fn proxy_state_responses() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg: InstantiateMsg = from_json(
+            br#"{
+                "owner": "owner",
+                "weight": 10,
+                "accepted_denoms": ["ORAI"],
+                "direct_part": "0.1",
+                "distribution_contracts": [["distribution", "1"]],
+                "membership_contract": "membership",
+                "halftime": { "time": 3600 },
+                "skip_contract_check": true
+            }"#,
+        )
+        .unwrap();
+        crate::instantiate(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&Addr::unchecked("owner"), &[]),
+            msg,
+        )
+        .unwrap();
+        let query = |msg| crate::query(deps.as_ref(), env.clone(), msg).unwrap();
+
+        let config: ConfigResponse = from_json(query(QueryMsg::Config {})).unwrap();
+        assert_eq!(
+            config,
+            ConfigResponse {
+                accepted_denoms: vec!["ORAI".to_owned()],
+                direct_part: Decimal::percent(10),
+                distribution_contracts: vec![(Addr::unchecked("distribution"), Decimal::one())],
+                membership_contract: Addr::unchecked("membership"),
+                is_closed: false,
+            }
+        );
+
+        let owner: OwnerResponse = from_json(query(QueryMsg::Owner {})).unwrap();
+        assert_eq!(owner.owner, Some(Addr::unchecked("owner")));
+        let weight: WeightResponse = from_json(query(QueryMsg::Weight {})).unwrap();
+        assert_eq!(weight.weight, 10);
+        let donations: DonationsResponse = from_json(query(QueryMsg::Donations {})).unwrap();
+        assert_eq!(donations.donations, 0);
+        let halftime: HalftimeResponse = from_json(query(QueryMsg::Halftime {})).unwrap();
+        assert_eq!(halftime.halftime, Duration::Time(3600));
+        let last_updated: LastUpdatedResponse = from_json(query(QueryMsg::LastUpdated {})).unwrap();
+        assert_eq!(
+            last_updated.last_updated,
+            Expiration::AtTime(env.block.time)
+        );
+    }
+}
@@ -1,50 +1,608 @@
-use cosmwasm_std::{coins, BankMsg, DepsMut, Env, Response, StdError, SubMsgResponse};
+use common::msg::{ProposeMemberData, WithdrawResponse};
+use cosmwasm_std::{
+    coin, coins, ensure, from_json, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg,
+    Decimal, DepsMut, Env, Event, IbcMsg, IbcTimeout, Response, StdError, StdResult, Storage,
+    SubMsgResponse, Uint128, WasmMsg,
+};
+use cw_utils::parse_execute_response_data;
 
+use super::exec::{
+    all_balances, block_point, increment, reserved_balance, retain, set_weight, spend,
+};
+use crate::state::{WithdrawalData, PROPOSED_MEMBERS, WEIGHTED_TARGETS};
 use crate::{
     error::ContractError,
-    state::{CONFIG, PENDING_WITHDRAWAL},
+    msg::{DistribtionExecMsg, IbcWithdraw},
+    state::{
+        Stream, WithdrawalRecord, CONFIG, DECAY_TARGETS, LAST_WITHDRAWN, NEXT_WITHDRAWAL_ID,
+        PENDING_WITHDRAWAL, RETAINED, STATE, STREAM, WITHDRAWAL_HISTORY,
+    },
 };
 
 // distribution contract send reply to proxy contract when it finish in handle the withdraw message sent from proxy contract (that mean the distribution contract has already send token to proxy contract)
 
 // in the flow this is the last step that proxy contract send token to receiver
-pub fn withdraw(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
-    // this is the use of PENDING_WITHDRAWAL
+pub fn withdraw(
+    deps: DepsMut,
+    env: Env,
+    payload: Binary,
+    reply: Result<SubMsgResponse, String>,
+) -> Result<Response, ContractError> {
     // when the execution entry point finished (withdraw handler proxy contract) and message is processed as part of transaction (in this case is the withdraw message sent from proxy contract to distribution contract) then we lost all the information that we pass with withdraw message to proxy contract
-    let withdraw_info = PENDING_WITHDRAWAL.load(deps.storage)?;
+    // so every withdraw submessage carries it as payload and gets it back here
+    let mut withdraw_info: WithdrawalData = from_json(payload)?;
+
+    let mut resp = Response::new();
+    if let Some(target) = &withdraw_info.target {
+        resp = resp.add_attribute("withdrawn_from", target.as_str());
+    }
+    let mut pending = PENDING_WITHDRAWAL.load(deps.storage)?;
+    match reply {
+        Ok(response) => {
+            let withdrawn = withdrawn_amount(
+                response,
+                withdraw_info.target.as_ref(),
+                &withdraw_info.denom,
+            )?;
+            pending.received = pending
+                .received
+                .checked_add(withdrawn)
+                .map_err(StdError::from)?;
+            resp = resp.add_attribute("withdrawn", withdrawn.to_string());
+        }
+        // with a single distribution contract nothing was committed yet, the withdraw just didn't happen
+        Err(err) if pending.pending_replies == 1 => {
+            PENDING_WITHDRAWAL.remove(deps.storage);
+            return Ok(resp.add_attribute("withdraw_failed", err));
+        }
+        // the others already moved the weight, only reverting everything keeps both sides in line
+        Err(err) => {
+            return Err(ContractError::SubMsgFailed {
+                submsg: "withdraw".to_owned(),
+                error: err,
+            });
+        }
+    }
 
+    // wait until every distribution contract has replied
+    if withdraw_info.pending_replies > 0 {
+        PENDING_WITHDRAWAL.save(deps.storage, &pending)?;
+        return Ok(resp);
+    }
+
+    // last reply, nothing is pending anymore
+    PENDING_WITHDRAWAL.remove(deps.storage);
+    withdraw_info.received = pending.received;
+
+    // distribution pays every accepted denom owed at once, anything else means it's misconfigured,
+    // rejecting here reverts the distribution withdraw as well
     let config = CONFIG.load(deps.storage)?;
+    let balances = all_balances(&deps.querier, &env.contract.address)?;
+    for coin in balances {
+        let before = withdraw_info
+            .balances_before
+            .iter()
+            .find(|before| before.denom == coin.denom)
+            .map(|before| before.amount)
+            .unwrap_or_default();
+        // the other accepted denoms that came in are the owner's too, the next withdraw in them pays them
+        if config.accepted_denoms.contains(&coin.denom) {
+            if coin.denom != withdraw_info.denom && coin.amount > before {
+                let received = coin.amount - before;
+                RETAINED.update(deps.storage, &coin.denom, |retained| -> StdResult<_> {
+                    Ok(retained.unwrap_or_default() + received)
+                })?;
+            }
+            continue;
+        }
+        ensure!(
+            coin.amount <= before,
+            ContractError::UnexpectedDenom {
+                expected: withdraw_info.denom,
+                got: coin.denom
+            }
+        );
+    }
+
+    let now = env.block.time.seconds();
+    let mut state = STATE.load(deps.storage)?;
+    let weight_event = set_weight(
+        deps.storage,
+        &env,
+        &mut state,
+        withdraw_info.new_weight,
+        "withdraw",
+    )?;
+    resp = resp.add_event(weight_event);
+    // new_weight already counts the closed epoch, the next one starts empty
+    state.epoch_donations = 0;
+    state.last_updated = block_point(&state.halftime, &env.block);
+    STATE.save(deps.storage, &state)?;
+
+    // everything owed by the old contracts came in with this withdraw
+    let mut config = config;
+    if let Some(distribution) = withdraw_info.new_distribution.take() {
+        config.distribution_contracts = vec![(distribution.clone(), Decimal::one())];
+        CONFIG.save(deps.storage, &config)?;
+        WEIGHTED_TARGETS.clear(deps.storage);
+        resp = resp.add_attribute("distribution_switched", distribution.as_str());
+    }
+    // the cooldown guards the owner's own withdraws only
+    if withdraw_info.keeper.is_none() {
+        LAST_WITHDRAWN.save(deps.storage, &now)?;
+    }
 
-    // remember the distribution contract may has already send distributed token of owner to proxy contract => so we just need query balance of proxy contract
-    // balance of proxy contract consists of 2 part
+    // remember the distribution contract may has already send distributed token of owner to proxy contract
+    // => what its balance grew by since the withdraw was sent is exactly what came in
     let total_amount = deps
         .querier
-        .query_balance(env.contract.address, &config.denom)?;
-
-    let amount = withdraw_info.amount.unwrap_or(total_amount.amount);
+        .query_balance(&env.contract.address, &withdraw_info.denom)?;
+    let before = withdraw_info
+        .balances_before
+        .iter()
+        .find(|before| before.denom == withdraw_info.denom)
+        .map(|before| before.amount)
+        .unwrap_or_default();
+    let delta = total_amount.amount.saturating_sub(before);
+    ensure!(
+        withdraw_info.received <= delta,
+        ContractError::WithdrawMismatch {
+            received: withdraw_info.received,
+            available: delta
+        }
+    );
+    if delta.is_zero() {
+        resp = resp.add_attribute("nothing_received", &withdraw_info.denom);
+    }
 
-    // send token to receiver
+    // whatever a previous capped withdraw retained is part of it again,
+    // as long as it is still on the proxy
+    let reserved = reserved_balance(deps.storage, &config, &withdraw_info.denom)?;
+    let retained = RETAINED
+        .may_load(deps.storage, &withdraw_info.denom)?
+        .unwrap_or_default();
+    let available = (delta + retained).min(total_amount.amount.saturating_sub(reserved));
 
-    let bank_msg = BankMsg::Send {
-        to_address: withdraw_info.receiver.into_string(),
-        amount: coins(amount.u128(), &config.denom),
+    // amount and the spend limit cap the payout, the rest stays on the proxy for the next withdraw
+    let amount = match withdraw_info.amount {
+        Some(amount) => amount.min(available),
+        None => available,
     };
+    let amount = spend(deps.storage, &config, &withdraw_info.denom, now, amount)?;
+    let retained = available - amount;
+    retain(deps.storage, &withdraw_info.denom, retained)?;
+
+    resp = resp
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("retained", retained.to_string());
+
+    // keeper fee comes out of the payout before it is split
+    let mut amount = amount;
+    if let Some(keeper) = withdraw_info.keeper {
+        let keeper_fee = amount.mul_floor(Decimal::bps(config.keeper_fee_bps));
+        amount -= keeper_fee;
+        resp = resp.add_attribute("keeper_fee", keeper_fee.to_string());
+        if !keeper_fee.is_zero() {
+            let bank_msg = BankMsg::Send {
+                to_address: keeper.into_string(),
+                amount: coins(keeper_fee.u128(), &withdraw_info.denom),
+            };
+            resp = resp.add_message(bank_msg);
+        }
+    }
+
+    // nothing to pay out isn't an error, the weight was still updated
+    if amount.is_zero() {
+        return Ok(resp);
+    }
+
+    // stays on the proxy, the receiver claims it as it vests
+    if let Some(duration) = withdraw_info.stream_duration {
+        let end = now
+            .checked_add(duration)
+            .ok_or(ContractError::TimeOverflow)?;
+        let (receiver, _) = withdraw_info.receivers.swap_remove(0);
+        record_withdrawal(
+            deps.storage,
+            &WithdrawalRecord {
+                receiver: receiver.clone(),
+                amount,
+                denom: withdraw_info.denom.clone(),
+                block_time: now,
+                epoch_donations: withdraw_info.previous_donations,
+            },
+        )?;
+        STREAM.save(
+            deps.storage,
+            &Stream {
+                receiver,
+                denom: withdraw_info.denom,
+                total: amount,
+                claimed: Uint128::zero(),
+                start: now,
+                end,
+            },
+        )?;
+        return Ok(resp.add_attribute("stream_end", end.to_string()));
+    }
 
-    let resp = Response::new()
-        .add_message(bank_msg)
-        .add_attribute("amount", amount.to_string());
+    // send token to receivers
+    for (receiver, part) in split_payout(amount, withdraw_info.receivers) {
+        if part.is_zero() {
+            continue;
+        }
+        // the history shows where the funds went, the remote address for IBC
+        let recorded = match &withdraw_info.ibc {
+            Some(ibc) => Addr::unchecked(&ibc.to_address),
+            None => receiver.clone(),
+        };
+        record_withdrawal(
+            deps.storage,
+            &WithdrawalRecord {
+                receiver: recorded,
+                amount: part,
+                denom: withdraw_info.denom.clone(),
+                block_time: now,
+                epoch_donations: withdraw_info.previous_donations,
+            },
+        )?;
+
+        // not a submessage, a failing receiver reverts the whole withdraw
+        resp = resp.add_message(payout_msg(
+            &env,
+            withdraw_info.ibc.as_ref(),
+            withdraw_info.receiver_msg.as_ref(),
+            receiver,
+            coin(part.u128(), &withdraw_info.denom),
+        ));
+    }
 
     Ok(resp)
 }
 
-// forward data get from reply of membership contract
-pub fn propose_member(reply: Result<SubMsgResponse, String>) -> Result<Response, ContractError> {
+// a bank send, the receiver message executed with the funds, or an ICS-20 transfer
+fn payout_msg(
+    env: &Env,
+    ibc: Option<&IbcWithdraw>,
+    receiver_msg: Option<&Binary>,
+    receiver: Addr,
+    funds: Coin,
+) -> CosmosMsg {
+    match (ibc, receiver_msg) {
+        (Some(ibc), _) => IbcMsg::Transfer {
+            channel_id: ibc.channel_id.clone(),
+            to_address: ibc.to_address.clone(),
+            amount: funds,
+            timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(ibc.timeout_seconds)),
+            memo: None,
+        }
+        .into(),
+        (None, Some(msg)) => WasmMsg::Execute {
+            contract_addr: receiver.into_string(),
+            msg: msg.clone(),
+            funds: vec![funds],
+        }
+        .into(),
+        (None, None) => BankMsg::Send {
+            to_address: receiver.into_string(),
+            amount: vec![funds],
+        }
+        .into(),
+    }
+}
+
+fn record_withdrawal(
+    storage: &mut dyn Storage,
+    record: &WithdrawalRecord,
+) -> Result<(), ContractError> {
+    let id = NEXT_WITHDRAWAL_ID.load(storage)?;
+    WITHDRAWAL_HISTORY.save(storage, id, record)?;
+    NEXT_WITHDRAWAL_ID.save(storage, &increment(id)?)?;
+    Ok(())
+}
+
+// every receiver gets its share rounded down, the first one also gets the rounding remainder
+fn split_payout(amount: Uint128, receivers: Vec<(Addr, Decimal)>) -> Vec<(Addr, Uint128)> {
+    let mut parts: Vec<_> = receivers
+        .into_iter()
+        .map(|(receiver, share)| (receiver, amount.mul_floor(share)))
+        .collect();
+    let paid: Uint128 = parts.iter().map(|(_, part)| part).sum();
+    parts[0].1 += amount - paid;
+    parts
+}
+
+// distribution reports what it paid out as WithdrawResponse data,
+// contracts which don't set data emit it as amount and denom attributes of their wasm event
+fn withdrawn_amount(
+    response: SubMsgResponse,
+    target: Option<&Addr>,
+    denom: &str,
+) -> Result<Uint128, ContractError> {
+    let events = response.events.clone();
+    if let Some(withdrawn) = withdrawn_assets(response_data(response))? {
+        let amount = withdrawn
+            .into_iter()
+            .filter(|withdrawn| withdrawn.denom == denom)
+            .map(|withdrawn| withdrawn.amount)
+            .sum();
+        return Ok(amount);
+    }
+
+    withdrawn_from_events(&events, target, denom).ok_or_else(|| {
+        let seen: Vec<_> = events.iter().map(event_summary).collect();
+        ContractError::UnreportedWithdraw {
+            events: seen.join(", "),
+        }
+    })
+}
+
+// sums every amount/denom attribute pair of the target's wasm events paid in denom,
+// None if no such pair was emitted at all
+fn withdrawn_from_events(events: &[Event], target: Option<&Addr>, denom: &str) -> Option<Uint128> {
+    let mut withdrawn = None;
+    for event in events {
+        if event.ty != "wasm" && !event.ty.starts_with("wasm-") {
+            continue;
+        }
+        let emitter = event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "_contract_address")
+            .map(|attr| attr.value.as_str());
+        if target.is_some_and(|target| emitter != Some(target.as_str())) {
+            continue;
+        }
+
+        // an amount is paired with the denom following it, or preceding it
+        let (mut amount, mut pair_denom) = (None, None);
+        for attr in &event.attributes {
+            match attr.key.as_str() {
+                "amount" => amount = attr.value.parse::<Uint128>().ok(),
+                "denom" => pair_denom = Some(attr.value.as_str()),
+                _ => continue,
+            }
+            if let (Some(paid), Some(paid_denom)) = (amount, pair_denom) {
+                let total = withdrawn.get_or_insert(Uint128::zero());
+                if paid_denom == denom {
+                    *total += paid;
+                }
+                (amount, pair_denom) = (None, None);
+            }
+        }
+    }
+    withdrawn
+}
+
+// event type and the keys it came with, enough to tell what the distribution emitted
+fn event_summary(event: &Event) -> String {
+    let keys: Vec<_> = event
+        .attributes
+        .iter()
+        .map(|attr| attr.key.as_str())
+        .filter(|key| *key != "_contract_address")
+        .collect();
+    format!("{}[{}]", event.ty, keys.join(","))
+}
+
+// since CosmWasm 2 the execute response is in msg_responses, data is deprecated
+fn response_data(response: SubMsgResponse) -> Option<Binary> {
+    response
+        .msg_responses
+        .into_iter()
+        .next()
+        .map(|msg| msg.value)
+        .filter(|value| !value.is_empty())
+}
+
+fn withdrawn_assets(data: Option<Binary>) -> Result<Option<Vec<WithdrawResponse>>, ContractError> {
+    let data = match data {
+        Some(data) => parse_execute_response_data(&data)?.data,
+        None => None,
+    };
+    data.map(from_json).transpose().map_err(Into::into)
+}
+
+// share withdrawn by the weight decay goes straight back to the distribution contract it came from,
+// only native denoms are forwarded and nothing is when the distribution reports no data
+pub fn decay_withdraw(
+    deps: DepsMut,
+    reply: Result<SubMsgResponse, String>,
+) -> Result<Response, ContractError> {
     let response = reply.map_err(StdError::generic_err)?;
-    // can do like the membership contract or simple like this
-    if let Some(data) = response.data {
-        let resp = Response::new().set_data(data);
-        Ok(resp)
+
+    // replies come in the order the withdraws were sent
+    let mut targets = DECAY_TARGETS.load(deps.storage)?;
+    let target = targets.remove(0);
+    if targets.is_empty() {
+        DECAY_TARGETS.remove(deps.storage);
     } else {
-        Ok(Response::new())
+        DECAY_TARGETS.save(deps.storage, &targets)?;
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let funds: Vec<_> = withdrawn_assets(response_data(response))?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|withdrawn| {
+            !withdrawn.amount.is_zero() && config.accepted_denoms.contains(&withdrawn.denom)
+        })
+        .map(|withdrawn| coin(withdrawn.amount.u128(), withdrawn.denom))
+        .collect();
+
+    let mut resp = Response::new().add_attribute("action", "decay_withdraw");
+    if funds.is_empty() {
+        return Ok(resp);
+    }
+
+    for funds in &funds {
+        resp = resp.add_attribute("redistributed", funds.to_string());
+    }
+    let distribute_msg = WasmMsg::Execute {
+        contract_addr: target.into_string(),
+        msg: to_json_binary(&DistribtionExecMsg::Distribute {})?,
+        funds,
+    };
+    Ok(resp.add_message(distribute_msg))
+}
+
+// forward data get from reply of membership contract
+// membership sets ProposeMemberData as data once the proposal created the candidate's proxy,
+// no data means the candidate still needs more votes
+pub fn propose_member(
+    deps: DepsMut,
+    payload: Binary,
+    reply: Result<SubMsgResponse, String>,
+) -> Result<Response, ContractError> {
+    let candidate: Addr = from_json(payload)?;
+    let response = reply.map_err(|err| ContractError::SubMsgFailed {
+        submsg: "propose_member".to_owned(),
+        error: err,
+    })?;
+
+    let Some(data) = response_data(response) else {
+        return Ok(Response::new());
+    };
+    let resp = Response::new().set_data(data.clone());
+
+    let proposed: Option<ProposeMemberData> = parse_execute_response_data(&data)?
+        .data
+        .map(from_json)
+        .transpose()?;
+    let Some(proposed) = proposed else {
+        return Ok(resp);
+    };
+    let proxy = deps.api.addr_validate(&proposed.proxy_addr)?;
+    PROPOSED_MEMBERS.save(deps.storage, &candidate, &proxy)?;
+
+    let resp = resp
+        .add_attribute("candidate", candidate.as_str())
+        .add_attribute("member_proxy", proxy.as_str());
+    Ok(resp)
+}
+
+// hook is sent with reply_on_error, so we only get here if it failed
+// returning Ok swallows the error and the donation goes through anyway
+pub fn hook_failed(reply: Result<SubMsgResponse, String>) -> Result<Response, ContractError> {
+    let err = reply.err().unwrap_or_default();
+    let resp = Response::new().add_attribute("hook_failed", err);
+    Ok(resp)
+}
+
+// the donation stays, the donor just goes without a receipt
+pub fn receipt_failed(reply: Result<SubMsgResponse, String>) -> Result<Response, ContractError> {
+    let err = reply.err().unwrap_or_default();
+    let resp = Response::new().add_attribute("receipt_failed", err);
+    Ok(resp)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::Attribute;
+
+    use super::*;
+
+    fn distribution() -> Addr {
+        Addr::unchecked("distribution")
+    }
+
+    fn wasm_event(contract: &str, attrs: &[(&str, &str)]) -> Event {
+        Event::new("wasm")
+            .add_attribute("_contract_address", contract)
+            .add_attributes(
+                attrs
+                    .iter()
+                    .map(|(key, value)| Attribute::new(*key, *value)),
+            )
+    }
+
+    #[allow(deprecated)]
+    fn response(events: Vec<Event>) -> SubMsgResponse {
+        SubMsgResponse {
+            events,
+            data: None,
+            msg_responses: vec![],
+        }
+    }
+
+    #[test]
+    fn withdrawn_from_distribution_event() {
+        let events = vec![
+            Event::new("execute").add_attribute("_contract_address", "distribution"),
+            wasm_event(
+                "distribution",
+                &[("action", "withdraw"), ("amount", "40"), ("denom", "ORAI")],
+            ),
+            // some other contract called along the way doesn't count
+            wasm_event("other", &[("amount", "1000"), ("denom", "ORAI")]),
+            Event::new("transfer").add_attribute("amount", "40ORAI"),
+            Event::new("wasm-payout")
+                .add_attribute("_contract_address", "distribution")
+                .add_attribute("denom", "ORAI")
+                .add_attribute("amount", "2"),
+        ];
+        let amount = withdrawn_amount(response(events), Some(&distribution()), "ORAI").unwrap();
+        assert_eq!(amount, Uint128::new(42));
+    }
+
+    #[test]
+    fn withdrawn_pairs_by_denom() {
+        let events = vec![wasm_event(
+            "distribution",
+            &[
+                ("amount", "5"),
+                ("denom", "USDC"),
+                ("amount", "7"),
+                ("denom", "ORAI"),
+            ],
+        )];
+        let amount = withdrawn_amount(response(events.clone()), Some(&distribution()), "ORAI");
+        assert_eq!(amount, Ok(Uint128::new(7)));
+        // paid only in another denom still counts as reported
+        let amount = withdrawn_amount(response(events), Some(&distribution()), "ATOM");
+        assert_eq!(amount, Ok(Uint128::zero()));
+    }
+
+    #[test]
+    fn withdrawn_unreported() {
+        let events = vec![
+            Event::new("execute").add_attribute("_contract_address", "distribution"),
+            wasm_event("distribution", &[("action", "withdraw")]),
+            wasm_event("other", &[("amount", "3"), ("denom", "ORAI")]),
+        ];
+        let err = withdrawn_amount(response(events), Some(&distribution()), "ORAI").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnreportedWithdraw {
+                events: "execute[], wasm[action], wasm[amount,denom]".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn ibc_payout() {
+        let env = cosmwasm_std::testing::mock_env();
+        let ibc = IbcWithdraw {
+            channel_id: "channel-7".to_owned(),
+            to_address: "osmo1creator".to_owned(),
+            timeout_seconds: 600,
+        };
+        let msg = payout_msg(
+            &env,
+            Some(&ibc),
+            None,
+            Addr::unchecked("owner"),
+            coin(90, "ORAI"),
+        );
+        assert_eq!(
+            msg,
+            CosmosMsg::Ibc(IbcMsg::Transfer {
+                channel_id: "channel-7".to_owned(),
+                to_address: "osmo1creator".to_owned(),
+                amount: coin(90, "ORAI"),
+                timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(600)),
+                memo: None,
+            })
+        );
     }
 }
@@ -0,0 +1,41 @@
+use cosmwasm_std::{Decimal, DepsMut, Env, Response, StdResult};
+use cw_utils::Duration;
+
+use crate::contract::exec::{mark_closed, replace_direct_part, replace_halftime};
+use crate::error::ContractError;
+use crate::state::{CloseReason, CONFIG};
+
+pub fn set_direct_part(deps: DepsMut, value: Decimal) -> Result<Response, ContractError> {
+    let old_direct_part = replace_direct_part(deps.storage, value)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "set_direct_part")
+        .add_attribute("origin", "sudo")
+        .add_attribute("old_direct_part", old_direct_part.to_string())
+        .add_attribute("new_direct_part", value.to_string());
+    Ok(resp)
+}
+
+pub fn set_halftime(deps: DepsMut, env: Env, value: Duration) -> Result<Response, ContractError> {
+    let old_halftime = replace_halftime(deps.storage, &env, value)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "set_halftime")
+        .add_attribute("origin", "sudo")
+        .add_attribute("old_halftime", old_halftime.to_string())
+        .add_attribute("new_halftime", value.to_string());
+    Ok(resp)
+}
+
+// stops donations without the owner's final withdraw, the owner can't reopen it
+pub fn force_close(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        mark_closed(&mut config, CloseReason::Governance, None, &env);
+        Ok(config)
+    })?;
+
+    let resp = Response::new()
+        .add_attribute("action", "force_close")
+        .add_attribute("origin", "sudo");
+    Ok(resp)
+}
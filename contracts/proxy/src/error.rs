@@ -1,17 +1,184 @@
-use cosmwasm_std::StdError;
-use cw_utils::PaymentError;
+use cosmwasm_std::{StdError, Uint128};
+use cw_ownable::OwnershipError;
+use cw_utils::{ParseReplyError, PaymentError};
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
     StdError(#[from] StdError),
+    #[error("{0}")]
+    Ownership(#[from] OwnershipError),
     #[error("Direct part has to be between 0 and 1")]
     InalidDirectPart,
     #[error("Unauthorized")]
     Unauthorized,
     #[error("{0}")]
     PaymentError(#[from] PaymentError),
+    #[error("{0}")]
+    ParseReplyError(#[from] ParseReplyError),
     #[error("Unrecognized reply id: {0}")]
     UnrecognizedReplyId(u64),
+    #[error("Proxy contract is closed")]
+    ContractClosed,
+    #[error("Proxy contract is not closed")]
+    NotClosed,
+    #[error("Proxy contract is paused")]
+    Paused,
+    #[error("Proxy contract is not paused")]
+    NotPaused,
+    #[error("Proxy was closed by the membership contract and can't be reopened")]
+    ClosedByMembership,
+    #[error("Proxy was closed by governance and can't be reopened")]
+    ClosedByGovernance,
+    #[error("At least one denom has to be accepted")]
+    NoAcceptedDenoms,
+    #[error("Denom {0} is listed more than once")]
+    DuplicatedDenom(String),
+    #[error("Denom can't be empty")]
+    EmptyDenom,
+    #[error("Denom {0} is longer than 128 characters")]
+    DenomTooLong(String),
+    #[error("Denom {0:?} contains whitespace")]
+    DenomWithWhitespace(String),
+    #[error("Membership contract can't be a distribution contract")]
+    DistributionIsMembership,
+    #[error("Distribution contract can't be the proxy or the membership contract")]
+    InvalidDistributionContract,
+    #[error("Donation too small, minimum is {min} but got {got}")]
+    DonationTooSmall { min: Uint128, got: Uint128 },
+    #[error("Direct part and protocol fee together can't exceed 100%")]
+    InvalidProtocolFee,
+    #[error("Direct part and burn part together can't exceed 100%")]
+    InvalidBurnPart,
+    #[error("Treasury address is required for a non zero protocol fee")]
+    MissingTreasury,
+    #[error("Distribution contract weights have to sum up to 1")]
+    InvalidDistributionWeights,
+    #[error("Tier thresholds have to be strictly ascending")]
+    UnsortedTiers,
+    #[error("Donations can't be credited to the proxy itself")]
+    DonateForProxy,
+    #[error("Can't migrate from {contract}")]
+    WrongContract { contract: String },
+    #[error("Halftime has to be above zero and at most ten years")]
+    InvalidHalftime,
+    #[error("Decay has to be above zero and at most one")]
+    InvalidDecay,
+    #[error("Bucket width has to be at least one second")]
+    InvalidBucketWidth,
+    #[error("Referral cut can't exceed 10000 bps")]
+    InvalidReferralBps,
+    #[error("Donors can't refer themselves")]
+    SelfReferral,
+    #[error("Withdraw amount has to be greater than zero")]
+    ZeroWithdrawAmount,
+    #[error("Donor is blocked by the owner")]
+    DonorBlocked,
+    #[error("Memo too long, at most {max} bytes allowed but got {len}")]
+    MemoTooLong { max: usize, len: usize },
+    #[error("Remote {field} is {len} bytes, at most {max} allowed")]
+    RemoteFieldTooLong {
+        field: String,
+        max: usize,
+        len: usize,
+    },
+    #[error("Remote {field} must be non-empty ASCII letters, digits, '-', '_', '.' or '/'")]
+    InvalidRemoteField { field: String },
+    #[error("{submsg} submessage failed: {error}")]
+    SubMsgFailed { submsg: String, error: String },
+    #[error("Distribution reported neither data nor an amount event, events seen: {events}")]
+    UnreportedWithdraw { events: String },
+    #[error("Close reason too long, at most {max} bytes allowed but got {len}")]
+    CloseReasonTooLong { max: usize, len: usize },
+    #[error("Metadata {field} too long, at most {max} bytes allowed but got {len}")]
+    MetadataTooLong {
+        field: String,
+        max: usize,
+        len: usize,
+    },
+    #[error("Metadata {field} can't contain control characters")]
+    MetadataControlChars { field: String },
+    #[error("Withdraw cooldown not elapsed, {remaining} seconds remaining")]
+    WithdrawCooldown { remaining: u64 },
+    #[error("Withdraw exceeds the spend limit, {remaining} left in this window")]
+    SpendLimitExceeded { remaining: Uint128 },
+    #[error("Spend limit window must be greater than zero")]
+    ZeroSpendWindow,
+    #[error("Keeper fee can't exceed 10000 bps")]
+    InvalidKeeperFee,
+    #[error("Automatic withdrawal is disabled")]
+    AutoWithdrawDisabled,
+    #[error("Only {pending} waiting to be withdrawn, more than {threshold} needed")]
+    BelowWithdrawThreshold {
+        pending: Uint128,
+        threshold: Uint128,
+    },
+    #[error("Weight doesn't fit in u64")]
+    WeightOverflow,
+    #[error("Counter doesn't fit in u64")]
+    CounterOverflow,
+    #[error("Time doesn't fit in u64")]
+    TimeOverflow,
+    #[error("Ownership transfer expiry must be in the future")]
+    InvalidOwnershipExpiry,
+    #[error("Renouncing the ownership has to be confirmed")]
+    RenounceNotConfirmed,
+    #[error("Invalid contract version {0}")]
+    InvalidVersion(String),
+    #[error("Can't migrate from {stored} down to {current}")]
+    Downgrade { stored: String, current: String },
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw,
+    #[error("Receiver can't be the proxy or one of its distribution contracts")]
+    InvalidReceiver,
+    #[error("Distribution sent {got} back instead of {expected}")]
+    UnexpectedDenom { expected: String, got: String },
+    #[error("Receiver message needs an explicit receiver")]
+    ReceiverMsgWithoutReceiver,
+    #[error("IBC withdraw pays the remote address only, no receiver or receiver message")]
+    IbcWithReceiver,
+    #[error("IBC channel id must not be empty")]
+    EmptyIbcChannel,
+    #[error("IBC remote address must not be empty")]
+    EmptyIbcAddress,
+    #[error("IBC timeout must be greater than zero")]
+    ZeroIbcTimeout,
+    #[error("Stream duration must be greater than zero")]
+    InvalidStreamDuration,
+    #[error("Another stream is still being claimed")]
+    StreamActive,
+    #[error("No active stream")]
+    NoActiveStream,
+    #[error("Withdraw needs at least one receiver")]
+    NoReceivers,
+    #[error("Withdraw can be split between at most {max} receivers")]
+    TooManyReceivers { max: usize },
+    #[error("Receiver shares have to sum up to 1")]
+    InvalidReceiverShares,
+    #[error("Another withdrawal is still pending")]
+    WithdrawalPending,
+    #[error("Distribution reported {received} withdrawn but only {available} is available")]
+    WithdrawMismatch {
+        received: Uint128,
+        available: Uint128,
+    },
+    #[error("Threshold {threshold} doesn't fit {approvers} approvers")]
+    InvalidThreshold { threshold: u32, approvers: u32 },
+    #[error("{0} is listed as an approver twice")]
+    DuplicateApprover(String),
+    #[error("Withdraw proposals need a duration")]
+    InvalidProposalDuration,
+    #[error("Withdraws need approvals, use ProposeWithdraw")]
+    ApprovalRequired,
+    #[error("Proxy has no approvers")]
+    NoApprovers,
+    #[error("Withdraw proposal {id} not found")]
+    ProposalNotFound { id: u64 },
+    #[error("Withdraw proposal {id} has expired")]
+    ProposalExpired { id: u64 },
+    #[error("Withdraw proposal already approved")]
+    AlreadyApproved,
+    #[error("{addr} is not a contract, expected the {which} contract")]
+    NotAContract { which: String, addr: String },
 }
@@ -1,38 +1,774 @@
-use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Empty, Int128, Uint128};
+use cw20::Cw20ReceiveMsg;
+use cw_ownable::{cw_ownable_execute, cw_ownable_query};
+use cw_utils::{Duration, Expiration};
+
+#[cw_serde]
+pub struct MigrateMsg {}
+
+// sent by the chain governance, same bounds as the owner setters
+#[cw_serde]
+pub enum SudoMsg {
+    SetDirectPart { value: Decimal },
+    SetHalftime { value: Duration },
+    ForceClose {},
+}
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: String,
     pub weight: u64,
-    pub denom: String,
+    pub accepted_denoms: Vec<String>,
     pub direct_part: Decimal,
-    pub distribution_contract: String,
+    pub distribution_contracts: Vec<(String, Decimal)>,
     pub membership_contract: String,
-    pub halftime: u64,
+    pub halftime: Duration, // { "time": seconds } or { "height": blocks }
+    pub cw20_address: Option<String>, // token accepted through the Receive hook
+    #[serde(default)]
+    pub min_donation: Uint128,
+    pub direct_beneficiary: Option<String>,
+    pub goal: Option<Uint128>,
+    #[serde(default)]
+    pub protocol_fee: Decimal, // cut sent to the treasury before the direct/distribution split
+    pub treasury: Option<String>,
+    #[serde(default)]
+    pub tiers: Vec<(Uint128, String)>,
+    pub bucket_width: Option<u64>,       // defaults to a day
+    pub matching_ratio: Option<Decimal>, // defaults to 1:1
+    pub decay: Option<Decimal>, // part of the weight lost every halftime, defaults to a half
+    #[serde(default)]
+    pub referral_bps: u64,
+    #[serde(default)]
+    pub burn_part: Decimal,
+    #[serde(default)]
+    pub withdraw_cooldown: u64, // seconds between owner withdraws, zero disables it
+    pub auto_withdraw_threshold: Option<Uint128>, // None disables Crank
+    #[serde(default)]
+    pub keeper_fee_bps: u64,
+    pub max_weight: Option<u64>, // no cap if not set
+    #[serde(default)]
+    pub decay_funds: DecayFunds,
+    #[serde(default)]
+    pub approvers: Vec<String>, // withdraws need threshold of them once set
+    #[serde(default)]
+    pub threshold: u32,
+    pub proposal_duration: Option<u64>, // seconds, defaults to a week
+    #[serde(default)]
+    pub skip_contract_check: bool, // for environments without the distribution and membership contracts
+    pub metadata: Option<ProxyMetadata>,
+    #[serde(default)]
+    pub member_id: Option<u64>, // assigned by the membership contract, echoed back in the data
+    #[serde(default)]
+    pub instantiated_by: Option<String>, // the membership contract spawning this proxy
+}
+
+// set as instantiate response data, read by the membership contract's instantiate reply
+#[cw_serde]
+pub struct ProxyInstantiateResponse {
+    pub member_id: Option<u64>,
+    pub instantiated_by: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct IbcWithdraw {
+    pub channel_id: String,
+    pub to_address: String, // can't be validated, the prefix is the remote chain's
+    pub timeout_seconds: u64, // from the block time of the payout
 }
 
+// creator's profile shown to supporters, also read by the membership contract
 #[cw_serde]
-pub enum QueryMsg {}
+pub struct ProxyMetadata {
+    pub name: String,                // at most 64 bytes
+    pub description: Option<String>, // at most 512 bytes
+    pub url: Option<String>,         // at most 256 bytes
+}
+
+// what happens to the share withdrawn when the weight decays
+#[cw_serde]
+#[derive(Default)]
+pub enum DecayFunds {
+    // sent back to the distribution contract, shared among the other weights
+    ReDistribute,
+    // left on the proxy for the owner's next withdraw
+    #[default]
+    KeepOnProxy,
+}
+
+#[cw_ownable_query]
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    // None once renounced, Ownership also has the pending transfer
+    #[returns(OwnerResponse)]
+    Owner {},
+    #[returns(WeightResponse)]
+    Weight {},
+    #[returns(DonationsResponse)]
+    Donations {},
+    #[returns(HalftimeResponse)]
+    Halftime {},
+    #[returns(LastUpdatedResponse)]
+    LastUpdated {},
+    #[returns(TotalDonatedResponse)]
+    TotalDonated {},
+    #[returns(DonorResponse)]
+    Donor { addr: String },
+    // ascending by address, at most 50 per page
+    // inactive_since only lists donors whose last donation is older than that block time,
+    // a page scans at most ten donors per one asked for so it can come back short
+    #[returns(DonorsResponse)]
+    Donors {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        inactive_since: Option<u64>,
+    },
+    // split of a donation in accepted_denoms[0] without a referrer, nothing is saved
+    // the blocklist isn't checked as the donor isn't known here
+    #[returns(SimulateDonationResponse)]
+    SimulateDonation { amount: Uint128 },
+    #[returns(StatsResponse)]
+    Stats {},
+    // both ends included
+    #[returns(DailyStatsResponse)]
+    DailyStats { from_day: u64, to_day: u64 },
+    // None until the first donation
+    #[returns(Option<LargestDonationResponse>)]
+    LargestDonation {},
+    #[returns(GoalProgressResponse)]
+    GoalProgress {},
+    // biggest donors first, equal totals ordered by address (descending), at most 30 per page
+    // start_after is the last donor of the previous page
+    #[returns(TopDonorsResponse)]
+    TopDonors {
+        #[serde(default)]
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // grouped by chain, shorter chain ids first as the key is length prefixed, at most 50 per page
+    #[returns(RemoteDonorsResponse)]
+    RemoteDonors {
+        start_after: Option<(String, String)>, // chain and donor of the last one seen
+        limit: Option<u32>,
+    },
+    #[returns(DonorTierResponse)]
+    DonorTier { addr: String },
+    #[returns(ReferrerStatsResponse)]
+    ReferrerStats { addr: String },
+    #[returns(ReferrersResponse)]
+    Referrers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(IsBlockedResponse)]
+    IsBlocked { addr: String },
+    #[returns(BlocklistResponse)]
+    Blocklist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(OperatorsResponse)]
+    Operators {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // newest first
+    #[returns(DonationHistoryResponse)]
+    DonationHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    // newest first, like DonationHistory
+    #[returns(WithdrawalHistoryResponse)]
+    WithdrawalHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    // what a Withdraw would pay out now, accepted_denoms[0] if no denom
+    #[returns(EstimatedWithdrawableResponse)]
+    EstimatedWithdrawable { denom: Option<String> },
+    // None unless a withdraw is waiting for the distribution replies
+    #[returns(Option<PendingWithdrawalResponse>)]
+    PendingWithdrawal {},
+    #[returns(Option<StreamResponse>)]
+    Stream {},
+    // when UpdateWeight will actually decay the weight
+    #[returns(NextUpdateResponse)]
+    NextUpdate {},
+    #[returns(cw2::ContractVersion)]
+    ContractVersion {},
+    // every denom held by the proxy
+    #[returns(BalancesResponse)]
+    Balances {},
+    #[returns(Option<ProxyMetadata>)]
+    Metadata {},
+    // members whose proxy got created on our proposal, ascending by candidate
+    #[returns(ProposedMembersResponse)]
+    ProposedMembers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // everything a proxy card shows in one query
+    #[returns(StatusResponse)]
+    Status {},
+    #[returns(PausedResponse)]
+    Paused {},
+    // weight at the end of the block, None before the proxy existed
+    #[returns(WeightAtResponse)]
+    WeightAt { height: u64 },
+    // newest first, start_after is a block height
+    #[returns(WeightHistoryResponse)]
+    WeightHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(Option<WithdrawProposalResponse>)]
+    WithdrawProposal { id: u64 },
+    // oldest first, executed proposals are gone
+    #[returns(WithdrawProposalsResponse)]
+    WithdrawProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct TotalDonatedResponse {
+    pub total: Uint128,
+}
+
+#[cw_serde]
+pub struct DonorResponse {
+    pub total: Uint128,
+    pub count: u64,
+    pub last_donated: Option<u64>, // block time, None if never donated
+}
+
+#[cw_serde]
+pub struct SimulateDonationResponse {
+    pub protocol_fee: Uint128,
+    pub burned: Uint128,
+    pub direct: Uint128,
+    pub to_distribute: Uint128, // carried dust and matched funds included
+    pub dust: Uint128,          // kept on the proxy until the next donation
+    pub dust_carried: Uint128,
+    pub matched: Uint128,
+    pub rejected: Option<String>, // why donating would fail right now
+}
+
+#[cw_serde]
+pub struct Donor {
+    pub addr: Addr,
+    pub total: Uint128,
+    pub count: u64,
+    pub last_donated: Option<u64>,
+}
+
+#[cw_serde]
+pub struct DonorsResponse {
+    pub donors: Vec<Donor>,
+    // start_after of the next page, None once every donor was scanned
+    pub next_start_after: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct RemoteDonor {
+    pub chain: String,
+    pub donor: String,
+    pub total: Uint128,
+    pub count: u64,
+    pub last_donated: u64,
+}
+
+#[cw_serde]
+pub struct RemoteDonorsResponse {
+    pub donors: Vec<RemoteDonor>,
+}
+
+#[cw_serde]
+pub struct TopDonor {
+    pub addr: Addr,
+    pub total: Uint128,
+}
+
+#[cw_serde]
+pub struct TopDonorsResponse {
+    pub donors: Vec<TopDonor>,
+}
+
+#[cw_serde]
+pub struct StatsResponse {
+    pub donations: u64, // since the last withdraw
+    pub lifetime_donations: u64,
+    pub unique_donors: u64,
+    pub total_donated: Uint128,
+    pub dust: Vec<Coin>, // held by the proxy, forwarded with the next donation
+    pub retained: Vec<Coin>, // owner funds kept by capped withdraws
+}
+
+#[cw_serde]
+pub struct GoalProgressResponse {
+    pub raised: Uint128,
+    pub goal: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct DailyBucket {
+    pub day: u64,
+    pub count: u64,
+    pub amount: Uint128,
+}
+
+// days without any donation are omitted, not returned as zeros
+#[cw_serde]
+pub struct DailyStatsResponse {
+    pub buckets: Vec<DailyBucket>,
+}
+
+#[cw_serde]
+pub struct LargestDonationResponse {
+    pub donor: Addr,
+    pub amount: Uint128,
+    pub time: u64,
+}
+
+#[cw_serde]
+pub struct DonorTierResponse {
+    pub tier: Option<String>,
+}
+
+#[cw_serde]
+pub struct ReferrerStatsResponse {
+    pub count: u64,
+    pub total: Uint128,
+    pub earned: Uint128,
+}
+
+#[cw_serde]
+pub struct Referrer {
+    pub addr: Addr,
+    pub count: u64,
+    pub total: Uint128,
+    pub earned: Uint128,
+}
+
+#[cw_serde]
+pub struct ReferrersResponse {
+    pub referrers: Vec<Referrer>,
+}
+
+#[cw_serde]
+pub struct IsBlockedResponse {
+    pub is_blocked: bool,
+}
+
+#[cw_serde]
+pub struct BlocklistResponse {
+    pub blocked: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct Operator {
+    pub addr: Addr,
+    pub can_withdraw: bool,
+    pub can_propose: bool,
+}
+
+#[cw_serde]
+pub struct OperatorsResponse {
+    pub operators: Vec<Operator>,
+}
+
+#[cw_serde]
+pub struct DonationRecordResponse {
+    pub id: u64,
+    pub donor: Addr,
+    pub amount: Uint128,
+    pub denom: String,
+    pub memo: Option<String>,
+    pub time: u64,
+}
+
+#[cw_serde]
+pub struct DonationHistoryResponse {
+    pub donations: Vec<DonationRecordResponse>,
+}
+
+#[cw_serde]
+pub struct WithdrawalRecordResponse {
+    pub id: u64,
+    pub receiver: Addr,
+    pub amount: Uint128,
+    pub denom: String,
+    pub block_time: u64,
+    pub epoch_donations: u64,
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub accepted_denoms: Vec<String>,
+    pub direct_part: Decimal,
+    pub distribution_contracts: Vec<(Addr, Decimal)>,
+    pub membership_contract: Addr,
+    pub is_closed: bool,
+}
+
+#[cw_serde]
+pub struct OwnerResponse {
+    pub owner: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct WeightResponse {
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub struct DonationsResponse {
+    pub donations: u64, // donations since the last withdraw
+}
 
+#[cw_serde]
+pub struct HalftimeResponse {
+    pub halftime: Duration,
+}
+
+#[cw_serde]
+pub struct LastUpdatedResponse {
+    pub last_updated: Expiration, // when the weight last decayed
+}
+
+#[cw_serde]
+pub struct EstimatedWithdrawableResponse {
+    pub from_distribution: Uint128, // reported by the distribution contracts
+    pub direct_on_proxy: Uint128,   // owner funds already on the proxy
+    pub total: Uint128,
+}
+
+#[cw_serde]
+pub struct PendingWithdrawalResponse {
+    pub receivers: Vec<(Addr, Decimal)>, // shares sum up to 1
+    pub amount: Option<Uint128>,         // whole withdrawable amount if None
+    pub denom: String,
+}
+
+#[cw_serde]
+pub struct ProposedMember {
+    pub candidate: Addr,
+    pub proxy: Addr,
+}
+
+#[cw_serde]
+pub struct ProposedMembersResponse {
+    pub members: Vec<ProposedMember>,
+}
+
+#[cw_serde]
+pub struct DenomBalance {
+    pub denom: String,
+    pub total_balance: Uint128,
+    pub reserved: Uint128, // dust, matching pool, stream and pending withdrawal funds
+    pub spendable: Uint128, // what WithdrawDirect and WithdrawAll can send
+}
+
+#[cw_serde]
+pub struct BalancesResponse {
+    pub balances: Vec<DenomBalance>,
+}
+
+#[cw_serde]
+pub struct StatusResponse {
+    pub owner: Option<Addr>,
+    pub weight: u64,
+    pub donations: u64, // since the last withdraw
+    pub lifetime_donations: u64,
+    pub total_donated: Uint128,
+    pub unique_donors: u64,
+    pub halftime: Duration,
+    pub last_updated: Expiration,
+    pub is_closed: bool,
+    pub reason: Option<String>, // canonical one for closes on the goal, by membership or governance
+    pub closed_at: Option<u64>,
+    pub paused: bool,
+    pub withdrawal_pending: bool,
+}
+
+#[cw_serde]
+pub struct PausedResponse {
+    pub paused: bool,
+}
+
+#[cw_serde]
+pub struct WithdrawProposalResponse {
+    pub id: u64,
+    pub receiver: Addr,
+    pub amount: Option<Uint128>,
+    pub approvals: Vec<Addr>,
+    pub expires: u64,
+    pub expired: bool,
+}
+
+#[cw_serde]
+pub struct WithdrawProposalsResponse {
+    pub proposals: Vec<WithdrawProposalResponse>,
+}
+
+#[cw_serde]
+pub struct WeightAtResponse {
+    pub weight: Option<u64>,
+}
+
+#[cw_serde]
+pub struct WeightChange {
+    pub height: u64,
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub struct WeightHistoryResponse {
+    pub weights: Vec<WeightChange>,
+}
+
+#[cw_serde]
+pub struct NextUpdateResponse {
+    pub last_updated: Expiration,
+    pub halftime: Duration,
+    pub next_eligible_at: Expiration,
+    pub remaining: u64, // seconds or blocks like halftime, zero once the update is due
+}
+
+#[cw_serde]
+pub struct StreamResponse {
+    pub receiver: Addr,
+    pub denom: String,
+    pub total: Uint128,
+    pub claimed: Uint128,
+    pub claimable: Uint128, // vested but not claimed yet
+    pub start: u64,
+    pub end: u64,
+}
+
+#[cw_serde]
+pub struct WithdrawalHistoryResponse {
+    pub withdrawals: Vec<WithdrawalRecordResponse>,
+}
+
+#[cw_ownable_execute]
 #[cw_serde]
 pub enum ExecMsg {
-    Donate {},
+    Donate {
+        memo: Option<String>,
+        referrer: Option<String>,
+    },
+    // paid by the sender, credited to on_behalf_of
+    DonateFor {
+        on_behalf_of: String,
+    },
+    // put in an ICS-20 memo, the ibc-hook sender is credited like Donate
+    // and the donor on the remote chain gets the attribution
+    DonateRemote {
+        remote_donor: String, // at most 128 bytes
+        remote_chain: String, // at most 64 bytes
+    },
     Withdraw {
         receiver: Option<String>,
         amount: Option<Uint128>,
+        denom: Option<String>, // first accepted denom if not given
+        // executed on the receiver with the funds instead of a bank send, receiver must be given
+        #[serde(default)]
+        receiver_msg: Option<Binary>,
+        // sent over ICS-20 to an address on another chain, receiver and receiver_msg must be empty
+        #[serde(default)]
+        ibc: Option<IbcWithdraw>,
+    },
+    // like Withdraw, but the amount is shared between receivers, shares sum up to 1
+    WithdrawSplit {
+        receivers: Vec<(String, Decimal)>,
+        amount: Option<Uint128>,
+        denom: Option<String>,
+    },
+    // owner's funds already on the proxy, in the first accepted denom, refused once approvers are set
+    WithdrawDirect {
+        receiver: Option<String>,
+    },
+    // every denom held by the proxy, minus what is reserved, refused once approvers are set
+    WithdrawAll {
+        receiver: Option<String>,
     },
-    Close {},
+    // replaces Withdraw once approvers are set, owner, withdraw operators or approvers
+    // an approver proposing approves it too
+    ProposeWithdraw {
+        receiver: Option<String>, // owner if not set
+        amount: Option<Uint128>,
+    },
+    // approvers only, the withdraw starts with the approval meeting the threshold
+    ApproveWithdraw {
+        id: u64,
+    },
+    // like Withdraw, but the payout stays on the proxy and vests linearly over the duration
+    WithdrawStreamed {
+        receiver: String,
+        duration_seconds: u64,
+    },
+    // anyone can pay the receiver what has vested so far
+    ClaimStream {},
+    // owner or the membership contract, reason is shown by the Status query
+    Close {
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    // owner only, not after the membership contract closed the proxy
+    Reopen {},
     ProposeMember {
         addr: String,
     },
     UpdateWeight {},
+    Receive(Cw20ReceiveMsg),
+    SetMinDonation {
+        min_donation: Uint128,
+    },
+    SetDirectBeneficiary {
+        addr: Option<String>, // None => back to owner
+    },
+    Block {
+        addr: String,
+    },
+    Unblock {
+        addr: String,
+    },
+    // funds in the first accepted denom
+    FundMatching {},
+    WithdrawMatching {},
+    SetHook {
+        addr: Option<String>, // None => no hook
+    },
+    // native donations of at least min_amount mint a receipt to the donor
+    SetReceiptNft {
+        receipt: Option<ReceiptConfigMsg>, // None => no receipts
+    },
+    SetMetadata {
+        metadata: Option<ProxyMetadata>, // None => no profile
+    },
+    SetWithdrawCooldown {
+        cooldown: u64,
+    },
+    // at most amount paid to the owner in each denom every window seconds, the rest stays on the proxy
+    SetSpendLimit {
+        limit: Option<(Uint128, u64)>, // None => no limit
+    },
+    // operators act for the owner, they can never manage operators or config
+    GrantOperator {
+        addr: String,
+        can_withdraw: bool,
+        can_propose: bool,
+    },
+    RevokeOperator {
+        addr: String,
+    },
+    // anyone can withdraw to the owner once enough is waiting in the distribution contracts,
+    // the caller earns the keeper fee
+    Crank {},
+    SetAutoWithdrawThreshold {
+        threshold: Option<Uint128>, // None disables Crank
+    },
+    SetKeeperFee {
+        keeper_fee_bps: u64,
+    },
+    // the time elapsed since the last update counts against the new halftime,
+    // a shorter one can make several periods due at once
+    // switching between time and height restarts the current period
+    SetHalftime {
+        halftime: Duration,
+    },
+    // 0 <= value <= 1, and still leaves room for the protocol fee and the burn part
+    SetDirectPart {
+        value: Decimal,
+    },
+    // replaces every distribution contract with addr, after a final withdraw to the owner
+    SetDistributionContract {
+        addr: String,
+    },
+    // 0 < decay <= 1
+    SetDecay {
+        decay: Decimal,
+    },
+    // anyone by default
+    SetUpdatePolicy {
+        policy: UpdatePolicyMsg,
+    },
+    // owner only, drops the transfer proposed with UpdateOwnership
+    CancelOwnershipTransfer {},
+    // no way back, owner-gated messages fail for good and the owner's share can't be withdrawn anymore
+    // confirm has to be true, refused while a withdrawal is pending
+    // UpdateOwnership refuses to renounce, it would skip these checks
+    RenounceOwnership {
+        confirm: bool,
+    },
+    // owner only, refuses every other message until Unpause, unlike Close
+    Pause {},
+    Unpause {},
+}
+
+#[cw_serde]
+pub struct ReceiptConfigMsg {
+    pub cw721_addr: String,
+    pub min_amount: Uint128,
+}
+
+// who may call UpdateWeight
+#[cw_serde]
+pub enum UpdatePolicyMsg {
+    Owner {},
+    Keeper { addr: String },
+    Membership {},
+    Anyone {},
+}
+
+// messages embedded in Cw20ReceiveMsg::msg
+#[cw_serde]
+pub enum Cw20HookMsg {
+    Donate { memo: Option<String> },
+}
+
+// sent to the hook contract on every donation, hooks should accept this message
+// a failing hook never fails the donation
+#[cw_serde]
+pub enum ProxyHookMsg {
+    DonationReceived {
+        donor: String,
+        amount: Uint128,
+        denom: String, // token contract address for cw20 donations
+    },
 }
 
 #[cw_serde]
 pub enum DistribtionExecMsg {
     Distribute {},
-    Withdraw { weight: u64, diff: i64 },
+    Withdraw { weight: u64, diff: Int128 },
+}
+
+// only the cw721-base message the receipts need
+#[cw_serde]
+pub enum Cw721ExecMsg {
+    Mint {
+        token_id: String,
+        owner: String,
+        token_uri: Option<String>,
+        extension: Option<Empty>,
+    },
+}
+
+#[cw_serde]
+pub enum DistributionQueryMsg {
+    Withdrawable { proxy: String, denom: String },
+}
+
+#[cw_serde]
+pub struct WithdrawableResponse {
+    pub amount: Uint128,
 }
 
 #[cw_serde]
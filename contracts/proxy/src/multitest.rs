@@ -1,54 +1,70 @@
 use anyhow::Result as AnyResult;
 use common::msg::ProposeMemberData;
-use cosmwasm_std::{from_json, Addr, Coin, Decimal};
-use cw_multi_test::{App, ContractWrapper, Executor};
-use cw_utils::parse_execute_response_data;
+use common::multitest::App;
+use cosmwasm_std::{from_json, to_json_binary, Addr, Binary, Coin, Decimal, Uint128};
+use cw2::ContractVersion;
+use cw20::Cw20ExecuteMsg;
+use cw_multi_test::{AppResponse, ContractWrapper, Executor};
+use cw_ownable::{Action, Ownership};
+use cw_utils::{parse_execute_response_data, Duration, Expiration};
 
+use crate::msg::{
+    BalancesResponse, BlocklistResponse, ConfigResponse, DailyStatsResponse, DenomBalance,
+    DonationsResponse, DonorTierResponse, DonorsResponse, EstimatedWithdrawableResponse,
+    GoalProgressResponse, HalftimeResponse, IbcWithdraw, IsBlockedResponse,
+    LargestDonationResponse, LastUpdatedResponse, MigrateMsg, NextUpdateResponse,
+    OperatorsResponse, OwnerResponse, PausedResponse, PendingWithdrawalResponse, ProposedMember,
+    ProposedMembersResponse, ProxyMetadata, ReceiptConfigMsg, ReferrerStatsResponse,
+    ReferrersResponse, RemoteDonorsResponse, SimulateDonationResponse, StatsResponse,
+    StatusResponse, StreamResponse, SudoMsg, TopDonorsResponse, UpdatePolicyMsg, WeightAtResponse,
+    WeightHistoryResponse, WeightResponse, WithdrawProposalResponse, WithdrawProposalsResponse,
+    WithdrawalHistoryResponse,
+};
 use crate::{
-    execute, instantiate,
-    msg::{ExecMsg, InstantiateMsg, MembershipExecMsg},
-    query, reply,
+    execute, instantiate, migrate,
+    msg::{
+        Cw20HookMsg, DonationHistoryResponse, DonorResponse, ExecMsg, InstantiateMsg,
+        MembershipExecMsg, QueryMsg, TotalDonatedResponse,
+    },
+    query, reply, sudo,
 };
 
+#[cfg(test)]
+mod distribution_mock;
+#[cfg(test)]
+mod hook_mock;
+#[cfg(test)]
+mod legacy_mock;
+#[cfg(test)]
+mod receiver_mock;
+#[cfg(test)]
+mod test;
+
 #[derive(Clone, Copy, Debug)]
 pub struct CodeId(u64);
 
 impl CodeId {
     pub fn store_code(app: &mut App) -> Self {
-        let contract = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+        let contract = ContractWrapper::new(execute, instantiate, query)
+            .with_reply(reply)
+            .with_migrate(migrate)
+            .with_sudo(sudo);
 
         CodeId(app.store_code(Box::new(contract)))
     } // return instance of Self
       // remember need to declare all entry point in ContractWrapper
 
+    // the proxy has grown too many init fields to pass them one by one
     #[track_caller]
     pub fn instantiate(
         self,
         app: &mut App,
         sender: &str,
-        owner: &str,
-        weight: u64,
-        denom: &str,
-        direct_part: Decimal,
-        distribution_contract: &str,
-        membership_contract: &str,
-        halftime: u64,
+        init_msg: &InstantiateMsg,
         label: &str,
     ) -> AnyResult<Contract> {
         // this is static function?
-        Contract::instantiate(
-            app,
-            self,
-            sender,
-            owner,
-            weight,
-            denom,
-            direct_part,
-            distribution_contract,
-            membership_contract,
-            halftime,
-            label,
-        )
+        Contract::instantiate(app, self, sender, init_msg, label)
     }
 }
 
@@ -78,29 +94,13 @@ impl Contract {
         app: &mut App,
         code_id: CodeId,
         sender: &str,
-        owner: &str,
-        weight: u64,
-        denom: &str,
-        direct_part: Decimal,
-        distribution_contract: &str,
-        membership_contract: &str,
-        halftime: u64,
+        init_msg: &InstantiateMsg,
         label: &str,
     ) -> AnyResult<Self> {
-        let init_msg = InstantiateMsg {
-            owner: owner.to_owned(),
-            weight,
-            denom: denom.to_owned(),
-            direct_part,
-            distribution_contract: distribution_contract.to_owned(),
-            membership_contract: membership_contract.to_owned(),
-            halftime,
-        };
-
         app.instantiate_contract(
             code_id.0,
             Addr::unchecked(sender),
-            &init_msg,
+            init_msg,
             &[],
             label,
             None,
@@ -115,12 +115,604 @@ impl Contract {
 
     #[track_caller]
     pub fn donate(&self, app: &mut App, sender: &str, funds: &[Coin]) -> AnyResult<()> {
-        let donate_msg = ExecMsg::Donate {};
+        self.donate_with_memo(app, sender, funds, None)
+    }
+
+    #[track_caller]
+    pub fn donate_with_memo(
+        &self,
+        app: &mut App,
+        sender: &str,
+        funds: &[Coin],
+        memo: Option<&str>,
+    ) -> AnyResult<()> {
+        let donate_msg = ExecMsg::Donate {
+            memo: memo.map(str::to_owned),
+            referrer: None,
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &donate_msg, funds)?;
+
+        Ok(())
+    }
+
+    // cw20 donations go through the token contract which calls our Receive hook
+    #[track_caller]
+    pub fn donate_with_referrer(
+        &self,
+        app: &mut App,
+        sender: &str,
+        funds: &[Coin],
+        referrer: &str,
+    ) -> AnyResult<()> {
+        let donate_msg = ExecMsg::Donate {
+            memo: None,
+            referrer: Some(referrer.to_owned()),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &donate_msg, funds)?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn donate_remote(
+        &self,
+        app: &mut App,
+        sender: &str,
+        remote_chain: &str,
+        remote_donor: &str,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        let donate_msg = ExecMsg::DonateRemote {
+            remote_donor: remote_donor.to_owned(),
+            remote_chain: remote_chain.to_owned(),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &donate_msg, funds)
+    }
+
+    pub fn remote_donors(
+        &self,
+        app: &App,
+        start_after: Option<(&str, &str)>,
+        limit: Option<u32>,
+    ) -> AnyResult<RemoteDonorsResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::RemoteDonors {
+                start_after: start_after.map(|(chain, donor)| (chain.to_owned(), donor.to_owned())),
+                limit,
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn donate_for(
+        &self,
+        app: &mut App,
+        sender: &str,
+        on_behalf_of: &str,
+        funds: &[Coin],
+    ) -> AnyResult<()> {
+        let donate_msg = ExecMsg::DonateFor {
+            on_behalf_of: on_behalf_of.to_owned(),
+        };
         app.execute_contract(Addr::unchecked(sender), self.0.clone(), &donate_msg, funds)?;
 
         Ok(())
     }
 
+    #[track_caller]
+    pub fn donate_cw20(
+        &self,
+        app: &mut App,
+        sender: &str,
+        token: &Addr,
+        amount: u128,
+    ) -> AnyResult<()> {
+        let send_msg = Cw20ExecuteMsg::Send {
+            contract: self.0.to_string(),
+            amount: Uint128::new(amount),
+            msg: to_json_binary(&Cw20HookMsg::Donate { memo: None })?,
+        };
+        app.execute_contract(Addr::unchecked(sender), token.clone(), &send_msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn withdraw(
+        &self,
+        app: &mut App,
+        sender: &str,
+        receiver: Option<&str>,
+        amount: Option<u128>,
+        denom: Option<&str>,
+    ) -> AnyResult<()> {
+        let withdraw_msg = ExecMsg::Withdraw {
+            receiver: receiver.map(str::to_owned),
+            amount: amount.map(Uint128::new),
+            denom: denom.map(str::to_owned),
+            receiver_msg: None,
+            ibc: None,
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &withdraw_msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn propose_withdraw(
+        &self,
+        app: &mut App,
+        sender: &str,
+        receiver: Option<&str>,
+        amount: Option<u128>,
+    ) -> AnyResult<AppResponse> {
+        let msg = ExecMsg::ProposeWithdraw {
+            receiver: receiver.map(str::to_owned),
+            amount: amount.map(Uint128::new),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])
+    }
+
+    #[track_caller]
+    pub fn approve_withdraw(&self, app: &mut App, sender: &str, id: u64) -> AnyResult<AppResponse> {
+        let msg = ExecMsg::ApproveWithdraw { id };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])
+    }
+
+    #[track_caller]
+    pub fn withdraw_with_msg(
+        &self,
+        app: &mut App,
+        sender: &str,
+        receiver: &str,
+        receiver_msg: Binary,
+    ) -> AnyResult<()> {
+        let withdraw_msg = ExecMsg::Withdraw {
+            receiver: Some(receiver.to_owned()),
+            amount: None,
+            denom: None,
+            receiver_msg: Some(receiver_msg),
+            ibc: None,
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &withdraw_msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn withdraw_ibc(
+        &self,
+        app: &mut App,
+        sender: &str,
+        ibc: IbcWithdraw,
+    ) -> AnyResult<AppResponse> {
+        let withdraw_msg = ExecMsg::Withdraw {
+            receiver: None,
+            amount: None,
+            denom: None,
+            receiver_msg: None,
+            ibc: Some(ibc),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &withdraw_msg, &[])
+    }
+
+    #[track_caller]
+    pub fn close(&self, app: &mut App, sender: &str) -> AnyResult<()> {
+        self.close_with_reason(app, sender, None)
+    }
+
+    #[track_caller]
+    pub fn close_with_reason(
+        &self,
+        app: &mut App,
+        sender: &str,
+        reason: Option<&str>,
+    ) -> AnyResult<()> {
+        let close_msg = ExecMsg::Close {
+            reason: reason.map(str::to_owned),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &close_msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn reopen(&self, app: &mut App, sender: &str) -> AnyResult<()> {
+        let msg = ExecMsg::Reopen {};
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn update_ownership(
+        &self,
+        app: &mut App,
+        sender: &str,
+        action: Action,
+    ) -> AnyResult<AppResponse> {
+        let msg = ExecMsg::UpdateOwnership(action);
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])
+    }
+
+    #[track_caller]
+    pub fn cancel_ownership_transfer(&self, app: &mut App, sender: &str) -> AnyResult<()> {
+        let msg = ExecMsg::CancelOwnershipTransfer {};
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn renounce_ownership(&self, app: &mut App, sender: &str, confirm: bool) -> AnyResult<()> {
+        let msg = ExecMsg::RenounceOwnership { confirm };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn sudo(&self, app: &mut App, msg: &SudoMsg) -> AnyResult<AppResponse> {
+        app.wasm_sudo(self.0.clone(), msg)
+    }
+
+    #[track_caller]
+    pub fn set_direct_part(&self, app: &mut App, sender: &str, value: Decimal) -> AnyResult<()> {
+        let msg = ExecMsg::SetDirectPart { value };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn pause(&self, app: &mut App, sender: &str) -> AnyResult<()> {
+        let msg = ExecMsg::Pause {};
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn unpause(&self, app: &mut App, sender: &str) -> AnyResult<()> {
+        let msg = ExecMsg::Unpause {};
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn set_distribution_contract(
+        &self,
+        app: &mut App,
+        sender: &str,
+        addr: &Addr,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::SetDistributionContract {
+            addr: addr.to_string(),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn fund_matching(&self, app: &mut App, sender: &str, funds: &[Coin]) -> AnyResult<()> {
+        let msg = ExecMsg::FundMatching {};
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, funds)?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn withdraw_split(
+        &self,
+        app: &mut App,
+        sender: &str,
+        receivers: &[(&str, Decimal)],
+        amount: Option<u128>,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::WithdrawSplit {
+            receivers: receivers
+                .iter()
+                .map(|(receiver, share)| (receiver.to_string(), *share))
+                .collect(),
+            amount: amount.map(Uint128::new),
+            denom: None,
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn withdraw_direct(
+        &self,
+        app: &mut App,
+        sender: &str,
+        receiver: Option<&str>,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::WithdrawDirect {
+            receiver: receiver.map(str::to_owned),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn withdraw_streamed(
+        &self,
+        app: &mut App,
+        sender: &str,
+        receiver: &str,
+        duration_seconds: u64,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::WithdrawStreamed {
+            receiver: receiver.to_owned(),
+            duration_seconds,
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn claim_stream(&self, app: &mut App, sender: &str) -> AnyResult<()> {
+        let msg = ExecMsg::ClaimStream {};
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn crank(&self, app: &mut App, sender: &str) -> AnyResult<()> {
+        let msg = ExecMsg::Crank {};
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn set_auto_withdraw_threshold(
+        &self,
+        app: &mut App,
+        sender: &str,
+        threshold: Option<u128>,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::SetAutoWithdrawThreshold {
+            threshold: threshold.map(Uint128::new),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn withdraw_all(
+        &self,
+        app: &mut App,
+        sender: &str,
+        receiver: Option<&str>,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::WithdrawAll {
+            receiver: receiver.map(str::to_owned),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn grant_operator(
+        &self,
+        app: &mut App,
+        sender: &str,
+        addr: &str,
+        can_withdraw: bool,
+        can_propose: bool,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::GrantOperator {
+            addr: addr.to_owned(),
+            can_withdraw,
+            can_propose,
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn revoke_operator(&self, app: &mut App, sender: &str, addr: &str) -> AnyResult<()> {
+        let msg = ExecMsg::RevokeOperator {
+            addr: addr.to_owned(),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn withdraw_matching(&self, app: &mut App, sender: &str) -> AnyResult<()> {
+        let msg = ExecMsg::WithdrawMatching {};
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn set_hook(&self, app: &mut App, sender: &str, addr: Option<&str>) -> AnyResult<()> {
+        let msg = ExecMsg::SetHook {
+            addr: addr.map(str::to_owned),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn set_receipt_nft(
+        &self,
+        app: &mut App,
+        sender: &str,
+        receipt: Option<(&Addr, u128)>,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::SetReceiptNft {
+            receipt: receipt.map(|(cw721_addr, min_amount)| ReceiptConfigMsg {
+                cw721_addr: cw721_addr.to_string(),
+                min_amount: Uint128::new(min_amount),
+            }),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn set_metadata(
+        &self,
+        app: &mut App,
+        sender: &str,
+        metadata: Option<ProxyMetadata>,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::SetMetadata { metadata };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    pub fn metadata(&self, app: &App) -> AnyResult<Option<ProxyMetadata>> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Metadata {})?;
+        Ok(resp)
+    }
+
+    #[track_caller]
+    pub fn update_weight(&self, app: &mut App, sender: &str) -> AnyResult<()> {
+        let msg = ExecMsg::UpdateWeight {};
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn migrate(&self, app: &mut App, sender: &str, code_id: CodeId) -> AnyResult<AppResponse> {
+        app.migrate_contract(
+            Addr::unchecked(sender),
+            self.0.clone(),
+            &MigrateMsg {},
+            code_id.0,
+        )
+    }
+
+    #[track_caller]
+    pub fn set_halftime(&self, app: &mut App, sender: &str, halftime: Duration) -> AnyResult<()> {
+        let msg = ExecMsg::SetHalftime { halftime };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn set_decay(&self, app: &mut App, sender: &str, decay: Decimal) -> AnyResult<()> {
+        let msg = ExecMsg::SetDecay { decay };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn set_update_policy(
+        &self,
+        app: &mut App,
+        sender: &str,
+        policy: UpdatePolicyMsg,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::SetUpdatePolicy { policy };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn set_withdraw_cooldown(
+        &self,
+        app: &mut App,
+        sender: &str,
+        cooldown: u64,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::SetWithdrawCooldown { cooldown };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn set_spend_limit(
+        &self,
+        app: &mut App,
+        sender: &str,
+        limit: Option<(u128, u64)>,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::SetSpendLimit {
+            limit: limit.map(|(amount, window)| (Uint128::new(amount), window)),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn set_min_donation(
+        &self,
+        app: &mut App,
+        sender: &str,
+        min_donation: u128,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::SetMinDonation {
+            min_donation: Uint128::new(min_donation),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn set_direct_beneficiary(
+        &self,
+        app: &mut App,
+        sender: &str,
+        addr: Option<&str>,
+    ) -> AnyResult<()> {
+        let msg = ExecMsg::SetDirectBeneficiary {
+            addr: addr.map(str::to_owned),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn block(&self, app: &mut App, sender: &str, addr: &str) -> AnyResult<()> {
+        let msg = ExecMsg::Block {
+            addr: addr.to_owned(),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn unblock(&self, app: &mut App, sender: &str, addr: &str) -> AnyResult<()> {
+        let msg = ExecMsg::Unblock {
+            addr: addr.to_owned(),
+        };
+        app.execute_contract(Addr::unchecked(sender), self.0.clone(), &msg, &[])?;
+
+        Ok(())
+    }
+
     #[track_caller]
     pub fn propose_member(
         &self,
@@ -148,4 +740,383 @@ impl Contract {
             .transpose()
             .map_err(Into::into)
     }
+
+    pub fn proposed_members(&self, app: &App) -> AnyResult<Vec<ProposedMember>> {
+        let resp: ProposedMembersResponse = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::ProposedMembers {
+                start_after: None,
+                limit: None,
+            },
+        )?;
+        Ok(resp.members)
+    }
+
+    pub fn config(&self, app: &App) -> AnyResult<ConfigResponse> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Config {})?;
+        Ok(resp)
+    }
+
+    pub fn owner(&self, app: &App) -> AnyResult<Option<Addr>> {
+        let resp: OwnerResponse = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Owner {})?;
+        Ok(resp.owner)
+    }
+
+    pub fn weight(&self, app: &App) -> AnyResult<u64> {
+        let resp: WeightResponse = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Weight {})?;
+        Ok(resp.weight)
+    }
+
+    pub fn donations(&self, app: &App) -> AnyResult<u64> {
+        let resp: DonationsResponse = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Donations {})?;
+        Ok(resp.donations)
+    }
+
+    pub fn halftime(&self, app: &App) -> AnyResult<Duration> {
+        let resp: HalftimeResponse = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Halftime {})?;
+        Ok(resp.halftime)
+    }
+
+    pub fn last_updated(&self, app: &App) -> AnyResult<Expiration> {
+        let resp: LastUpdatedResponse = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::LastUpdated {})?;
+        Ok(resp.last_updated)
+    }
+
+    pub fn estimated_withdrawable(
+        &self,
+        app: &App,
+        denom: Option<&str>,
+    ) -> AnyResult<EstimatedWithdrawableResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::EstimatedWithdrawable {
+                denom: denom.map(str::to_owned),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn pending_withdrawal(&self, app: &App) -> AnyResult<Option<PendingWithdrawalResponse>> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::PendingWithdrawal {})?;
+        Ok(resp)
+    }
+
+    pub fn total_donated(&self, app: &App) -> AnyResult<TotalDonatedResponse> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::TotalDonated {})?;
+        Ok(resp)
+    }
+
+    pub fn donor(&self, app: &App, addr: &str) -> AnyResult<DonorResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Donor {
+                addr: addr.to_owned(),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn donation_history(
+        &self,
+        app: &App,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> AnyResult<DonationHistoryResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::DonationHistory { start_after, limit },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn contract_version(&self, app: &App) -> AnyResult<ContractVersion> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::ContractVersion {})?;
+        Ok(resp)
+    }
+
+    pub fn balances(&self, app: &App) -> AnyResult<Vec<DenomBalance>> {
+        let resp: BalancesResponse = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Balances {})?;
+        Ok(resp.balances)
+    }
+
+    pub fn status(&self, app: &App) -> AnyResult<StatusResponse> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Status {})?;
+        Ok(resp)
+    }
+
+    pub fn paused(&self, app: &App) -> AnyResult<bool> {
+        let resp: PausedResponse = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Paused {})?;
+        Ok(resp.paused)
+    }
+
+    pub fn ownership(&self, app: &App) -> AnyResult<Ownership<Addr>> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Ownership {})?;
+        Ok(resp)
+    }
+
+    pub fn weight_at(&self, app: &App, height: u64) -> AnyResult<Option<u64>> {
+        let resp: WeightAtResponse = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::WeightAt { height })?;
+        Ok(resp.weight)
+    }
+
+    pub fn weight_history(
+        &self,
+        app: &App,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> AnyResult<Vec<(u64, u64)>> {
+        let resp: WeightHistoryResponse = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::WeightHistory { start_after, limit },
+        )?;
+        Ok(resp
+            .weights
+            .into_iter()
+            .map(|change| (change.height, change.weight))
+            .collect())
+    }
+
+    pub fn next_update(&self, app: &App) -> AnyResult<NextUpdateResponse> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::NextUpdate {})?;
+        Ok(resp)
+    }
+
+    pub fn stream(&self, app: &App) -> AnyResult<Option<StreamResponse>> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Stream {})?;
+        Ok(resp)
+    }
+
+    pub fn withdraw_proposal(
+        &self,
+        app: &App,
+        id: u64,
+    ) -> AnyResult<Option<WithdrawProposalResponse>> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::WithdrawProposal { id })?;
+        Ok(resp)
+    }
+
+    pub fn withdraw_proposals(&self, app: &App) -> AnyResult<Vec<WithdrawProposalResponse>> {
+        let resp: WithdrawProposalsResponse = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::WithdrawProposals {
+                start_after: None,
+                limit: None,
+            },
+        )?;
+        Ok(resp.proposals)
+    }
+
+    pub fn operators(&self, app: &App) -> AnyResult<OperatorsResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Operators {
+                start_after: None,
+                limit: None,
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn withdrawal_history(
+        &self,
+        app: &App,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> AnyResult<WithdrawalHistoryResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::WithdrawalHistory { start_after, limit },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn stats(&self, app: &App) -> AnyResult<StatsResponse> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Stats {})?;
+        Ok(resp)
+    }
+
+    pub fn top_donors(&self, app: &App, limit: Option<u32>) -> AnyResult<TopDonorsResponse> {
+        self.top_donors_after(app, None, limit)
+    }
+
+    pub fn top_donors_after(
+        &self,
+        app: &App,
+        start_after: Option<&str>,
+        limit: Option<u32>,
+    ) -> AnyResult<TopDonorsResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::TopDonors {
+                start_after: start_after.map(str::to_owned),
+                limit,
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn goal_progress(&self, app: &App) -> AnyResult<GoalProgressResponse> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::GoalProgress {})?;
+        Ok(resp)
+    }
+
+    pub fn is_blocked(&self, app: &App, addr: &str) -> AnyResult<IsBlockedResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::IsBlocked {
+                addr: addr.to_owned(),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn blocklist(
+        &self,
+        app: &App,
+        start_after: Option<&str>,
+        limit: Option<u32>,
+    ) -> AnyResult<BlocklistResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Blocklist {
+                start_after: start_after.map(str::to_owned),
+                limit,
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn donor_tier(&self, app: &App, addr: &str) -> AnyResult<DonorTierResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::DonorTier {
+                addr: addr.to_owned(),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn daily_stats(
+        &self,
+        app: &App,
+        from_day: u64,
+        to_day: u64,
+    ) -> AnyResult<DailyStatsResponse> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::DailyStats { from_day, to_day })?;
+        Ok(resp)
+    }
+
+    pub fn referrer_stats(&self, app: &App, addr: &str) -> AnyResult<ReferrerStatsResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::ReferrerStats {
+                addr: addr.to_owned(),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn simulate_donation(
+        &self,
+        app: &App,
+        amount: u128,
+    ) -> AnyResult<SimulateDonationResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::SimulateDonation {
+                amount: Uint128::new(amount),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn donors(
+        &self,
+        app: &App,
+        start_after: Option<&str>,
+        limit: Option<u32>,
+    ) -> AnyResult<DonorsResponse> {
+        self.inactive_donors(app, start_after, limit, None)
+    }
+
+    pub fn inactive_donors(
+        &self,
+        app: &App,
+        start_after: Option<&str>,
+        limit: Option<u32>,
+        inactive_since: Option<u64>,
+    ) -> AnyResult<DonorsResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Donors {
+                start_after: start_after.map(str::to_owned),
+                limit,
+                inactive_since,
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn referrers(
+        &self,
+        app: &App,
+        start_after: Option<&str>,
+        limit: Option<u32>,
+    ) -> AnyResult<ReferrersResponse> {
+        let resp = app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Referrers {
+                start_after: start_after.map(str::to_owned),
+                limit,
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn largest_donation(&self, app: &App) -> AnyResult<Option<LargestDonationResponse>> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::LargestDonation {})?;
+        Ok(resp)
+    }
 }
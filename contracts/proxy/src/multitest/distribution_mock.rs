@@ -0,0 +1,188 @@
+use anyhow::Result as AnyResult;
+use common::multitest::App;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, Int128, MessageInfo, Response,
+    StdError, StdResult, WasmMsg,
+};
+use cw20::Cw20ReceiveMsg;
+use cw_multi_test::{ContractWrapper, Executor};
+use cw_storage_plus::Item;
+
+use crate::msg::{
+    ExecMsg as ProxyExecMsg, PendingWithdrawalResponse, QueryMsg as ProxyQueryMsg,
+    WithdrawableResponse,
+};
+
+// stand-in for a distribution contract which reports no WithdrawResponse data, only amount and denom attributes
+// it accepts the same messages the proxy sends and pays its whole balance back on withdraw,
+// or fails every withdraw if told so, every Withdraw received is recorded
+// crank_on_withdraw makes it call back the proxy's Crank before paying, like a reentrant contract could
+// the proxy's PendingWithdrawal seen by the last Withdraw is kept too
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub fail_withdraw: bool,
+    pub crank_on_withdraw: bool,
+}
+
+#[cw_serde]
+pub enum ExecMsg {
+    Distribute {},
+    Withdraw { weight: u64, diff: Int128 },
+    Receive(Cw20ReceiveMsg),
+}
+
+// Withdrawable matches the proxy's DistributionQueryMsg
+#[cw_serde]
+pub enum QueryMsg {
+    Withdrawable { proxy: String, denom: String },
+    Withdrawals {},
+    PendingSeen {},
+}
+
+const FAIL_WITHDRAW: Item<bool> = Item::new("fail_withdraw");
+const CRANK_ON_WITHDRAW: Item<bool> = Item::new("crank_on_withdraw");
+const WITHDRAWALS: Item<Vec<(u64, Int128)>> = Item::new("withdrawals"); // (weight, diff)
+const PENDING_SEEN: Item<Option<PendingWithdrawalResponse>> = Item::new("pending_seen");
+
+fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    FAIL_WITHDRAW.save(deps.storage, &msg.fail_withdraw)?;
+    CRANK_ON_WITHDRAW.save(deps.storage, &msg.crank_on_withdraw)?;
+    WITHDRAWALS.save(deps.storage, &vec![])?;
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecMsg) -> StdResult<Response> {
+    match msg {
+        ExecMsg::Distribute {} | ExecMsg::Receive(_) => Ok(Response::new()),
+        ExecMsg::Withdraw { weight, diff } => {
+            if FAIL_WITHDRAW.load(deps.storage)? {
+                return Err(StdError::generic_err("distribution is broken"));
+            }
+            WITHDRAWALS.update(deps.storage, |mut withdrawals| -> StdResult<_> {
+                withdrawals.push((weight, diff));
+                Ok(withdrawals)
+            })?;
+            let pending: Option<PendingWithdrawalResponse> = deps
+                .querier
+                .query_wasm_smart(&info.sender, &ProxyQueryMsg::PendingWithdrawal {})?;
+            PENDING_SEEN.save(deps.storage, &pending)?;
+
+            let mut resp = Response::new();
+            if CRANK_ON_WITHDRAW.load(deps.storage)? {
+                let crank_msg = WasmMsg::Execute {
+                    contract_addr: info.sender.to_string(),
+                    msg: to_json_binary(&ProxyExecMsg::Crank {})?,
+                    funds: vec![],
+                };
+                resp = resp.add_message(crank_msg);
+            }
+
+            // single proxy in tests => everything distributed so far is its share
+            #[allow(deprecated)]
+            let balance = deps.querier.query_all_balances(env.contract.address)?;
+            if balance.is_empty() {
+                let denom = pending.map(|pending| pending.denom).unwrap_or_default();
+                return Ok(resp
+                    .add_attribute("amount", "0")
+                    .add_attribute("denom", denom));
+            }
+            for coin in &balance {
+                resp = resp
+                    .add_attribute("amount", coin.amount.to_string())
+                    .add_attribute("denom", &coin.denom);
+            }
+
+            let bank_msg = BankMsg::Send {
+                to_address: info.sender.into_string(),
+                amount: balance,
+            };
+            Ok(resp.add_message(bank_msg))
+        }
+    }
+}
+
+fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Withdrawable { denom, .. } => {
+            let balance = deps.querier.query_balance(env.contract.address, denom)?;
+            to_json_binary(&WithdrawableResponse {
+                amount: balance.amount,
+            })
+        }
+        QueryMsg::Withdrawals {} => to_json_binary(&WITHDRAWALS.load(deps.storage)?),
+        QueryMsg::PendingSeen {} => to_json_binary(&PENDING_SEEN.may_load(deps.storage)?.flatten()),
+    }
+}
+
+#[derive(Debug)]
+pub struct DistributionMock(Addr);
+
+impl DistributionMock {
+    #[track_caller]
+    pub fn instantiate(app: &mut App) -> AnyResult<Self> {
+        Self::instantiate_with(app, false, false)
+    }
+
+    #[track_caller]
+    pub fn failing(app: &mut App) -> AnyResult<Self> {
+        Self::instantiate_with(app, true, false)
+    }
+
+    #[track_caller]
+    pub fn reentrant(app: &mut App) -> AnyResult<Self> {
+        Self::instantiate_with(app, false, true)
+    }
+
+    #[track_caller]
+    fn instantiate_with(
+        app: &mut App,
+        fail_withdraw: bool,
+        crank_on_withdraw: bool,
+    ) -> AnyResult<Self> {
+        let contract = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(contract));
+
+        app.instantiate_contract(
+            code_id,
+            Addr::unchecked("distribution owner"),
+            &InstantiateMsg {
+                fail_withdraw,
+                crank_on_withdraw,
+            },
+            &[],
+            "Distribution mock",
+            None,
+        )
+        .map(Self)
+    }
+
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+
+    // (weight, diff) of every Withdraw received so far
+    pub fn withdrawals(&self, app: &App) -> AnyResult<Vec<(u64, i128)>> {
+        let resp: Vec<(u64, Int128)> = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Withdrawals {})?;
+        Ok(resp
+            .into_iter()
+            .map(|(weight, diff)| (weight, diff.i128()))
+            .collect())
+    }
+
+    // what the proxy reported as pending while the last Withdraw ran
+    pub fn pending_seen(&self, app: &App) -> AnyResult<Option<PendingWithdrawalResponse>> {
+        let resp = app
+            .wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::PendingSeen {})?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::Result as AnyResult;
+use common::multitest::App;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+    StdResult,
+};
+use cw_multi_test::{ContractWrapper, Executor};
+use cw_storage_plus::Item;
+
+use crate::msg::ProxyHookMsg;
+
+// notification bot listening to proxy donations, can be told to always fail
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub fail: bool,
+}
+
+const FAIL: Item<bool> = Item::new("fail");
+const NOTIFIED: Item<Vec<ProxyHookMsg>> = Item::new("notified");
+
+fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    FAIL.save(deps.storage, &msg.fail)?;
+    NOTIFIED.save(deps.storage, &vec![])?;
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: ProxyHookMsg) -> StdResult<Response> {
+    if FAIL.load(deps.storage)? {
+        return Err(StdError::generic_err("hook is broken"));
+    }
+
+    NOTIFIED.update(deps.storage, |mut notified| -> StdResult<_> {
+        notified.push(msg);
+        Ok(notified)
+    })?;
+    Ok(Response::new())
+}
+
+fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&NOTIFIED.load(deps.storage)?)
+}
+
+#[derive(Debug)]
+pub struct HookMock(Addr);
+
+impl HookMock {
+    #[track_caller]
+    pub fn instantiate(app: &mut App, fail: bool) -> AnyResult<Self> {
+        let contract = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(contract));
+
+        app.instantiate_contract(
+            code_id,
+            Addr::unchecked("hook owner"),
+            &InstantiateMsg { fail },
+            &[],
+            "Hook mock",
+            None,
+        )
+        .map(Self)
+    }
+
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+
+    pub fn notified(&self, app: &App) -> AnyResult<Vec<ProxyHookMsg>> {
+        let resp = app.wrap().query_wasm_smart(self.0.clone(), &Empty {})?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,257 @@
+use anyhow::Result as AnyResult;
+use common::multitest::App;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    Addr, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Order, Reply, Response,
+    StdError, StdResult, Storage,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw_multi_test::{ContractWrapper, Executor};
+use cw_ownable::Ownership;
+use cw_storage_plus::{Index, Item, Map};
+use semver::Version;
+
+use super::Contract;
+use crate::error::ContractError;
+use crate::msg::{ExecMsg as ProxyExecMsg, InstantiateMsg as ProxyInstantiateMsg};
+use crate::state::{State, DONORS, STATE};
+
+// proxy from before cw2, stores exactly what the baseline instantiate did:
+// a single denom, and halftime and last_updated kept as plain seconds
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub weight: u64,
+    pub denom: String,
+    pub direct_part: Decimal,
+    pub distribution_contract: String,
+    pub membership_contract: String,
+    pub halftime: u64,
+}
+
+#[cw_serde]
+struct Config {
+    denom: String,
+    direct_part: Decimal,
+    distribution_contract: Addr,
+    membership_contract: Addr,
+    is_closed: bool,
+}
+
+fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    Item::new("owner").save(deps.storage, &Addr::unchecked(msg.owner))?;
+    Item::new("weight").save(deps.storage, &msg.weight)?;
+    Item::new("donations").save(deps.storage, &0u64)?;
+    Item::new("config").save(
+        deps.storage,
+        &Config {
+            denom: msg.denom,
+            direct_part: msg.direct_part,
+            distribution_contract: Addr::unchecked(msg.distribution_contract),
+            membership_contract: Addr::unchecked(msg.membership_contract),
+            is_closed: false,
+        },
+    )?;
+    Item::new("halftime").save(deps.storage, &msg.halftime)?;
+    Item::new("last_updated").save(deps.storage, &env.block.time.seconds())?;
+    Ok(Response::new())
+}
+
+fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Err(StdError::generic_err("legacy proxy"))
+}
+
+fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Err(StdError::generic_err("legacy proxy"))
+}
+
+#[track_caller]
+pub fn instantiate_legacy(app: &mut App, admin: &str, msg: &InstantiateMsg) -> AnyResult<Contract> {
+    let contract = ContractWrapper::new(execute, instantiate, query);
+    let code_id = app.store_code(Box::new(contract));
+
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(admin),
+        msg,
+        &[],
+        "Legacy proxy",
+        Some(admin.to_owned()),
+    )
+    .map(Contract::from_addr)
+}
+
+// current proxy code recording another version in cw2, stands for code deployed before (or after) this one
+#[cw_serde]
+pub struct VersionedInstantiateMsg {
+    pub version: String,
+    pub msg: ProxyInstantiateMsg,
+}
+
+fn instantiate_versioned(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: VersionedInstantiateMsg,
+) -> Result<Response, ContractError> {
+    let resp = crate::instantiate(deps.branch(), env, info, msg.msg)?;
+    // the owner was a plain item before cw_ownable
+    if Version::parse(&msg.version).map_err(|err| StdError::generic_err(err.to_string()))?
+        < Version::new(0, 3, 0)
+    {
+        if let Some(owner) = cw_ownable::get_ownership(deps.storage)?.owner {
+            Item::new("owner").save(deps.storage, &owner)?;
+        }
+        Item::<Ownership<Addr>>::new("ownership").remove(deps.storage);
+    }
+    split_legacy(deps.storage, &msg.version)?;
+    set_contract_version(deps.storage, "proxy", msg.version)?;
+    Ok(resp)
+}
+
+fn is_before(version: &str, major: u64, minor: u64) -> StdResult<bool> {
+    let version = Version::parse(version).map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(version < Version::new(major, minor, 0))
+}
+
+// the counters were separate items before 0.5.0
+fn legacy_state(version: &str) -> StdResult<bool> {
+    is_before(version, 0, 5)
+}
+
+// the leaderboard was a map of its own before 0.6.0
+fn legacy_index(version: &str) -> StdResult<bool> {
+    is_before(version, 0, 6)
+}
+
+// lifetime donations weren't counted before 0.4.0
+fn legacy_donations(version: &str) -> StdResult<bool> {
+    is_before(version, 0, 4)
+}
+
+fn split_state(storage: &mut dyn Storage, version: &str) -> StdResult<()> {
+    let state = STATE.load(storage)?;
+    STATE.remove(storage);
+    Item::new("weight").save(storage, &state.weight)?;
+    Item::new("donations").save(storage, &state.epoch_donations)?;
+    if !legacy_donations(version)? {
+        Item::new("lifetime_donations").save(storage, &state.lifetime_donations)?;
+    }
+    Item::new("total_donated").save(storage, &state.total_donated)?;
+    Item::new("halftime").save(storage, &state.halftime)?;
+    Item::new("last_updated").save(storage, &state.last_updated)
+}
+
+fn join_state(storage: &mut dyn Storage, version: &str) -> StdResult<()> {
+    let lifetime_donations = match legacy_donations(version)? {
+        true => 0,
+        false => Item::new("lifetime_donations").load(storage)?,
+    };
+    let state = State {
+        weight: Item::new("weight").load(storage)?,
+        epoch_donations: Item::new("donations").load(storage)?,
+        lifetime_donations,
+        total_donated: Item::new("total_donated").load(storage)?,
+        halftime: Item::new("halftime").load(storage)?,
+        last_updated: Item::new("last_updated").load(storage)?,
+    };
+    STATE.save(storage, &state)
+}
+
+const LEGACY_DONORS_BY_AMOUNT: Map<(u128, &Addr), Empty> = Map::new("donors_by_amount");
+
+fn split_donor_index(storage: &mut dyn Storage) -> StdResult<()> {
+    let donors = DONORS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (addr, donor) in donors {
+        DONORS.idx.amount.remove(storage, addr.as_bytes(), &donor)?;
+        LEGACY_DONORS_BY_AMOUNT.save(storage, (donor.total.u128(), &addr), &Empty {})?;
+    }
+    Ok(())
+}
+
+fn join_donor_index(storage: &mut dyn Storage) -> StdResult<()> {
+    let donors = DONORS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (addr, donor) in donors {
+        DONORS.idx.amount.save(storage, addr.as_bytes(), &donor)?;
+    }
+    LEGACY_DONORS_BY_AMOUNT.clear(storage);
+    Ok(())
+}
+
+// turns the current state into the layout of the version
+fn split_legacy(storage: &mut dyn Storage, version: &str) -> StdResult<()> {
+    if legacy_state(version)? {
+        split_state(storage, version)?;
+    }
+    if legacy_index(version)? {
+        split_donor_index(storage)?;
+    }
+    Ok(())
+}
+
+fn join_legacy(storage: &mut dyn Storage, version: &str) -> StdResult<()> {
+    if legacy_state(version)? {
+        join_state(storage, version)?;
+    }
+    if legacy_index(version)? {
+        join_donor_index(storage)?;
+    }
+    Ok(())
+}
+
+// the current code needs the current layout, so it only exists during the call
+fn execute_versioned(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ProxyExecMsg,
+) -> Result<Response, ContractError> {
+    let version = get_contract_version(deps.storage)?.version;
+    join_legacy(deps.storage, &version)?;
+    let resp = crate::execute(deps.branch(), env, info, msg)?;
+    split_legacy(deps.storage, &version)?;
+    Ok(resp)
+}
+
+fn reply_versioned(mut deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
+    let version = get_contract_version(deps.storage)?.version;
+    join_legacy(deps.storage, &version)?;
+    let resp = crate::reply(deps.branch(), env, reply)?;
+    split_legacy(deps.storage, &version)?;
+    Ok(resp)
+}
+
+#[track_caller]
+pub fn instantiate_with_version(
+    app: &mut App,
+    admin: &str,
+    version: &str,
+    msg: &ProxyInstantiateMsg,
+) -> AnyResult<Contract> {
+    let contract = ContractWrapper::new(execute_versioned, instantiate_versioned, crate::query)
+        .with_reply(reply_versioned);
+    let code_id = app.store_code(Box::new(contract));
+
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(admin),
+        &VersionedInstantiateMsg {
+            version: version.to_owned(),
+            msg: msg.clone(),
+        },
+        &[],
+        "Versioned proxy",
+        Some(admin.to_owned()),
+    )
+    .map(Contract::from_addr)
+}
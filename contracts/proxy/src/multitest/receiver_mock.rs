@@ -0,0 +1,86 @@
+use anyhow::Result as AnyResult;
+use common::multitest::App;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Coin, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+    StdResult,
+};
+use cw_multi_test::{ContractWrapper, Executor};
+use cw_storage_plus::Item;
+
+// treasury which only takes funds through Deposit, can be told to always fail
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub fail: bool,
+}
+
+#[cw_serde]
+pub enum ExecMsg {
+    Deposit {},
+}
+
+const FAIL: Item<bool> = Item::new("fail");
+const DEPOSITS: Item<Vec<Coin>> = Item::new("deposits");
+
+fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    FAIL.save(deps.storage, &msg.fail)?;
+    DEPOSITS.save(deps.storage, &vec![])?;
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecMsg) -> StdResult<Response> {
+    let ExecMsg::Deposit {} = msg;
+    if FAIL.load(deps.storage)? {
+        return Err(StdError::generic_err("treasury is locked"));
+    }
+
+    DEPOSITS.update(deps.storage, |mut deposits| -> StdResult<_> {
+        deposits.extend(info.funds);
+        Ok(deposits)
+    })?;
+    Ok(Response::new())
+}
+
+fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&DEPOSITS.load(deps.storage)?)
+}
+
+#[derive(Debug)]
+pub struct ReceiverMock(Addr);
+
+impl ReceiverMock {
+    #[track_caller]
+    pub fn instantiate(app: &mut App, fail: bool) -> AnyResult<Self> {
+        let contract = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(contract));
+
+        app.instantiate_contract(
+            code_id,
+            Addr::unchecked("treasury owner"),
+            &InstantiateMsg { fail },
+            &[],
+            "Receiver mock",
+            None,
+        )
+        .map(Self)
+    }
+
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+
+    pub fn deposit_msg() -> Binary {
+        to_json_binary(&ExecMsg::Deposit {}).unwrap()
+    }
+
+    pub fn deposits(&self, app: &App) -> AnyResult<Vec<Coin>> {
+        let resp = app.wrap().query_wasm_smart(self.0.clone(), &Empty {})?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,6020 @@
+use common::multitest::{mock_app, App};
+use cosmwasm_std::{
+    coin, coins, to_json_binary, Addr, Coin, Decimal, Empty, Event, IbcTimeout, Timestamp, Uint128,
+    WasmMsg,
+};
+use cw2::ContractVersion;
+use cw20::{BalanceResponse, Cw20Coin, Cw20QueryMsg, Cw20ReceiveMsg};
+use cw_multi_test::{Contract as MtContract, ContractWrapper, Executor};
+use cw_ownable::{Action, Ownership, OwnershipError};
+use cw_utils::{Duration, Expiration, PaymentError};
+use distribution::multitest::membership_mock::MembershipMock;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ConfigResponse, Cw20HookMsg, DailyBucket, DecayFunds, DenomBalance,
+    EstimatedWithdrawableResponse, ExecMsg, IbcWithdraw, InstantiateMsg, NextUpdateResponse,
+    Operator, PendingWithdrawalResponse, ProxyHookMsg, ProxyMetadata, RemoteDonor, StatusResponse,
+    StreamResponse, SudoMsg, TopDonorsResponse, UpdatePolicyMsg, WithdrawProposalResponse,
+    WithdrawalRecordResponse,
+};
+use crate::multitest::distribution_mock::DistributionMock;
+use crate::multitest::hook_mock::HookMock;
+use crate::multitest::legacy_mock::{
+    instantiate_legacy, instantiate_with_version, InstantiateMsg as LegacyInstantiateMsg,
+};
+use crate::multitest::receiver_mock::ReceiverMock;
+use crate::multitest::{CodeId, Contract};
+
+const DENOM: &str = "ORAI";
+const OWNER: &str = "owner";
+const DONOR: &str = "donor";
+
+fn app_with_balances(balances: &[(&str, Vec<Coin>)]) -> App {
+    let mut app = mock_app();
+    app.init_modules(|router, _api, storage| {
+        for (addr, coins) in balances {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(*addr), coins.clone())
+                .unwrap();
+        }
+    });
+    app
+}
+
+fn app_with_donors(donors: &[(&str, u128)]) -> App {
+    let balances: Vec<_> = donors
+        .iter()
+        .map(|(donor, amount)| (*donor, coins(*amount, DENOM)))
+        .collect();
+    app_with_balances(&balances)
+}
+
+fn init_msg(distribution: &Addr) -> InstantiateMsg {
+    InstantiateMsg {
+        owner: OWNER.to_owned(),
+        weight: 10,
+        accepted_denoms: vec![DENOM.to_owned()],
+        direct_part: Decimal::percent(10),
+        distribution_contracts: vec![(distribution.to_string(), Decimal::one())],
+        membership_contract: "membership".to_owned(),
+        halftime: Duration::Time(3600 * 24 * 30),
+        cw20_address: None,
+        min_donation: Uint128::zero(),
+        direct_beneficiary: None,
+        goal: None,
+        protocol_fee: Decimal::zero(),
+        treasury: None,
+        tiers: vec![],
+        bucket_width: None,
+        matching_ratio: None,
+        decay: None,
+        referral_bps: 0,
+        burn_part: Decimal::zero(),
+        withdraw_cooldown: 0,
+        auto_withdraw_threshold: None,
+        keeper_fee_bps: 0,
+        max_weight: None,
+        decay_funds: DecayFunds::KeepOnProxy,
+        approvers: vec![],
+        threshold: 0,
+        proposal_duration: None,
+        skip_contract_check: true, // "membership" is a plain account here
+        metadata: None,
+        member_id: None,
+        instantiated_by: None,
+    }
+}
+
+fn instantiate_proxy(app: &mut App, init_msg: &InstantiateMsg) -> Contract {
+    CodeId::store_code(app)
+        .instantiate(app, OWNER, init_msg, "Proxy")
+        .unwrap()
+}
+
+// deploy the mock distribution contract and a proxy pointing to it
+fn setup(app: &mut App) -> (Contract, DistributionMock) {
+    let distribution = DistributionMock::instantiate(app).unwrap();
+    let proxy = instantiate_proxy(app, &init_msg(distribution.addr()));
+
+    (proxy, distribution)
+}
+
+fn balance(app: &App, addr: impl Into<String>) -> u128 {
+    app.wrap().query_balance(addr, DENOM).unwrap().amount.u128()
+}
+
+// deprecated for chains dropping the query, the multitest bank still answers it
+#[allow(deprecated)]
+fn all_balances(app: &App, addr: impl Into<String>) -> Vec<Coin> {
+    app.wrap().query_all_balances(addr).unwrap()
+}
+
+fn cw20_contract() -> Box<dyn MtContract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    );
+    Box::new(contract)
+}
+
+fn instantiate_cw20(app: &mut App, symbol: &str, balances: &[(&str, u128)]) -> Addr {
+    let code_id = app.store_code(cw20_contract());
+    let init_msg = cw20_base::msg::InstantiateMsg {
+        name: format!("{symbol} token"),
+        symbol: symbol.to_owned(),
+        decimals: 6,
+        initial_balances: balances
+            .iter()
+            .map(|(address, amount)| Cw20Coin {
+                address: address.to_string(),
+                amount: Uint128::new(*amount),
+            })
+            .collect(),
+        mint: None,
+        marketing: None,
+    };
+
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(OWNER),
+        &init_msg,
+        &[],
+        symbol,
+        None,
+    )
+    .unwrap()
+}
+
+fn instantiate_cw721(app: &mut App, minter: &Addr) -> Addr {
+    let contract = ContractWrapper::new(
+        cw721_base::entry::execute,
+        cw721_base::entry::instantiate,
+        cw721_base::entry::query,
+    );
+    let code_id = app.store_code(Box::new(contract));
+    let init_msg = cw721_base::msg::InstantiateMsg {
+        name: "Receipts".to_owned(),
+        symbol: "RCPT".to_owned(),
+        collection_info_extension: None,
+        minter: Some(minter.to_string()),
+        creator: None,
+        withdraw_address: None,
+    };
+
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(OWNER),
+        &init_msg,
+        &[],
+        "receipts",
+        None,
+    )
+    .unwrap()
+}
+
+fn cw20_balance(app: &App, token: &Addr, addr: &Addr) -> u128 {
+    let resp: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            token,
+            &Cw20QueryMsg::Balance {
+                address: addr.to_string(),
+            },
+        )
+        .unwrap();
+    resp.balance.u128()
+}
+
+#[test]
+fn donate_after_close_fails() {
+    let mut app = app_with_donors(&[(DONOR, 200)]);
+    let (proxy, _) = setup(&mut app);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    proxy.close(&mut app, OWNER).unwrap();
+
+    let err = proxy
+        .donate(&mut app, DONOR, &coins(100, DENOM))
+        .unwrap_err();
+    assert_eq!(ContractError::ContractClosed, err.downcast().unwrap());
+
+    // donor keeps the funds of the rejected donation
+    assert_eq!(balance(&app, DONOR), 100);
+}
+
+#[test]
+fn total_donated_is_lifetime() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    assert_eq!(proxy.total_donated(&app).unwrap().total.u128(), 0);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(250, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(7, DENOM)).unwrap();
+
+    assert_eq!(proxy.total_donated(&app).unwrap().total.u128(), 357);
+
+    // withdraw resets the epoch counter but not the lifetime total
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+
+    assert_eq!(proxy.total_donated(&app).unwrap().total.u128(), 357);
+}
+
+#[test]
+fn epoch_and_lifetime_donations() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let stats = proxy.stats(&app).unwrap();
+    assert_eq!(stats.donations, 1);
+    assert_eq!(stats.lifetime_donations, 3);
+
+    // the new weight comes from the epoch counter only
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(
+        distribution.withdrawals(&app).unwrap(),
+        vec![(10, -8), (2, -1)]
+    );
+    assert_eq!(proxy.weight(&app).unwrap(), 1);
+    assert_eq!(proxy.stats(&app).unwrap().lifetime_donations, 3);
+}
+
+#[test]
+fn donor_totals() {
+    let mut app = app_with_balances(&[
+        ("donor1", coins(1000, DENOM)),
+        ("donor2", coins(1000, DENOM)),
+        ("stranger", coins(1000, "OTHER")),
+    ]);
+    let (proxy, _) = setup(&mut app);
+
+    proxy
+        .donate(&mut app, "donor1", &coins(100, DENOM))
+        .unwrap();
+    proxy.donate(&mut app, "donor2", &coins(30, DENOM)).unwrap();
+    // same block, same donor => accumulates
+    proxy.donate(&mut app, "donor1", &coins(50, DENOM)).unwrap();
+
+    // wrong denom is rejected and never recorded
+    proxy
+        .donate(&mut app, "stranger", &coins(100, "OTHER"))
+        .unwrap_err();
+
+    let donor1 = proxy.donor(&app, "donor1").unwrap();
+    assert_eq!(donor1.total.u128(), 150);
+    assert_eq!(donor1.count, 2);
+    assert_eq!(donor1.last_donated, Some(app.block_info().time.seconds()));
+
+    let donor2 = proxy.donor(&app, "donor2").unwrap();
+    assert_eq!(donor2.total.u128(), 30);
+    assert_eq!(donor2.count, 1);
+
+    let stranger = proxy.donor(&app, "stranger").unwrap();
+    assert_eq!(stranger.total.u128(), 0);
+    assert_eq!(stranger.count, 0);
+    assert_eq!(stranger.last_donated, None);
+
+    let err = proxy.donor(&app, "Not An Address").unwrap_err();
+    assert!(err.to_string().contains("Invalid donor address"));
+}
+
+#[test]
+fn donors_pagination() {
+    let donors = [
+        "donor1", "donor2", "donor3", "donor4", "donor5", "donor6", "donor7",
+    ];
+    let balances: Vec<_> = donors.iter().map(|donor| (*donor, 1000)).collect();
+    let mut app = app_with_donors(&balances);
+    let (proxy, _) = setup(&mut app);
+
+    // donate out of order, listing is by address anyway
+    for (i, donor) in donors.iter().enumerate().rev() {
+        proxy
+            .donate(&mut app, donor, &coins(10 * (i as u128 + 1), DENOM))
+            .unwrap();
+    }
+    proxy.donate(&mut app, "donor3", &coins(5, DENOM)).unwrap();
+
+    let mut listed = vec![];
+    let mut start_after = None;
+    let mut pages = 0;
+    loop {
+        let page = proxy.donors(&app, start_after.as_deref(), Some(3)).unwrap();
+        if page.donors.is_empty() {
+            break;
+        }
+        pages += 1;
+        start_after = page.donors.last().map(|donor| donor.addr.to_string());
+        listed.extend(page.donors);
+    }
+    assert_eq!(pages, 3);
+
+    let addrs: Vec<_> = listed.iter().map(|donor| donor.addr.as_str()).collect();
+    assert_eq!(addrs, donors);
+    assert_eq!(listed[2].total.u128(), 35);
+    assert_eq!(listed[2].count, 2);
+    assert_eq!(listed[6].total.u128(), 70);
+    assert_eq!(listed[6].count, 1);
+
+    // start_after is exclusive
+    let page = proxy.donors(&app, Some("donor6"), None).unwrap();
+    assert_eq!(page.donors.len(), 1);
+    assert_eq!(page.donors[0].addr.as_str(), "donor7");
+
+    let err = proxy
+        .donors(&app, Some("Not An Address"), None)
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid start_after address"));
+}
+
+#[test]
+fn inactive_donors() {
+    let mut app = app_with_donors(&[("donor_a", 1000), ("donor_b", 1000), ("donor_c", 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    proxy
+        .donate(&mut app, "donor_a", &coins(100, DENOM))
+        .unwrap();
+    proxy
+        .donate(&mut app, "donor_c", &coins(100, DENOM))
+        .unwrap();
+    let first = app.block_info().time.seconds();
+    app.update_block(|block| block.time = block.time.plus_seconds(1000));
+    proxy
+        .donate(&mut app, "donor_b", &coins(100, DENOM))
+        .unwrap();
+    let second = app.block_info().time.seconds();
+
+    assert_eq!(
+        proxy.donor(&app, "donor_b").unwrap().last_donated,
+        Some(second)
+    );
+
+    let cutoff = Some(first + 500);
+    let inactive = proxy
+        .inactive_donors(&app, None, None, cutoff)
+        .unwrap()
+        .donors;
+    let addrs: Vec<_> = inactive.iter().map(|donor| donor.addr.as_str()).collect();
+    assert_eq!(addrs, ["donor_a", "donor_c"]);
+    assert_eq!(inactive[0].last_donated, Some(first));
+
+    // filtered donors don't use up the page
+    let page = proxy
+        .inactive_donors(&app, Some("donor_a"), Some(1), cutoff)
+        .unwrap();
+    assert_eq!(page.donors.len(), 1);
+    assert_eq!(page.donors[0].addr.as_str(), "donor_c");
+    assert_eq!(page.next_start_after, None);
+    let page = proxy
+        .inactive_donors(&app, Some("donor_c"), Some(1), cutoff)
+        .unwrap();
+    assert!(page.donors.is_empty());
+
+    // the cutoff is exclusive
+    let page = proxy
+        .inactive_donors(&app, None, None, Some(first))
+        .unwrap();
+    assert!(page.donors.is_empty());
+    assert_eq!(
+        proxy
+            .inactive_donors(&app, None, None, Some(second + 1))
+            .unwrap()
+            .donors
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn inactive_donors_scan_is_bounded() {
+    let active: Vec<_> = (0..11).map(|idx| format!("donor_{idx:02}")).collect();
+    let mut balances: Vec<_> = active.iter().map(|donor| (donor.as_str(), 1000)).collect();
+    balances.push(("old_donor", 1000));
+    let mut app = app_with_donors(&balances);
+    let (proxy, _) = setup(&mut app);
+
+    proxy
+        .donate(&mut app, "old_donor", &coins(100, DENOM))
+        .unwrap();
+    let cutoff = Some(app.block_info().time.seconds() + 1);
+    app.update_block(|block| block.time = block.time.plus_seconds(1000));
+    for donor in &active {
+        proxy.donate(&mut app, donor, &coins(100, DENOM)).unwrap();
+    }
+
+    // one asked for scans ten, where it stopped is where the next page starts
+    let page = proxy.inactive_donors(&app, None, Some(1), cutoff).unwrap();
+    assert!(page.donors.is_empty());
+    assert_eq!(page.next_start_after, Some(Addr::unchecked("donor_09")));
+    let page = proxy
+        .inactive_donors(&app, Some("donor_09"), Some(1), cutoff)
+        .unwrap();
+    assert_eq!(page.donors[0].addr.as_str(), "old_donor");
+    assert_eq!(page.next_start_after, None);
+
+    // a full page points at its last donor
+    let page = proxy.donors(&app, None, Some(2)).unwrap();
+    assert_eq!(page.next_start_after, Some(Addr::unchecked("donor_01")));
+}
+
+#[test]
+fn cw20_donation() {
+    let mut app = mock_app();
+    let token = instantiate_cw20(&mut app, "DON", &[(DONOR, 1000)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            cw20_address: Some(token.to_string()),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    proxy.donate_cw20(&mut app, DONOR, &token, 100).unwrap();
+
+    // 10% stays on the proxy, the rest goes to distribution
+    assert_eq!(cw20_balance(&app, &token, proxy.addr()), 10);
+    assert_eq!(cw20_balance(&app, &token, distribution.addr()), 90);
+
+    let donor = proxy.donor(&app, DONOR).unwrap();
+    assert_eq!(donor.total.u128(), 100);
+    assert_eq!(donor.count, 1);
+}
+
+#[test]
+fn cw20_donation_from_unknown_token() {
+    let mut app = mock_app();
+    let token = instantiate_cw20(&mut app, "DON", &[(DONOR, 1000)]);
+    let other = instantiate_cw20(&mut app, "OTH", &[(DONOR, 1000)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            cw20_address: Some(token.to_string()),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    let err = proxy.donate_cw20(&mut app, DONOR, &other, 100).unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+    assert_eq!(cw20_balance(&app, &other, &Addr::unchecked(DONOR)), 1000);
+}
+
+#[test]
+fn donate_and_withdraw_in_two_denoms() {
+    let mut app = app_with_balances(&[(DONOR, vec![coin(100, DENOM), coin(200, "USDC")])]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            accepted_denoms: vec![DENOM.to_owned(), "USDC".to_owned()],
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(200, "USDC")).unwrap();
+
+    // distribution part is forwarded in the denom it was donated in
+    let balances = all_balances(&app, distribution.addr());
+    assert_eq!(balances, vec![coin(90, DENOM), coin(180, "USDC")]);
+
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    proxy
+        .withdraw(&mut app, OWNER, None, None, Some("USDC"))
+        .unwrap();
+
+    let balances = all_balances(&app, OWNER);
+    assert_eq!(balances, vec![coin(100, DENOM), coin(200, "USDC")]);
+}
+
+#[test]
+fn donate_in_not_accepted_denom() {
+    let mut app = app_with_balances(&[(DONOR, vec![coin(100, DENOM), coin(100, "USDC")])]);
+    let (proxy, _) = setup(&mut app);
+
+    let err = proxy
+        .donate(&mut app, DONOR, &coins(100, "USDC"))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::PaymentError(PaymentError::MissingDenom(DENOM.to_owned())),
+        err.downcast().unwrap()
+    );
+
+    let err = proxy.donate(&mut app, DONOR, &[]).unwrap_err();
+    assert_eq!(
+        ContractError::PaymentError(PaymentError::NoFunds {}),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn extra_denoms_are_refunded() {
+    let mut app = app_with_balances(&[(
+        DONOR,
+        vec![coin(100, "AAA"), coin(100, DENOM), coin(100, "ZZZ")],
+    )]);
+    let (proxy, distribution) = setup(&mut app);
+
+    // correct denom only
+    proxy.donate(&mut app, DONOR, &coins(50, DENOM)).unwrap();
+    assert_eq!(balance(&app, DONOR), 50);
+
+    // correct denom plus dust of other ones
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &[coin(5, "AAA"), coin(50, DENOM), coin(7, "ZZZ")],
+        )
+        .unwrap();
+    let wasm = resp.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+    let refunded = wasm
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "refunded")
+        .unwrap();
+    assert_eq!(refunded.value, "AAA,ZZZ");
+
+    assert_eq!(
+        all_balances(&app, DONOR),
+        vec![coin(100, "AAA"), coin(100, "ZZZ")]
+    );
+    assert_eq!(balance(&app, distribution.addr()), 90);
+    assert_eq!(proxy.donor(&app, DONOR).unwrap().total.u128(), 100);
+
+    // none of the accepted denoms => whole donation fails
+    proxy
+        .donate(&mut app, DONOR, &[coin(5, "AAA"), coin(7, "ZZZ")])
+        .unwrap_err();
+    assert_eq!(
+        all_balances(&app, DONOR),
+        vec![coin(100, "AAA"), coin(100, "ZZZ")]
+    );
+}
+
+#[test]
+fn invalid_accepted_denoms() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let code_id = CodeId::store_code(&mut app);
+
+    let err = code_id
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                accepted_denoms: vec![],
+                ..init_msg(distribution.addr())
+            },
+            "Proxy",
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::NoAcceptedDenoms, err.downcast().unwrap());
+
+    let err = code_id
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                accepted_denoms: vec![DENOM.to_owned(), "USDC".to_owned(), DENOM.to_owned()],
+                ..init_msg(distribution.addr())
+            },
+            "Proxy",
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::DuplicatedDenom(DENOM.to_owned()),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn invalid_denom_format() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let code_id = CodeId::store_code(&mut app);
+    let instantiate = |app: &mut App, denom: &str| {
+        code_id
+            .instantiate(
+                app,
+                OWNER,
+                &InstantiateMsg {
+                    accepted_denoms: vec![DENOM.to_owned(), denom.to_owned()],
+                    ..init_msg(distribution.addr())
+                },
+                "Proxy",
+            )
+            .unwrap_err()
+    };
+
+    let err = instantiate(&mut app, "");
+    assert_eq!(ContractError::EmptyDenom, err.downcast().unwrap());
+
+    let long = "u".repeat(129);
+    let err = instantiate(&mut app, &long);
+    assert_eq!(ContractError::DenomTooLong(long), err.downcast().unwrap());
+
+    let err = instantiate(&mut app, "u orai");
+    assert_eq!(
+        ContractError::DenomWithWhitespace("u orai".to_owned()),
+        err.downcast().unwrap()
+    );
+
+    // 128 characters is still fine
+    code_id
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                accepted_denoms: vec!["u".repeat(128)],
+                ..init_msg(distribution.addr())
+            },
+            "Proxy",
+        )
+        .unwrap();
+}
+
+#[test]
+fn invalid_distribution_address() {
+    let mut app = mock_app();
+    let code_id = CodeId::store_code(&mut app);
+
+    let err = code_id
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                distribution_contracts: vec![("".to_owned(), Decimal::one())],
+                skip_contract_check: true,
+                ..init_msg(&Addr::unchecked("distribution"))
+            },
+            "Proxy",
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::StdError(_)
+    ));
+}
+
+#[test]
+fn distribution_is_membership() {
+    let mut app = mock_app();
+    let code_id = CodeId::store_code(&mut app);
+
+    let err = code_id
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                membership_contract: "membership".to_owned(),
+                ..init_msg(&Addr::unchecked("membership"))
+            },
+            "Proxy",
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::DistributionIsMembership,
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn contract_check() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    // any contract stands in for the membership one
+    let membership = DistributionMock::instantiate(&mut app).unwrap();
+    let code_id = CodeId::store_code(&mut app);
+
+    let err = code_id
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                membership_contract: membership.addr().to_string(),
+                skip_contract_check: false,
+                ..init_msg(&Addr::unchecked("distribution"))
+            },
+            "Proxy",
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotAContract {
+            which: "distribution".to_owned(),
+            addr: "distribution".to_owned(),
+        },
+        err.downcast().unwrap()
+    );
+
+    let err = code_id
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                skip_contract_check: false,
+                ..init_msg(distribution.addr())
+            },
+            "Proxy",
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotAContract {
+            which: "membership".to_owned(),
+            addr: "membership".to_owned(),
+        },
+        err.downcast().unwrap()
+    );
+
+    let proxy = code_id
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                membership_contract: membership.addr().to_string(),
+                skip_contract_check: false,
+                ..init_msg(distribution.addr())
+            },
+            "Proxy",
+        )
+        .unwrap();
+
+    // a distribution contract set later is checked as well
+    let err = proxy
+        .set_distribution_contract(&mut app, OWNER, &Addr::unchecked("account"))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotAContract {
+            which: "distribution".to_owned(),
+            addr: "account".to_owned(),
+        },
+        err.downcast().unwrap()
+    );
+    let other = DistributionMock::instantiate(&mut app).unwrap();
+    proxy
+        .set_distribution_contract(&mut app, OWNER, other.addr())
+        .unwrap();
+}
+
+#[test]
+fn instantiate_reports_config() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let code_id = CodeId::store_code(&mut app);
+
+    let msg = WasmMsg::Instantiate {
+        admin: None,
+        code_id: code_id.0,
+        msg: to_json_binary(&init_msg(distribution.addr())).unwrap(),
+        funds: vec![],
+        label: "Proxy".to_owned(),
+    };
+    let resp = app.execute(Addr::unchecked(OWNER), msg.into()).unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "instantiate")
+            .add_attribute("owner", OWNER)
+            .add_attribute("weight", "10")
+            .add_attribute("accepted_denoms", DENOM)
+            .add_attribute("direct_part", "0.1")
+            .add_attribute(
+                "distribution_contracts",
+                format!("{}:1", distribution.addr())
+            )
+            .add_attribute("membership_contract", "membership")
+            .add_attribute("halftime", "time: 2592000")
+            .add_attribute("decay", "0.5")
+    ));
+}
+
+#[test]
+fn min_donation() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            min_donation: Uint128::new(50),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    let err = proxy
+        .donate(&mut app, DONOR, &coins(49, DENOM))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::DonationTooSmall {
+            min: Uint128::new(50),
+            got: Uint128::new(49)
+        },
+        err.downcast().unwrap()
+    );
+    // rejected donation is not taken
+    assert_eq!(balance(&app, DONOR), 1000);
+
+    // exactly the minimum is fine
+    proxy.donate(&mut app, DONOR, &coins(50, DENOM)).unwrap();
+
+    let err = proxy.set_min_donation(&mut app, DONOR, 0).unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    // zero disables the minimum
+    proxy.set_min_donation(&mut app, OWNER, 0).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(1, DENOM)).unwrap();
+
+    assert_eq!(proxy.donor(&app, DONOR).unwrap().count, 2);
+}
+
+#[test]
+fn direct_part_paid_to_owner() {
+    let mut app = app_with_donors(&[(DONOR, 100)]);
+    let (proxy, distribution) = setup(&mut app);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    assert_eq!(balance(&app, OWNER), 10);
+
+    assert_eq!(balance(&app, distribution.addr()), 90);
+
+    assert_eq!(balance(&app, proxy.addr()), 0);
+}
+
+#[test]
+fn zero_direct_part_sends_nothing_to_owner() {
+    let mut app = app_with_donors(&[(DONOR, 100)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            direct_part: Decimal::zero(),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    assert_eq!(balance(&app, OWNER), 0);
+
+    assert_eq!(balance(&app, distribution.addr()), 100);
+}
+
+#[test]
+fn direct_beneficiary() {
+    let mut app = app_with_donors(&[(DONOR, 300)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            direct_beneficiary: Some("cold wallet".to_owned()),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(balance(&app, "cold wallet"), 10);
+
+    let err = proxy
+        .set_direct_beneficiary(&mut app, DONOR, Some(DONOR))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    proxy
+        .set_direct_beneficiary(&mut app, OWNER, Some("multisig"))
+        .unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(balance(&app, "multisig"), 10);
+
+    // unset => owner again
+    proxy.set_direct_beneficiary(&mut app, OWNER, None).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(balance(&app, OWNER), 10);
+
+    // earlier payouts are untouched
+    assert_eq!(balance(&app, "cold wallet"), 10);
+}
+
+#[test]
+fn donation_history_with_memos() {
+    let mut app = app_with_donors(&[("donor1", 1000), ("donor2", 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    for amount in 1..=7 {
+        let (donor, memo) = if amount % 2 == 0 {
+            ("donor2", None)
+        } else {
+            ("donor1", Some("keep it up!"))
+        };
+        proxy
+            .donate_with_memo(&mut app, donor, &coins(amount * 10, DENOM), memo)
+            .unwrap();
+    }
+
+    let page1 = proxy.donation_history(&app, None, Some(3)).unwrap();
+    let ids: Vec<_> = page1.donations.iter().map(|d| d.id).collect();
+    assert_eq!(ids, [6, 5, 4]);
+    assert_eq!(page1.donations[0].amount.u128(), 70);
+    assert_eq!(page1.donations[0].memo.as_deref(), Some("keep it up!"));
+    assert_eq!(page1.donations[1].donor, Addr::unchecked("donor2"));
+    assert_eq!(page1.donations[1].memo, None);
+
+    let page2 = proxy.donation_history(&app, Some(4), Some(3)).unwrap();
+    let ids: Vec<_> = page2.donations.iter().map(|d| d.id).collect();
+    assert_eq!(ids, [3, 2, 1]);
+
+    let page3 = proxy.donation_history(&app, Some(1), Some(3)).unwrap();
+    let ids: Vec<_> = page3.donations.iter().map(|d| d.id).collect();
+    assert_eq!(ids, [0]);
+    assert_eq!(page3.donations[0].denom, DENOM);
+    assert_eq!(page3.donations[0].time, app.block_info().time.seconds());
+}
+
+#[test]
+fn memo_too_long() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    let memo = "a".repeat(257);
+    let err = proxy
+        .donate_with_memo(&mut app, DONOR, &coins(10, DENOM), Some(&memo))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MemoTooLong { max: 256, len: 257 },
+        err.downcast().unwrap()
+    );
+
+    let memo = "a".repeat(256);
+    proxy
+        .donate_with_memo(&mut app, DONOR, &coins(10, DENOM), Some(&memo))
+        .unwrap();
+
+    let history = proxy.donation_history(&app, None, None).unwrap();
+    assert_eq!(history.donations.len(), 1);
+}
+
+#[test]
+fn unique_donors_survive_withdraw() {
+    let mut app = app_with_donors(&[("donor1", 1000), ("donor2", 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    proxy
+        .donate(&mut app, "donor1", &coins(100, DENOM))
+        .unwrap();
+    proxy
+        .donate(&mut app, "donor2", &coins(100, DENOM))
+        .unwrap();
+    proxy
+        .donate(&mut app, "donor1", &coins(100, DENOM))
+        .unwrap();
+
+    let stats = proxy.stats(&app).unwrap();
+    assert_eq!((stats.donations, stats.unique_donors), (3, 2));
+    assert_eq!(stats.total_donated.u128(), 300);
+
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+
+    // the epoch counter is reset, the unique donors are not
+    let stats = proxy.stats(&app).unwrap();
+    assert_ne!(stats.donations, 3);
+    assert_eq!(stats.unique_donors, 2);
+
+    proxy
+        .donate(&mut app, "donor2", &coins(100, DENOM))
+        .unwrap();
+    assert_eq!(proxy.stats(&app).unwrap().unique_donors, 2);
+}
+
+#[test]
+fn top_donors() {
+    let donors: Vec<_> = (0..35).map(|idx| format!("donor{idx:02}")).collect();
+    let balances: Vec<_> = donors.iter().map(|donor| (donor.as_str(), 1000)).collect();
+    let mut app = app_with_donors(&balances);
+    let (proxy, _) = setup(&mut app);
+
+    proxy
+        .donate(&mut app, "donor01", &coins(100, DENOM))
+        .unwrap();
+    proxy
+        .donate(&mut app, "donor02", &coins(300, DENOM))
+        .unwrap();
+    proxy
+        .donate(&mut app, "donor03", &coins(200, DENOM))
+        .unwrap();
+    proxy
+        .donate(&mut app, "donor04", &coins(200, DENOM))
+        .unwrap();
+    // donor01 overtakes everyone
+    proxy
+        .donate(&mut app, "donor01", &coins(250, DENOM))
+        .unwrap();
+
+    let top = proxy.top_donors(&app, Some(4)).unwrap();
+    let top: Vec<_> = top
+        .donors
+        .iter()
+        .map(|donor| (donor.addr.as_str(), donor.total.u128()))
+        .collect();
+    assert_eq!(
+        top,
+        [
+            ("donor01", 350),
+            ("donor02", 300),
+            ("donor04", 200),
+            ("donor03", 200)
+        ]
+    );
+
+    // the limit is capped
+    for donor in &donors {
+        proxy.donate(&mut app, donor, &coins(1, DENOM)).unwrap();
+    }
+    assert_eq!(proxy.top_donors(&app, Some(100)).unwrap().donors.len(), 30);
+}
+
+fn top(resp: TopDonorsResponse) -> Vec<(String, u128)> {
+    resp.donors
+        .into_iter()
+        .map(|donor| (donor.addr.into_string(), donor.total.u128()))
+        .collect()
+}
+
+#[test]
+fn top_donors_pages_after_overtaking() {
+    let donors = ["donor1", "donor2", "donor3", "donor4", "donor5"];
+    let balances: Vec<_> = donors.iter().map(|donor| (*donor, 1000)).collect();
+    let mut app = app_with_donors(&balances);
+    let (proxy, _) = setup(&mut app);
+
+    for (donor, amount) in donors.iter().zip([50, 400, 300, 200, 100]) {
+        proxy
+            .donate(&mut app, donor, &coins(amount, DENOM))
+            .unwrap();
+    }
+    assert_eq!(
+        top(proxy.top_donors(&app, Some(2)).unwrap()),
+        [("donor2".to_owned(), 400), ("donor3".to_owned(), 300)]
+    );
+    assert_eq!(
+        top(proxy
+            .top_donors_after(&app, Some("donor3"), Some(2))
+            .unwrap()),
+        [("donor4".to_owned(), 200), ("donor5".to_owned(), 100)]
+    );
+
+    // the last one climbs to the top, leaving no trace of its old rank
+    proxy
+        .donate(&mut app, "donor1", &coins(400, DENOM))
+        .unwrap();
+    assert_eq!(
+        top(proxy.top_donors(&app, Some(2)).unwrap()),
+        [("donor1".to_owned(), 450), ("donor2".to_owned(), 400)]
+    );
+    assert_eq!(
+        top(proxy.top_donors_after(&app, Some("donor2"), None).unwrap()),
+        [
+            ("donor3".to_owned(), 300),
+            ("donor4".to_owned(), 200),
+            ("donor5".to_owned(), 100)
+        ]
+    );
+
+    // overtaking within the page boundary
+    proxy
+        .donate(&mut app, "donor5", &coins(150, DENOM))
+        .unwrap();
+    assert_eq!(
+        top(proxy.top_donors_after(&app, Some("donor3"), None).unwrap()),
+        [("donor5".to_owned(), 250), ("donor4".to_owned(), 200)]
+    );
+
+    let err = proxy
+        .top_donors_after(&app, Some("stranger"), None)
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid start_after donor"));
+}
+
+#[test]
+fn goal_closes_the_proxy() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            goal: Some(Uint128::new(250)),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    proxy.donate(&mut app, DONOR, &coins(200, DENOM)).unwrap();
+
+    let progress = proxy.goal_progress(&app).unwrap();
+    assert_eq!(progress.raised.u128(), 200);
+    assert_eq!(progress.goal, Some(Uint128::new(250)));
+
+    // crossing donation is taken in full
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &coins(100, DENOM),
+        )
+        .unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attribute("goal_reached", "yes")));
+    assert_eq!(balance(&app, DONOR), 700);
+    assert_eq!(proxy.goal_progress(&app).unwrap().raised.u128(), 300);
+
+    let err = proxy.donate(&mut app, DONOR, &coins(1, DENOM)).unwrap_err();
+    assert_eq!(ContractError::ContractClosed, err.downcast().unwrap());
+}
+
+#[test]
+fn protocol_fee_three_way_split() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            direct_part: Decimal::percent(33),
+            protocol_fee: Decimal::percent(3),
+            treasury: Some("treasury".to_owned()),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    // fee = 3, remainder 98 => direct = 32, distribution = 65, 1 left as dust
+    proxy.donate(&mut app, DONOR, &coins(101, DENOM)).unwrap();
+
+    assert_eq!(balance(&app, "treasury"), 3);
+    assert_eq!(balance(&app, OWNER), 32);
+    assert_eq!(balance(&app, distribution.addr()), 65);
+    assert_eq!(
+        balance(&app, "treasury")
+            + balance(&app, OWNER)
+            + balance(&app, distribution.addr())
+            + balance(&app, proxy.addr()),
+        101
+    );
+}
+
+#[test]
+fn simulated_donation_matches_execution() {
+    let mut app = app_with_donors(&[(DONOR, 1000), (OWNER, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            direct_part: Decimal::percent(33),
+            protocol_fee: Decimal::percent(3),
+            treasury: Some("treasury".to_owned()),
+            burn_part: Decimal::percent(5),
+            ..init_msg(distribution.addr())
+        },
+    );
+    proxy
+        .fund_matching(&mut app, OWNER, &coins(5, DENOM))
+        .unwrap();
+
+    // awkward amounts leave dust behind and drain the matching pool mid way
+    for amount in [1, 3, 3, 101] {
+        let simulated = proxy.simulate_donation(&app, amount).unwrap();
+        assert_eq!(simulated.rejected, None);
+
+        let resp = app
+            .execute_contract(
+                Addr::unchecked(DONOR),
+                proxy.addr().clone(),
+                &ExecMsg::Donate {
+                    memo: None,
+                    referrer: None,
+                },
+                &coins(amount, DENOM),
+            )
+            .unwrap();
+        let executed = Event::new("wasm")
+            .add_attribute("protocol_fee", simulated.protocol_fee.to_string())
+            .add_attribute("burned", simulated.burned.to_string())
+            .add_attribute("direct_amount", simulated.direct.to_string())
+            .add_attribute("to_distribute", simulated.to_distribute.to_string())
+            .add_attribute("dust_carried", simulated.dust_carried.to_string())
+            .add_attribute("matched", simulated.matched.to_string());
+        assert!(resp.has_event(&executed), "amount {}", amount);
+        assert_eq!(
+            simulated.protocol_fee
+                + simulated.burned
+                + simulated.direct
+                + simulated.dust
+                + simulated.to_distribute,
+            Uint128::new(amount) + simulated.dust_carried + simulated.matched
+        );
+    }
+}
+
+#[test]
+fn simulated_donation_rejected() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            min_donation: Uint128::new(50),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    let simulated = proxy.simulate_donation(&app, 49).unwrap();
+    assert_eq!(
+        simulated.rejected,
+        Some(
+            ContractError::DonationTooSmall {
+                min: Uint128::new(50),
+                got: Uint128::new(49),
+            }
+            .to_string()
+        )
+    );
+    // the split is still reported
+    assert_eq!(simulated.direct.u128(), 4);
+    assert_eq!(proxy.simulate_donation(&app, 50).unwrap().rejected, None);
+
+    proxy.close(&mut app, OWNER).unwrap();
+    assert_eq!(
+        proxy.simulate_donation(&app, 50).unwrap().rejected,
+        Some(ContractError::ContractClosed.to_string())
+    );
+}
+
+#[test]
+fn invalid_protocol_fee() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let code_id = CodeId::store_code(&mut app);
+
+    let err = code_id
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                direct_part: Decimal::percent(90),
+                protocol_fee: Decimal::percent(11),
+                treasury: Some("treasury".to_owned()),
+                ..init_msg(distribution.addr())
+            },
+            "Proxy",
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidProtocolFee, err.downcast().unwrap());
+
+    let err = code_id
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                protocol_fee: Decimal::percent(1),
+                ..init_msg(distribution.addr())
+            },
+            "Proxy",
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::MissingTreasury, err.downcast().unwrap());
+}
+
+#[test]
+fn blocked_donors() {
+    let mut app = app_with_donors(&[("donor1", 1000), ("donor2", 1000), (OWNER, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    proxy
+        .donate(&mut app, "donor1", &coins(100, DENOM))
+        .unwrap();
+
+    let err = proxy.block(&mut app, "donor2", "donor1").unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    proxy.block(&mut app, OWNER, "donor1").unwrap();
+    proxy.block(&mut app, OWNER, "donor2").unwrap();
+    // blocking yourself is allowed and harmless
+    proxy.block(&mut app, OWNER, OWNER).unwrap();
+
+    let err = proxy
+        .donate(&mut app, "donor1", &coins(100, DENOM))
+        .unwrap_err();
+    assert_eq!(ContractError::DonorBlocked, err.downcast().unwrap());
+    assert!(proxy.is_blocked(&app, "donor1").unwrap().is_blocked);
+
+    // earlier donations are kept
+    assert_eq!(proxy.donor(&app, "donor1").unwrap().total.u128(), 100);
+
+    let page = proxy.blocklist(&app, None, Some(2)).unwrap();
+    assert_eq!(
+        page.blocked,
+        [Addr::unchecked("donor1"), Addr::unchecked("donor2")]
+    );
+    let page = proxy.blocklist(&app, Some("donor2"), Some(2)).unwrap();
+    assert_eq!(page.blocked, [Addr::unchecked(OWNER)]);
+
+    proxy.unblock(&mut app, OWNER, "donor1").unwrap();
+    assert!(!proxy.is_blocked(&app, "donor1").unwrap().is_blocked);
+    proxy
+        .donate(&mut app, "donor1", &coins(100, DENOM))
+        .unwrap();
+}
+
+#[test]
+fn split_between_distribution_contracts() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let first = DistributionMock::instantiate(&mut app).unwrap();
+    let second = DistributionMock::instantiate(&mut app).unwrap();
+
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            distribution_contracts: vec![
+                (first.addr().to_string(), Decimal::percent(70)),
+                (second.addr().to_string(), Decimal::percent(30)),
+            ],
+            ..init_msg(first.addr())
+        },
+    );
+
+    // 10 direct, 91 distributed: 27.3 floored for the second, the remainder for the first
+    proxy.donate(&mut app, DONOR, &coins(102, DENOM)).unwrap();
+    assert_eq!(balance(&app, first.addr()), 64);
+    assert_eq!(balance(&app, second.addr()), 27);
+    assert_eq!(balance(&app, OWNER), 10);
+
+    // both targets pay their share back before the owner gets paid out, dust stays
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(balance(&app, first.addr()), 0);
+    assert_eq!(balance(&app, second.addr()), 0);
+    assert_eq!(balance(&app, OWNER), 101);
+    assert_eq!(balance(&app, proxy.addr()), 1);
+}
+
+#[test]
+fn invalid_distribution_weights() {
+    let mut app = mock_app();
+    let first = DistributionMock::instantiate(&mut app).unwrap();
+    let code_id = CodeId::store_code(&mut app);
+
+    let mut msg = init_msg(first.addr());
+    msg.distribution_contracts = vec![
+        (first.addr().to_string(), Decimal::percent(70)),
+        ("second".to_owned(), Decimal::percent(20)),
+    ];
+    let err = code_id
+        .instantiate(&mut app, OWNER, &msg, "Proxy")
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidDistributionWeights,
+        err.downcast().unwrap()
+    );
+
+    msg.distribution_contracts = vec![];
+    let err = code_id
+        .instantiate(&mut app, OWNER, &msg, "Proxy")
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidDistributionWeights,
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn donor_tiers() {
+    let mut app = app_with_donors(&[(DONOR, 2000)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            tiers: vec![
+                (Uint128::new(100), "bronze".to_owned()),
+                (Uint128::new(500), "silver".to_owned()),
+                (Uint128::new(1000), "gold".to_owned()),
+            ],
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    proxy.donate(&mut app, DONOR, &coins(50, DENOM)).unwrap();
+    assert_eq!(proxy.donor_tier(&app, DONOR).unwrap().tier, None);
+
+    // 50 + 600 skips bronze straight to silver
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &coins(600, DENOM),
+        )
+        .unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attribute("tier", "silver")));
+    assert_eq!(
+        proxy.donor_tier(&app, DONOR).unwrap().tier.as_deref(),
+        Some("silver")
+    );
+    assert_eq!(proxy.donor_tier(&app, "stranger").unwrap().tier, None);
+}
+
+#[test]
+fn no_tiers_no_attribute() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &coins(600, DENOM),
+        )
+        .unwrap();
+    let wasm = resp.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+    assert!(wasm.attributes.iter().all(|attr| attr.key != "tier"));
+}
+
+#[test]
+fn unsorted_tiers() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+
+    let err = CodeId::store_code(&mut app)
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                tiers: vec![
+                    (Uint128::new(500), "silver".to_owned()),
+                    (Uint128::new(100), "bronze".to_owned()),
+                ],
+                ..init_msg(distribution.addr())
+            },
+            "Proxy",
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::UnsortedTiers, err.downcast().unwrap());
+}
+
+#[test]
+fn donate_for_third_party() {
+    let mut app = app_with_donors(&[("processor", 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+
+    proxy
+        .donate_for(&mut app, "processor", "user", &coins(100, DENOM))
+        .unwrap();
+
+    // paid by the processor, credited to the user
+    assert_eq!(balance(&app, "processor"), 900);
+    assert_eq!(balance(&app, OWNER), 10);
+    assert_eq!(balance(&app, distribution.addr()), 90);
+    assert_eq!(proxy.donor(&app, "user").unwrap().total.u128(), 100);
+    assert_eq!(proxy.donor(&app, "processor").unwrap().count, 0);
+
+    let err = proxy
+        .donate_for(&mut app, "processor", "user", &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::PaymentError(PaymentError::NoFunds {}),
+        err.downcast().unwrap()
+    );
+
+    let err = proxy
+        .donate_for(
+            &mut app,
+            "processor",
+            proxy.addr().as_str(),
+            &coins(100, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::DonateForProxy, err.downcast().unwrap());
+}
+
+#[test]
+fn donate_remote_attribution() {
+    let hook = "ibchook";
+    let mut app = app_with_donors(&[(hook, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+    let now = app.block_info().time.seconds();
+
+    let resp = proxy
+        .donate_remote(
+            &mut app,
+            hook,
+            "osmosis-1",
+            "osmo1alice",
+            &coins(100, DENOM),
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "donate")
+            .add_attribute("donor", hook)
+            .add_attribute("hook_sender", hook)
+            .add_attribute("remote_chain", "osmosis-1")
+            .add_attribute("remote_donor", "osmo1alice")
+    ));
+    // split like any donation, the hook sender is the local donor
+    assert_eq!(balance(&app, distribution.addr()), 90);
+    assert_eq!(proxy.donor(&app, hook).unwrap().total.u128(), 100);
+
+    proxy
+        .donate_remote(&mut app, hook, "osmosis-1", "osmo1alice", &coins(50, DENOM))
+        .unwrap();
+    proxy
+        .donate_remote(&mut app, hook, "osmosis-1", "osmo1bob", &coins(30, DENOM))
+        .unwrap();
+    proxy
+        .donate_remote(
+            &mut app,
+            hook,
+            "cosmoshub-4",
+            "cosmos1carol",
+            &coins(20, DENOM),
+        )
+        .unwrap();
+    assert_eq!(proxy.donor(&app, hook).unwrap().count, 4);
+
+    let remote_donor = |chain: &str, donor: &str, total: u128, count: u64| RemoteDonor {
+        chain: chain.to_owned(),
+        donor: donor.to_owned(),
+        total: Uint128::new(total),
+        count,
+        last_donated: now,
+    };
+    assert_eq!(
+        proxy.remote_donors(&app, None, None).unwrap().donors,
+        vec![
+            remote_donor("osmosis-1", "osmo1alice", 150, 2),
+            remote_donor("osmosis-1", "osmo1bob", 30, 1),
+            remote_donor("cosmoshub-4", "cosmos1carol", 20, 1),
+        ]
+    );
+    assert_eq!(
+        proxy
+            .remote_donors(&app, Some(("osmosis-1", "osmo1alice")), Some(1))
+            .unwrap()
+            .donors,
+        vec![remote_donor("osmosis-1", "osmo1bob", 30, 1)]
+    );
+
+    let err = proxy
+        .donate_remote(&mut app, hook, "", "osmo1alice", &coins(10, DENOM))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidRemoteField {
+            field: "chain".to_owned()
+        },
+        err.downcast().unwrap()
+    );
+    let err = proxy
+        .donate_remote(
+            &mut app,
+            hook,
+            "osmosis-1",
+            "osmo1 alice\n",
+            &coins(10, DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidRemoteField {
+            field: "donor".to_owned()
+        },
+        err.downcast().unwrap()
+    );
+    let long = "a".repeat(129);
+    let err = proxy
+        .donate_remote(&mut app, hook, "osmosis-1", &long, &coins(10, DENOM))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RemoteFieldTooLong {
+            field: "donor".to_owned(),
+            max: 128,
+            len: 129,
+        },
+        err.downcast().unwrap()
+    );
+
+    // nothing recorded without a donation
+    let err = proxy
+        .donate_remote(&mut app, hook, "osmosis-1", "osmo1dave", &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::PaymentError(PaymentError::NoFunds {}),
+        err.downcast().unwrap()
+    );
+    assert_eq!(
+        proxy.remote_donors(&app, None, None).unwrap().donors.len(),
+        3
+    );
+}
+
+#[test]
+fn daily_stats() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    let today = app.block_info().time.seconds() / 86400;
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(50, DENOM)).unwrap();
+
+    // skip tomorrow entirely
+    app.update_block(|block| block.time = block.time.plus_seconds(2 * 86400));
+    proxy.donate(&mut app, DONOR, &coins(30, DENOM)).unwrap();
+
+    let stats = proxy.daily_stats(&app, today, today + 2).unwrap();
+    assert_eq!(
+        stats.buckets,
+        [
+            DailyBucket {
+                day: today,
+                count: 2,
+                amount: Uint128::new(150),
+            },
+            DailyBucket {
+                day: today + 2,
+                count: 1,
+                amount: Uint128::new(30),
+            },
+        ]
+    );
+
+    let stats = proxy.daily_stats(&app, today + 1, today + 1).unwrap();
+    assert!(stats.buckets.is_empty());
+}
+
+#[test]
+fn custom_bucket_width() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            bucket_width: Some(3600),
+            ..init_msg(distribution.addr())
+        },
+    );
+    let hour = app.block_info().time.seconds() / 3600;
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(3600));
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let stats = proxy.daily_stats(&app, hour, hour + 1).unwrap();
+    let counts: Vec<_> = stats.buckets.iter().map(|b| (b.day, b.count)).collect();
+    assert_eq!(counts, [(hour, 1), (hour + 1, 1)]);
+}
+
+#[test]
+fn rounding_dust_is_accounted_for() {
+    let mut app = app_with_donors(&[(DONOR, 10_000)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            direct_part: Decimal::from_ratio(1u128, 3u128),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    let mut donated = 0;
+    for amount in [1, 2, 5, 7, 10, 11, 13, 100, 101, 334] {
+        proxy
+            .donate(&mut app, DONOR, &coins(amount, DENOM))
+            .unwrap();
+        donated += amount;
+
+        let dust = proxy.stats(&app).unwrap().dust;
+        let dust = dust.first().map(|coin| coin.amount.u128()).unwrap_or(0);
+        assert_eq!(
+            balance(&app, distribution.addr()) + balance(&app, OWNER) + dust,
+            donated
+        );
+        assert_eq!(balance(&app, proxy.addr()), dust);
+    }
+}
+
+#[test]
+fn matching_pool_runs_out_mid_donation() {
+    let mut app = app_with_donors(&[(DONOR, 1000), (OWNER, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+
+    let err = proxy
+        .fund_matching(&mut app, DONOR, &coins(150, DENOM))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    proxy
+        .fund_matching(&mut app, OWNER, &coins(150, DENOM))
+        .unwrap();
+
+    // fully matched: 90 + 100 distributed
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(balance(&app, distribution.addr()), 190);
+
+    // only 50 left in the pool
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &coins(100, DENOM),
+        )
+        .unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attribute("matched", "50")));
+    assert_eq!(balance(&app, distribution.addr()), 330);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(balance(&app, distribution.addr()), 420);
+    assert_eq!(balance(&app, proxy.addr()), 0);
+
+    // matched funds don't count as donations
+    assert_eq!(proxy.total_donated(&app).unwrap().total.u128(), 300);
+}
+
+#[test]
+fn withdraw_matching_pool() {
+    let mut app = app_with_donors(&[(DONOR, 1000), (OWNER, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    proxy
+        .fund_matching(&mut app, OWNER, &coins(500, DENOM))
+        .unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(balance(&app, OWNER), 510);
+
+    // the regular withdraw leaves the pool alone
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(balance(&app, OWNER), 700);
+    assert_eq!(balance(&app, proxy.addr()), 400);
+
+    proxy.withdraw_matching(&mut app, OWNER).unwrap();
+    assert_eq!(balance(&app, OWNER), 1100);
+    assert_eq!(balance(&app, proxy.addr()), 0);
+
+    // nothing left to recover
+    proxy.withdraw_matching(&mut app, OWNER).unwrap();
+}
+
+#[test]
+fn referrals() {
+    let mut app = app_with_donors(&[("donor1", 1000), ("donor2", 1000)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            referral_bps: 5000,
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    // half of the 10 direct goes to the promoter, distribution is untouched
+    proxy
+        .donate_with_referrer(&mut app, "donor1", &coins(100, DENOM), "promoter")
+        .unwrap();
+    assert_eq!(balance(&app, "promoter"), 5);
+    assert_eq!(balance(&app, OWNER), 5);
+    assert_eq!(balance(&app, distribution.addr()), 90);
+
+    proxy
+        .donate_with_referrer(&mut app, "donor2", &coins(200, DENOM), "promoter")
+        .unwrap();
+    proxy
+        .donate_with_referrer(&mut app, "donor2", &coins(100, DENOM), "another")
+        .unwrap();
+
+    let stats = proxy.referrer_stats(&app, "promoter").unwrap();
+    assert_eq!(stats.count, 2);
+    assert_eq!(stats.total.u128(), 300);
+    assert_eq!(stats.earned.u128(), 15);
+    assert_eq!(proxy.referrer_stats(&app, "nobody").unwrap().count, 0);
+
+    let page = proxy.referrers(&app, None, Some(1)).unwrap();
+    assert_eq!(page.referrers.len(), 1);
+    assert_eq!(page.referrers[0].addr.as_str(), "another");
+    let page = proxy.referrers(&app, Some("another"), None).unwrap();
+    assert_eq!(page.referrers.len(), 1);
+    assert_eq!(page.referrers[0].addr.as_str(), "promoter");
+
+    let err = proxy
+        .donate_with_referrer(&mut app, "donor1", &coins(100, DENOM), "donor1")
+        .unwrap_err();
+    assert_eq!(ContractError::SelfReferral, err.downcast().unwrap());
+}
+
+#[test]
+fn referral_without_cut() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    proxy
+        .donate_with_referrer(&mut app, DONOR, &coins(100, DENOM), "promoter")
+        .unwrap();
+    assert_eq!(balance(&app, "promoter"), 0);
+    assert_eq!(balance(&app, OWNER), 10);
+    assert_eq!(proxy.referrer_stats(&app, "promoter").unwrap().count, 1);
+}
+
+#[test]
+fn burn_part_shrinks_supply() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            burn_part: Decimal::percent(20),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &coins(100, DENOM),
+        )
+        .unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attribute("burned", "20")));
+
+    assert_eq!(balance(&app, OWNER), 10);
+    assert_eq!(balance(&app, distribution.addr()), 70);
+
+    // multi-test 0.16 has no supply query, so sum up every holder instead
+    let supply = balance(&app, DONOR)
+        + balance(&app, OWNER)
+        + balance(&app, distribution.addr())
+        + balance(&app, proxy.addr());
+    assert_eq!(supply, 980);
+}
+
+#[test]
+fn zero_burn_part_burns_nothing() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &coins(100, DENOM),
+        )
+        .unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attribute("burned", "0")));
+
+    let supply = balance(&app, DONOR)
+        + balance(&app, OWNER)
+        + balance(&app, distribution.addr())
+        + balance(&app, proxy.addr());
+    assert_eq!(supply, 1000);
+}
+
+#[test]
+fn invalid_burn_part() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+
+    let err = CodeId::store_code(&mut app)
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                direct_part: Decimal::percent(90),
+                burn_part: Decimal::percent(20),
+                ..init_msg(distribution.addr())
+            },
+            "Proxy",
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidBurnPart, err.downcast().unwrap());
+}
+
+#[test]
+fn structured_events() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &coins(200, DENOM),
+        )
+        .unwrap();
+    // multi-test prefixes custom events with "wasm-" the same way wasmd does
+    assert!(resp.has_event(
+        &Event::new("wasm-proxy-donation")
+            .add_attribute("donor", DONOR)
+            .add_attribute("amount", "200")
+            .add_attribute("denom", DENOM)
+            .add_attribute("direct_amount", "20")
+            .add_attribute("distributed_amount", "180")
+            .add_attribute("donation_id", "1")
+    ));
+    // legacy attributes are still there
+    assert!(resp.has_event(&Event::new("wasm").add_attribute("direct_amount", "20")));
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Withdraw {
+                receiver: Some("receiver".to_owned()),
+                amount: Some(Uint128::new(50)),
+                denom: None,
+                receiver_msg: None,
+                ibc: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm-proxy-withdraw")
+            .add_attribute("receiver", "receiver")
+            .add_attribute("denom", DENOM)
+            .add_attribute("weight", "10")
+            .add_attribute("diff", "-8")
+            .add_attribute("amount", "50")
+    ));
+
+    app.update_block(|block| block.time = block.time.plus_seconds(3600 * 24 * 30));
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::UpdateWeight {},
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm-proxy-weight-update")
+            .add_attribute("old_weight", "2")
+            .add_attribute("new_weight", "1")
+            .add_attribute("diff", "-1")
+    ));
+}
+
+#[test]
+fn full_direct_part_sends_nothing_to_distribution() {
+    let mut app = app_with_donors(&[(DONOR, 100)]);
+
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            direct_part: Decimal::percent(100),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &coins(100, DENOM),
+        )
+        .unwrap();
+
+    // no Distribute call at all
+    assert!(!resp.has_event(
+        &Event::new("execute").add_attribute("_contract_address", distribution.addr().as_str())
+    ));
+    assert_eq!(balance(&app, OWNER), 100);
+    assert_eq!(balance(&app, distribution.addr()), 0);
+    assert_eq!(proxy.donor(&app, DONOR).unwrap().total.u128(), 100);
+}
+
+#[test]
+fn single_unit_donation_floors_to_zero() {
+    let mut app = app_with_donors(&[(DONOR, 100)]);
+    let (proxy, distribution) = setup(&mut app);
+
+    // 10% of 1 floors to 0 direct, 90% of 1 floors to 0 distributed, the unit is kept as dust
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &coins(1, DENOM),
+        )
+        .unwrap();
+
+    assert!(!resp.has_event(
+        &Event::new("execute").add_attribute("_contract_address", distribution.addr().as_str())
+    ));
+    assert!(!resp.has_event(&Event::new("transfer").add_attribute("recipient", OWNER)));
+    assert_eq!(balance(&app, proxy.addr()), 1);
+    assert_eq!(proxy.donor(&app, DONOR).unwrap().count, 1);
+}
+
+#[test]
+fn donation_at_halftime_decays_weight() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    let donate_msg = ExecMsg::Donate {
+        memo: None,
+        referrer: None,
+    };
+
+    // one second short
+    app.update_block(|block| block.time = block.time.plus_seconds(3600 * 24 * 30 - 1));
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &donate_msg,
+            &coins(100, DENOM),
+        )
+        .unwrap();
+    assert!(!resp.has_event(&Event::new("wasm").add_attribute("auto_weight_update", "yes")));
+
+    // exactly at the boundary
+    app.update_block(|block| block.time = block.time.plus_seconds(1));
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &donate_msg,
+            &coins(100, DENOM),
+        )
+        .unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attribute("auto_weight_update", "yes")));
+    assert!(resp.has_event(
+        &Event::new("wasm-proxy-weight-update")
+            .add_attribute("old_weight", "10")
+            .add_attribute("new_weight", "5")
+    ));
+
+    // the clock restarted, so the next donation doesn't decay again
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &donate_msg,
+            &coins(100, DENOM),
+        )
+        .unwrap();
+    assert!(!resp.has_event(&Event::new("wasm").add_attribute("auto_weight_update", "yes")));
+}
+
+#[test]
+fn donation_long_after_halftime_decays_once() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    let donate_msg = ExecMsg::Donate {
+        memo: None,
+        referrer: None,
+    };
+
+    app.update_block(|block| block.time = block.time.plus_seconds(5 * 3600 * 24 * 30));
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &donate_msg,
+            &coins(100, DENOM),
+        )
+        .unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attribute("auto_weight_update", "yes")));
+    let decays: Vec<_> = resp
+        .events
+        .iter()
+        .filter(|ev| ev.ty == "wasm-proxy-weight-update")
+        .collect();
+    assert_eq!(decays.len(), 1);
+
+    // the donation itself still went through
+    assert_eq!(proxy.donor(&app, DONOR).unwrap().total.u128(), 100);
+    assert_eq!(balance(&app, OWNER), 10);
+
+    // withdraw runs the same check
+    app.update_block(|block| block.time = block.time.plus_seconds(3600 * 24 * 30));
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Withdraw {
+                receiver: None,
+                amount: None,
+                denom: None,
+                receiver_msg: None,
+                ibc: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attribute("auto_weight_update", "yes")));
+}
+
+#[test]
+fn proxy_metadata() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let profile = ProxyMetadata {
+        name: "Alice".to_owned(),
+        description: Some("Writes about CosmWasm".to_owned()),
+        url: Some("https://alice.example".to_owned()),
+    };
+
+    let err = CodeId::store_code(&mut app)
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                metadata: Some(ProxyMetadata {
+                    name: "x".repeat(65),
+                    ..profile.clone()
+                }),
+                ..init_msg(distribution.addr())
+            },
+            "Proxy",
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MetadataTooLong {
+            field: "name".to_owned(),
+            max: 64,
+            len: 65,
+        },
+        err.downcast().unwrap()
+    );
+
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            metadata: Some(profile.clone()),
+            ..init_msg(distribution.addr())
+        },
+    );
+    assert_eq!(proxy.metadata(&app).unwrap(), Some(profile.clone()));
+
+    let err = proxy.set_metadata(&mut app, DONOR, None).unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    let err = proxy
+        .set_metadata(
+            &mut app,
+            OWNER,
+            Some(ProxyMetadata {
+                description: Some("x".repeat(513)),
+                ..profile.clone()
+            }),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MetadataTooLong {
+            field: "description".to_owned(),
+            max: 512,
+            len: 513,
+        },
+        err.downcast().unwrap()
+    );
+
+    let err = proxy
+        .set_metadata(
+            &mut app,
+            OWNER,
+            Some(ProxyMetadata {
+                url: Some("https://alice.example\n".to_owned()),
+                ..profile.clone()
+            }),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MetadataControlChars {
+            field: "url".to_owned()
+        },
+        err.downcast().unwrap()
+    );
+    assert_eq!(proxy.metadata(&app).unwrap(), Some(profile));
+
+    let renamed = ProxyMetadata {
+        name: "Alice B.".to_owned(),
+        description: None,
+        url: Some("x".repeat(256)),
+    };
+    proxy
+        .set_metadata(&mut app, OWNER, Some(renamed.clone()))
+        .unwrap();
+    assert_eq!(proxy.metadata(&app).unwrap(), Some(renamed));
+
+    proxy.set_metadata(&mut app, OWNER, None).unwrap();
+    assert_eq!(proxy.metadata(&app).unwrap(), None);
+}
+
+#[test]
+fn hook_is_notified() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    let hook = HookMock::instantiate(&mut app, false).unwrap();
+
+    let err = proxy
+        .set_hook(&mut app, DONOR, Some(hook.addr().as_str()))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    proxy
+        .set_hook(&mut app, OWNER, Some(hook.addr().as_str()))
+        .unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    assert_eq!(
+        hook.notified(&app).unwrap(),
+        [ProxyHookMsg::DonationReceived {
+            donor: DONOR.to_owned(),
+            amount: Uint128::new(100),
+            denom: DENOM.to_owned(),
+        }]
+    );
+
+    // unset => no more notifications
+    proxy.set_hook(&mut app, OWNER, None).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(hook.notified(&app).unwrap().len(), 1);
+}
+
+#[test]
+fn broken_hook_does_not_block_donations() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+    let hook = HookMock::instantiate(&mut app, true).unwrap();
+
+    proxy
+        .set_hook(&mut app, OWNER, Some(hook.addr().as_str()))
+        .unwrap();
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &coins(100, DENOM),
+        )
+        .unwrap();
+
+    let hook_failed = resp
+        .events
+        .iter()
+        .flat_map(|ev| &ev.attributes)
+        .any(|attr| attr.key == "hook_failed");
+    assert!(hook_failed);
+    assert_eq!(balance(&app, distribution.addr()), 90);
+    assert_eq!(proxy.donor(&app, DONOR).unwrap().total.u128(), 100);
+}
+
+#[test]
+fn largest_donation() {
+    let mut app = app_with_donors(&[("donor1", 1000), ("donor2", 1000)]);
+    let (proxy, _) = setup(&mut app);
+    assert_eq!(proxy.largest_donation(&app).unwrap(), None);
+
+    let start = app.block_info().time.seconds();
+    proxy
+        .donate(&mut app, "donor1", &coins(100, DENOM))
+        .unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(10));
+    // a tie keeps the earlier one
+    proxy
+        .donate(&mut app, "donor2", &coins(100, DENOM))
+        .unwrap();
+    proxy.donate(&mut app, "donor2", &coins(50, DENOM)).unwrap();
+
+    let largest = proxy.largest_donation(&app).unwrap().unwrap();
+    assert_eq!(largest.donor.as_str(), "donor1");
+    assert_eq!(largest.amount.u128(), 100);
+    assert_eq!(largest.time, start);
+
+    proxy
+        .donate(&mut app, "donor2", &coins(101, DENOM))
+        .unwrap();
+    let largest = proxy.largest_donation(&app).unwrap().unwrap();
+    assert_eq!(largest.donor.as_str(), "donor2");
+    assert_eq!(largest.amount.u128(), 101);
+    assert_eq!(largest.time, start + 10);
+}
+
+#[test]
+fn spend_limit_per_window() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+
+    let err = proxy
+        .set_spend_limit(&mut app, DONOR, Some((60, 100)))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    let err = proxy
+        .set_spend_limit(&mut app, OWNER, Some((60, 0)))
+        .unwrap_err();
+    assert_eq!(ContractError::ZeroSpendWindow, err.downcast().unwrap());
+    proxy
+        .set_spend_limit(&mut app, OWNER, Some((60, 100)))
+        .unwrap();
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    // 90 pulled from distribution, 30 paid, 60 retained on the proxy
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), Some(30), None)
+        .unwrap();
+    assert_eq!(balance(&app, "receiver"), 30);
+    assert_eq!(balance(&app, proxy.addr()), 60);
+
+    // only 30 of the retained 60 fit in the window, the rest stays on the proxy
+    app.update_block(|block| block.time = block.time.plus_seconds(99));
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+        .unwrap();
+    assert_eq!(balance(&app, "receiver"), 60);
+    assert_eq!(balance(&app, proxy.addr()), 30);
+    assert_eq!(balance(&app, distribution.addr()), 0);
+    assert_eq!(proxy.stats(&app).unwrap().retained, coins(30, DENOM));
+
+    // a full window later the counter starts over
+    app.update_block(|block| block.time = block.time.plus_seconds(1));
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+        .unwrap();
+    assert_eq!(balance(&app, "receiver"), 90);
+    assert_eq!(balance(&app, proxy.addr()), 0);
+    assert!(proxy.stats(&app).unwrap().retained.is_empty());
+
+    // unset => no limit
+    proxy.set_spend_limit(&mut app, OWNER, None).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+        .unwrap();
+    assert_eq!(balance(&app, "receiver"), 180);
+}
+
+#[test]
+fn spend_limit_on_direct_payouts() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    proxy
+        .set_spend_limit(&mut app, OWNER, Some((60, 100)))
+        .unwrap();
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), Some(30), None)
+        .unwrap();
+    assert_eq!(balance(&app, proxy.addr()), 60);
+
+    // the retained funds are paid directly only as far as the window allows
+    proxy
+        .withdraw_direct(&mut app, OWNER, Some("receiver"))
+        .unwrap();
+    assert_eq!(balance(&app, "receiver"), 60);
+    assert_eq!(balance(&app, proxy.addr()), 30);
+    assert_eq!(proxy.stats(&app).unwrap().retained, coins(30, DENOM));
+
+    let err = proxy
+        .withdraw_all(&mut app, OWNER, Some("receiver"))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::SpendLimitExceeded {
+            remaining: Uint128::zero()
+        },
+        err.downcast().unwrap()
+    );
+    let err = proxy
+        .withdraw_direct(&mut app, OWNER, Some("receiver"))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::SpendLimitExceeded {
+            remaining: Uint128::zero()
+        },
+        err.downcast().unwrap()
+    );
+    assert_eq!(balance(&app, proxy.addr()), 30);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+    proxy
+        .withdraw_all(&mut app, OWNER, Some("receiver"))
+        .unwrap();
+    assert_eq!(balance(&app, "receiver"), 90);
+    assert_eq!(balance(&app, proxy.addr()), 0);
+    assert!(proxy.stats(&app).unwrap().retained.is_empty());
+}
+
+#[test]
+fn spend_limit_per_denom() {
+    let mut app = app_with_balances(&[(DONOR, vec![coin(100, DENOM), coin(200, "USDC")])]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            accepted_denoms: vec![DENOM.to_owned(), "USDC".to_owned()],
+            ..init_msg(distribution.addr())
+        },
+    );
+    proxy
+        .set_spend_limit(&mut app, OWNER, Some((50, 100)))
+        .unwrap();
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(200, "USDC")).unwrap();
+
+    // every denom has a window of its own
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    proxy
+        .withdraw(&mut app, OWNER, None, None, Some("USDC"))
+        .unwrap();
+    assert_eq!(
+        all_balances(&app, OWNER),
+        vec![coin(60, DENOM), coin(70, "USDC")]
+    );
+    assert_eq!(
+        all_balances(&app, proxy.addr()),
+        vec![coin(40, DENOM), coin(130, "USDC")]
+    );
+}
+
+#[test]
+fn membership_close_ignores_spend_limit() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    proxy
+        .set_spend_limit(&mut app, OWNER, Some((10, 100)))
+        .unwrap();
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(balance(&app, OWNER), 20);
+    assert_eq!(balance(&app, proxy.addr()), 80);
+
+    // the owner closing it sweeps nothing past the limit
+    proxy.close(&mut app, OWNER).unwrap();
+    assert_eq!(balance(&app, OWNER), 20);
+    assert_eq!(proxy.stats(&app).unwrap().retained, coins(80, DENOM));
+
+    // the membership contract closing it pays the owner out in full
+    proxy.close(&mut app, "membership").unwrap();
+    assert_eq!(balance(&app, OWNER), 100);
+    assert_eq!(balance(&app, proxy.addr()), 0);
+    assert!(proxy.stats(&app).unwrap().retained.is_empty());
+}
+
+#[test]
+fn capped_withdraw() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    // 90 received from distribution, 60 kept on the proxy
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), Some(30), None)
+        .unwrap();
+    assert_eq!(balance(&app, "receiver"), 30);
+    assert_eq!(balance(&app, proxy.addr()), 60);
+    assert_eq!(proxy.stats(&app).unwrap().retained, coins(60, DENOM));
+
+    // asking for more than there is pays out everything
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), Some(1000), None)
+        .unwrap();
+    assert_eq!(balance(&app, "receiver"), 90);
+    assert_eq!(balance(&app, proxy.addr()), 0);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+        .unwrap();
+    assert_eq!(balance(&app, "receiver"), 180);
+    assert!(proxy.stats(&app).unwrap().retained.is_empty());
+
+    let err = proxy
+        .withdraw(&mut app, OWNER, None, Some(0), None)
+        .unwrap_err();
+    assert_eq!(ContractError::ZeroWithdrawAmount, err.downcast().unwrap());
+}
+
+#[test]
+fn withdrawn_amount_reported_by_distribution() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let membership = MembershipMock::instantiate(&mut app).unwrap();
+    let distribution = distribution::multitest::CodeId::store_code(&mut app)
+        .instantiate(
+            &mut app,
+            "distribution owner",
+            membership.addr(),
+            "Distribution",
+        )
+        .unwrap();
+    let proxy = instantiate_proxy(&mut app, &init_msg(distribution.addr()));
+    membership
+        .add_member(&mut app, proxy.addr().as_str())
+        .unwrap();
+
+    // nobody has weight yet, distribution keeps it for later
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Withdraw {
+                receiver: Some("receiver".to_owned()),
+                amount: None,
+                denom: None,
+                receiver_msg: None,
+                ibc: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("_contract_address", proxy.addr())
+            .add_attribute("withdrawn", "0")
+    ));
+    assert_eq!(balance(&app, "receiver"), 0);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Withdraw {
+                receiver: Some("receiver".to_owned()),
+                amount: None,
+                denom: None,
+                receiver_msg: None,
+                ibc: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("_contract_address", proxy.addr())
+            .add_attribute("withdrawn", "180")
+    ));
+    assert_eq!(balance(&app, "receiver"), 180);
+    assert_eq!(balance(&app, distribution.addr()), 0);
+}
+
+#[test]
+fn estimated_withdrawable() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let membership = MembershipMock::instantiate(&mut app).unwrap();
+    let distribution = distribution::multitest::CodeId::store_code(&mut app)
+        .instantiate(
+            &mut app,
+            "distribution owner",
+            membership.addr(),
+            "Distribution",
+        )
+        .unwrap();
+    let proxy = instantiate_proxy(&mut app, &init_msg(distribution.addr()));
+    membership
+        .add_member(&mut app, proxy.addr().as_str())
+        .unwrap();
+
+    // the proxy only has weight in the distribution after its first withdraw
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+        .unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    assert_eq!(
+        proxy.estimated_withdrawable(&app, None).unwrap(),
+        EstimatedWithdrawableResponse {
+            from_distribution: Uint128::new(270),
+            direct_on_proxy: Uint128::zero(),
+            total: Uint128::new(270),
+        }
+    );
+
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+        .unwrap();
+    assert_eq!(balance(&app, "receiver"), 270);
+    assert_eq!(
+        proxy.estimated_withdrawable(&app, None).unwrap().total,
+        Uint128::zero()
+    );
+}
+
+#[test]
+fn withdraw_with_nothing_received() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let membership = MembershipMock::instantiate(&mut app).unwrap();
+    let distribution = distribution::multitest::CodeId::store_code(&mut app)
+        .instantiate(
+            &mut app,
+            "distribution owner",
+            membership.addr(),
+            "Distribution",
+        )
+        .unwrap();
+    let proxy = instantiate_proxy(&mut app, &init_msg(distribution.addr()));
+    membership
+        .add_member(&mut app, proxy.addr().as_str())
+        .unwrap();
+
+    // no weight in the distribution yet, so no accrued share to pay
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Withdraw {
+                receiver: Some("receiver".to_owned()),
+                amount: None,
+                denom: None,
+                receiver_msg: None,
+                ibc: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(&Event::new("wasm").add_attribute("nothing_received", DENOM)));
+    assert!(!resp.events.iter().any(|event| event.ty == "transfer"
+        && event
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "recipient" && attr.value == "receiver")));
+    assert_eq!(balance(&app, "receiver"), 0);
+    // the weight still follows the donations
+    assert_eq!(proxy.weight(&app).unwrap(), 1);
+}
+
+#[test]
+fn withdraw_replies_get_own_payload() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let membership = MembershipMock::instantiate(&mut app).unwrap();
+    let code_id = distribution::multitest::CodeId::store_code(&mut app);
+    let first = code_id
+        .instantiate(&mut app, "distribution owner", membership.addr(), "First")
+        .unwrap();
+    let second = code_id
+        .instantiate(&mut app, "distribution owner", membership.addr(), "Second")
+        .unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            distribution_contracts: vec![
+                (first.addr().to_string(), Decimal::percent(70)),
+                (second.addr().to_string(), Decimal::percent(30)),
+            ],
+            ..init_msg(first.addr())
+        },
+    );
+    membership
+        .add_member(&mut app, proxy.addr().as_str())
+        .unwrap();
+
+    // weight in both only comes with the first withdraw, what was distributed before is paid later
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+        .unwrap();
+    proxy.donate(&mut app, DONOR, &coins(200, DENOM)).unwrap();
+
+    // both submessages are in flight at once, each reply reads its own target from its payload
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Withdraw {
+                receiver: Some("receiver".to_owned()),
+                amount: None,
+                denom: None,
+                receiver_msg: None,
+                ibc: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("withdrawn_from", first.addr())
+            .add_attribute("withdrawn", "189")
+    ));
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("withdrawn_from", second.addr())
+            .add_attribute("withdrawn", "81")
+    ));
+    // and only the last one paid out what both sent
+    assert_eq!(balance(&app, "receiver"), 270);
+    assert_eq!(proxy.pending_withdrawal(&app).unwrap(), None);
+}
+
+#[test]
+fn estimated_withdrawable_unsupported() {
+    let mut app = mock_app();
+    let hook = HookMock::instantiate(&mut app, false).unwrap();
+    let proxy = instantiate_proxy(&mut app, &init_msg(hook.addr()));
+
+    let err = proxy.estimated_withdrawable(&app, None).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("can't estimate the withdrawable amount"));
+}
+
+#[test]
+fn pending_withdrawal() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(proxy.pending_withdrawal(&app).unwrap(), None);
+
+    proxy
+        .withdraw(&mut app, OWNER, Some("cold wallet"), Some(50), None)
+        .unwrap();
+    // the distribution contract sees it in flight
+    assert_eq!(
+        distribution.pending_seen(&app).unwrap(),
+        Some(PendingWithdrawalResponse {
+            receivers: vec![(Addr::unchecked("cold wallet"), Decimal::one())],
+            amount: Some(Uint128::new(50)),
+            denom: DENOM.to_owned(),
+        })
+    );
+    assert_eq!(proxy.pending_withdrawal(&app).unwrap(), None);
+}
+
+#[test]
+fn withdraw_cooldown() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            withdraw_cooldown: 100,
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    // nothing withdrawn yet, nothing to wait for
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(60));
+    let err = proxy
+        .withdraw(&mut app, OWNER, None, None, None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::WithdrawCooldown { remaining: 40 },
+        err.downcast().unwrap()
+    );
+
+    // weight updates are not withdraws
+    app.execute_contract(
+        Addr::unchecked(DONOR),
+        proxy.addr().clone(),
+        &ExecMsg::UpdateWeight {},
+        &[],
+    )
+    .unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(40));
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+
+    let err = proxy.set_withdraw_cooldown(&mut app, DONOR, 0).unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    proxy.set_withdraw_cooldown(&mut app, OWNER, 0).unwrap();
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+}
+
+fn approvers_msg(distribution: &Addr) -> InstantiateMsg {
+    InstantiateMsg {
+        approvers: vec!["alice".to_owned(), "bob".to_owned(), "carol".to_owned()],
+        threshold: 2,
+        proposal_duration: Some(100),
+        ..init_msg(distribution)
+    }
+}
+
+#[test]
+fn approved_withdraw() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(&mut app, &approvers_msg(distribution.addr()));
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let err = proxy
+        .withdraw(&mut app, OWNER, None, None, None)
+        .unwrap_err();
+    assert_eq!(ContractError::ApprovalRequired, err.downcast().unwrap());
+    let err = proxy
+        .propose_withdraw(&mut app, DONOR, None, None)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+
+    // the owner's proposal has no approval yet
+    proxy
+        .propose_withdraw(&mut app, OWNER, Some("cold wallet"), None)
+        .unwrap();
+    let err = proxy.approve_withdraw(&mut app, OWNER, 0).unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+    proxy.approve_withdraw(&mut app, "alice", 0).unwrap();
+    assert_eq!(distribution.withdrawals(&app).unwrap(), vec![]);
+    assert_eq!(
+        proxy.withdraw_proposal(&app, 0).unwrap(),
+        Some(WithdrawProposalResponse {
+            id: 0,
+            receiver: Addr::unchecked("cold wallet"),
+            amount: None,
+            approvals: vec![Addr::unchecked("alice")],
+            expires: app.block_info().time.seconds() + 100,
+            expired: false,
+        })
+    );
+
+    let resp = proxy.approve_withdraw(&mut app, "carol", 0).unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "withdraw")
+            .add_attribute("proposal_id", "0")
+    ));
+    assert_eq!(distribution.withdrawals(&app).unwrap(), vec![(10, -9)]);
+    assert_eq!(balance(&app, "cold wallet"), 90);
+    assert_eq!(proxy.withdraw_proposal(&app, 0).unwrap(), None);
+    let err = proxy.approve_withdraw(&mut app, "bob", 0).unwrap_err();
+    assert_eq!(
+        ContractError::ProposalNotFound { id: 0 },
+        err.downcast().unwrap()
+    );
+
+    // an approver proposing approves it too
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.propose_withdraw(&mut app, "bob", None, None).unwrap();
+    assert_eq!(
+        proxy.withdraw_proposal(&app, 1).unwrap().unwrap().approvals,
+        vec![Addr::unchecked("bob")]
+    );
+    proxy.approve_withdraw(&mut app, "alice", 1).unwrap();
+    assert_eq!(balance(&app, OWNER), 20 + 90);
+}
+
+#[test]
+fn approvers_guard_every_owner_payout() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(&mut app, &approvers_msg(distribution.addr()));
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let err = proxy.withdraw_direct(&mut app, OWNER, None).unwrap_err();
+    assert_eq!(ContractError::ApprovalRequired, err.downcast().unwrap());
+    let err = proxy.withdraw_all(&mut app, OWNER, None).unwrap_err();
+    assert_eq!(ContractError::ApprovalRequired, err.downcast().unwrap());
+    let other = DistributionMock::instantiate(&mut app).unwrap();
+    let err = proxy
+        .set_distribution_contract(&mut app, OWNER, other.addr())
+        .unwrap_err();
+    assert_eq!(ContractError::ApprovalRequired, err.downcast().unwrap());
+    proxy
+        .set_auto_withdraw_threshold(&mut app, OWNER, Some(0))
+        .unwrap();
+    let err = proxy.crank(&mut app, "keeper").unwrap_err();
+    assert_eq!(ContractError::ApprovalRequired, err.downcast().unwrap());
+
+    // closing and reopening moves nothing
+    proxy.close(&mut app, OWNER).unwrap();
+    proxy.reopen(&mut app, OWNER).unwrap();
+    assert_eq!(balance(&app, OWNER), 10);
+    assert_eq!(balance(&app, distribution.addr()), 90);
+    assert_eq!(distribution.withdrawals(&app).unwrap(), vec![]);
+
+    // the membership contract still pays the owner out
+    proxy.close(&mut app, "membership").unwrap();
+    assert_eq!(balance(&app, OWNER), 100);
+}
+
+#[test]
+fn duplicate_withdraw_approval() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(&mut app, &approvers_msg(distribution.addr()));
+
+    proxy
+        .propose_withdraw(&mut app, "alice", None, None)
+        .unwrap();
+    let err = proxy.approve_withdraw(&mut app, "alice", 0).unwrap_err();
+    assert_eq!(ContractError::AlreadyApproved, err.downcast().unwrap());
+    assert_eq!(distribution.withdrawals(&app).unwrap(), vec![]);
+}
+
+#[test]
+fn expired_withdraw_proposal() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(&mut app, &approvers_msg(distribution.addr()));
+
+    proxy
+        .propose_withdraw(&mut app, "alice", None, None)
+        .unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+    assert!(proxy.withdraw_proposals(&app).unwrap()[0].expired);
+
+    let err = proxy.approve_withdraw(&mut app, "bob", 0).unwrap_err();
+    assert_eq!(
+        ContractError::ProposalExpired { id: 0 },
+        err.downcast().unwrap()
+    );
+    assert_eq!(distribution.withdrawals(&app).unwrap(), vec![]);
+}
+
+#[test]
+fn invalid_approvers() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let code_id = CodeId::store_code(&mut app);
+
+    let cases = [
+        (
+            vec!["alice", "bob"],
+            3,
+            ContractError::InvalidThreshold {
+                threshold: 3,
+                approvers: 2,
+            },
+        ),
+        (
+            vec!["alice", "bob"],
+            0,
+            ContractError::InvalidThreshold {
+                threshold: 0,
+                approvers: 2,
+            },
+        ),
+        (
+            vec![],
+            1,
+            ContractError::InvalidThreshold {
+                threshold: 1,
+                approvers: 0,
+            },
+        ),
+        (
+            vec!["alice", "alice"],
+            1,
+            ContractError::DuplicateApprover("alice".to_owned()),
+        ),
+    ];
+    for (approvers, threshold, expected) in cases {
+        let msg = InstantiateMsg {
+            approvers: approvers.into_iter().map(str::to_owned).collect(),
+            threshold,
+            ..init_msg(distribution.addr())
+        };
+        let err = code_id
+            .instantiate(&mut app, OWNER, &msg, "Proxy")
+            .unwrap_err();
+        assert_eq!(expected, err.downcast().unwrap());
+    }
+}
+
+#[test]
+fn failed_distribution_withdraw_commits_nothing() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::failing(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            withdraw_cooldown: 100,
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    let weight = proxy.weight(&app).unwrap();
+    let withdraw_msg = ExecMsg::Withdraw {
+        receiver: Some("receiver".to_owned()),
+        amount: None,
+        denom: None,
+        receiver_msg: None,
+        ibc: None,
+    };
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &withdraw_msg,
+            &[],
+        )
+        .unwrap();
+    let failed = resp
+        .events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .find(|attr| attr.key == "withdraw_failed")
+        .unwrap();
+    // multi-test reports the failed message rather than the contract's own error
+    assert!(
+        failed.value.contains(distribution.addr().as_str()),
+        "{}",
+        failed.value
+    );
+
+    assert_eq!(balance(&app, "receiver"), 0);
+    assert_eq!(balance(&app, distribution.addr()), 90);
+    assert_eq!(proxy.weight(&app).unwrap(), weight);
+    assert_eq!(proxy.stats(&app).unwrap().donations, 1);
+    assert_eq!(proxy.pending_withdrawal(&app).unwrap(), None);
+
+    // the cooldown did not start, the distribution fails again
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &withdraw_msg,
+            &[],
+        )
+        .unwrap();
+    assert!(resp.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "withdraw_failed")));
+}
+
+#[test]
+fn failed_withdraw_with_several_distributions_reverts() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let working = DistributionMock::instantiate(&mut app).unwrap();
+    let failing = DistributionMock::failing(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            distribution_contracts: vec![
+                (working.addr().to_string(), Decimal::percent(50)),
+                (failing.addr().to_string(), Decimal::percent(50)),
+            ],
+            ..init_msg(working.addr())
+        },
+    );
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    let err = proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+        .unwrap_err();
+    match err.downcast().unwrap() {
+        ContractError::SubMsgFailed { submsg, error } => {
+            assert_eq!(submsg, "withdraw");
+            assert!(error.contains(failing.addr().as_str()), "{}", error);
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    // the working one's withdraw is undone as well
+    assert_eq!(working.withdrawals(&app).unwrap(), vec![]);
+    assert_eq!(balance(&app, "receiver"), 0);
+    assert_eq!(proxy.stats(&app).unwrap().donations, 1);
+    assert_eq!(proxy.pending_withdrawal(&app).unwrap(), None);
+}
+
+#[test]
+fn failed_propose_member() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    // any execute on the "membership" contract fails
+    let membership = HookMock::instantiate(&mut app, true).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            membership_contract: membership.addr().to_string(),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    let err = proxy
+        .propose_member(&mut app, OWNER, "candidate")
+        .unwrap_err();
+    match err.downcast().unwrap() {
+        ContractError::SubMsgFailed { submsg, error } => {
+            assert_eq!(submsg, "propose_member");
+            assert!(error.contains(membership.addr().as_str()), "{}", error);
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+    assert_eq!(proxy.proposed_members(&app).unwrap(), vec![]);
+}
+
+#[test]
+fn withdraw_split_between_receivers() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    // 37 doesn't split evenly: 18 + 11 + 7, the remaining 1 goes to alice
+    proxy
+        .withdraw_split(
+            &mut app,
+            OWNER,
+            &[
+                ("alice", Decimal::percent(50)),
+                ("bob", Decimal::percent(30)),
+                ("carol", Decimal::percent(20)),
+            ],
+            Some(37),
+        )
+        .unwrap();
+    assert_eq!(balance(&app, "alice"), 19);
+    assert_eq!(balance(&app, "bob"), 11);
+    assert_eq!(balance(&app, "carol"), 7);
+    assert_eq!(balance(&app, proxy.addr()), 53);
+}
+
+#[test]
+fn invalid_withdraw_split() {
+    let mut app = mock_app();
+    let (proxy, _) = setup(&mut app);
+
+    let err = proxy
+        .withdraw_split(&mut app, OWNER, &[], None)
+        .unwrap_err();
+    assert_eq!(ContractError::NoReceivers, err.downcast().unwrap());
+
+    let receivers = vec![("alice", Decimal::percent(10)); 11];
+    let err = proxy
+        .withdraw_split(&mut app, OWNER, &receivers, None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::TooManyReceivers { max: 10 },
+        err.downcast().unwrap()
+    );
+
+    let err = proxy
+        .withdraw_split(
+            &mut app,
+            OWNER,
+            &[
+                ("alice", Decimal::percent(50)),
+                ("bob", Decimal::percent(40)),
+            ],
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidReceiverShares,
+        err.downcast().unwrap()
+    );
+
+    let err = proxy
+        .withdraw_split(&mut app, DONOR, &[("alice", Decimal::one())], None)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+}
+
+#[test]
+fn withdraw_direct_leaves_reserved_funds() {
+    let mut app = app_with_donors(&[(DONOR, 1000), (OWNER, 500)]);
+    let (proxy, _) = setup(&mut app);
+
+    let err = proxy.withdraw_direct(&mut app, OWNER, None).unwrap_err();
+    assert_eq!(ContractError::NothingToWithdraw, err.downcast().unwrap());
+
+    // 60 retained on the proxy by a capped withdraw, 50 in the matching pool
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), Some(30), None)
+        .unwrap();
+    proxy
+        .fund_matching(&mut app, OWNER, &coins(50, DENOM))
+        .unwrap();
+
+    let err = proxy
+        .withdraw_direct(&mut app, DONOR, Some("receiver"))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    proxy
+        .withdraw_direct(&mut app, OWNER, Some("receiver"))
+        .unwrap();
+    assert_eq!(balance(&app, "receiver"), 90);
+    assert_eq!(balance(&app, proxy.addr()), 50);
+    assert!(proxy.stats(&app).unwrap().retained.is_empty());
+
+    let err = proxy.withdraw_direct(&mut app, OWNER, None).unwrap_err();
+    assert_eq!(ContractError::NothingToWithdraw, err.downcast().unwrap());
+}
+
+#[test]
+fn extreme_weights_do_not_wrap() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            weight: u64::MAX,
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    // halves the weight, an i64 cast would have made it negative
+    app.update_block(|block| block.time = block.time.plus_seconds(3600 * 24 * 30));
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::UpdateWeight {},
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm-proxy-weight-update")
+            .add_attribute("old_weight", u64::MAX.to_string())
+            .add_attribute("new_weight", (u64::MAX - u64::MAX / 2).to_string())
+            .add_attribute("diff", (-i128::from(u64::MAX / 2)).to_string())
+    ));
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Withdraw {
+                receiver: None,
+                amount: None,
+                denom: None,
+                receiver_msg: None,
+                ibc: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm-proxy-withdraw")
+            .add_attribute("diff", (-i128::from(u64::MAX - u64::MAX / 2)).to_string())
+    ));
+}
+
+#[test]
+fn keeper_cranks_withdrawal() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            withdraw_cooldown: 100,
+            keeper_fee_bps: 1000,
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    let err = proxy.crank(&mut app, "keeper").unwrap_err();
+    assert_eq!(ContractError::AutoWithdrawDisabled, err.downcast().unwrap());
+
+    let err = proxy
+        .set_auto_withdraw_threshold(&mut app, "keeper", Some(100))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    proxy
+        .set_auto_withdraw_threshold(&mut app, OWNER, Some(100))
+        .unwrap();
+
+    // 90 waiting in the distribution contract
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    let err = proxy.crank(&mut app, "keeper").unwrap_err();
+    assert_eq!(
+        ContractError::BelowWithdrawThreshold {
+            pending: Uint128::new(90),
+            threshold: Uint128::new(100)
+        },
+        err.downcast().unwrap()
+    );
+
+    proxy
+        .set_auto_withdraw_threshold(&mut app, OWNER, Some(50))
+        .unwrap();
+    proxy.crank(&mut app, "keeper").unwrap();
+    assert_eq!(balance(&app, "keeper"), 9);
+    assert_eq!(balance(&app, OWNER), 10 + 81);
+    assert_eq!(balance(&app, distribution.addr()), 0);
+
+    // cranks don't start the owner's cooldown
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+}
+
+#[test]
+fn donations_reset_to_zero_after_withdraw() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(proxy.stats(&app).unwrap().donations, 0);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(proxy.stats(&app).unwrap().donations, 0);
+
+    // 10 -> 1 after one donation, 1 -> 2 after two
+    assert_eq!(
+        distribution.withdrawals(&app).unwrap(),
+        vec![(10, -9), (1, 1)]
+    );
+}
+
+#[test]
+fn withdraw_all_denoms() {
+    let mut app = app_with_balances(&[(OWNER, coins(50, DENOM)), ("sender", coins(30, "ATOM"))]);
+    let (proxy, _) = setup(&mut app);
+
+    // stray ATOM sent to the proxy, ORAI in the matching pool stays reserved
+    app.send_tokens(
+        Addr::unchecked("sender"),
+        proxy.addr().clone(),
+        &coins(30, "ATOM"),
+    )
+    .unwrap();
+    proxy
+        .fund_matching(&mut app, OWNER, &coins(50, DENOM))
+        .unwrap();
+
+    let err = proxy.withdraw_all(&mut app, DONOR, None).unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    proxy
+        .withdraw_all(&mut app, OWNER, Some("receiver"))
+        .unwrap();
+    assert_eq!(all_balances(&app, "receiver"), coins(30, "ATOM"));
+    assert_eq!(balance(&app, proxy.addr()), 50);
+
+    let err = proxy.withdraw_all(&mut app, OWNER, None).unwrap_err();
+    assert_eq!(ContractError::NothingToWithdraw, err.downcast().unwrap());
+}
+
+#[test]
+fn withdraw_while_pending() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::reentrant(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            auto_withdraw_threshold: Some(Uint128::new(50)),
+            ..init_msg(distribution.addr())
+        },
+    );
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    // the distribution cranks the proxy while the owner's withdraw is still pending,
+    // the crank fails and takes the distribution withdraw down with it, ours reports that
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Withdraw {
+                receiver: Some("receiver".to_owned()),
+                amount: None,
+                denom: None,
+                receiver_msg: None,
+                ibc: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "withdraw_failed")));
+    assert_eq!(proxy.pending_withdrawal(&app).unwrap(), None);
+    assert_eq!(balance(&app, "receiver"), 0);
+    assert_eq!(balance(&app, distribution.addr()), 90);
+
+    // withdraws in one batch don't collide, each reply lands before the next withdraw
+    let (proxy, _) = setup(&mut app);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    let withdraw_msg = WasmMsg::Execute {
+        contract_addr: proxy.addr().to_string(),
+        msg: to_json_binary(&ExecMsg::Withdraw {
+            receiver: Some("receiver".to_owned()),
+            amount: Some(Uint128::new(40)),
+            denom: None,
+            receiver_msg: None,
+            ibc: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    };
+    app.execute_multi(
+        Addr::unchecked(OWNER),
+        vec![withdraw_msg.clone().into(), withdraw_msg.into()],
+    )
+    .unwrap();
+    assert_eq!(balance(&app, "receiver"), 80);
+}
+
+#[test]
+fn withdrawal_history() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), Some(30), None)
+        .unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(10));
+    proxy
+        .withdraw_split(
+            &mut app,
+            OWNER,
+            &[
+                ("alice", Decimal::percent(50)),
+                ("bob", Decimal::percent(50)),
+            ],
+            None,
+        )
+        .unwrap();
+
+    let time = app.block_info().time.seconds();
+    let history = proxy.withdrawal_history(&app, None, None).unwrap();
+    assert_eq!(
+        history.withdrawals,
+        vec![
+            WithdrawalRecordResponse {
+                id: 2,
+                receiver: Addr::unchecked("bob"),
+                amount: Uint128::new(30),
+                denom: DENOM.to_owned(),
+                block_time: time,
+                epoch_donations: 0,
+            },
+            WithdrawalRecordResponse {
+                id: 1,
+                receiver: Addr::unchecked("alice"),
+                amount: Uint128::new(30),
+                denom: DENOM.to_owned(),
+                block_time: time,
+                epoch_donations: 0,
+            },
+            // the capped amount, not the whole balance
+            WithdrawalRecordResponse {
+                id: 0,
+                receiver: Addr::unchecked("receiver"),
+                amount: Uint128::new(30),
+                denom: DENOM.to_owned(),
+                block_time: time - 10,
+                epoch_donations: 1,
+            },
+        ]
+    );
+
+    let page = proxy.withdrawal_history(&app, None, Some(2)).unwrap();
+    let ids: Vec<_> = page.withdrawals.iter().map(|record| record.id).collect();
+    assert_eq!(ids, vec![2, 1]);
+    let page = proxy.withdrawal_history(&app, Some(1), Some(2)).unwrap();
+    let ids: Vec<_> = page.withdrawals.iter().map(|record| record.id).collect();
+    assert_eq!(ids, vec![0]);
+}
+
+#[test]
+fn failed_withdraw_leaves_no_record() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::failing(&mut app).unwrap();
+    let proxy = instantiate_proxy(&mut app, &init_msg(distribution.addr()));
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    // reported as withdraw_failed, nothing paid out
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+
+    let history = proxy.withdrawal_history(&app, None, None).unwrap();
+    assert!(history.withdrawals.is_empty());
+}
+
+#[test]
+fn operators() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    let err = proxy
+        .grant_operator(&mut app, "operator", "operator", true, true)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    proxy
+        .grant_operator(&mut app, OWNER, "operator", true, false)
+        .unwrap();
+    assert_eq!(
+        proxy.operators(&app).unwrap().operators,
+        vec![Operator {
+            addr: Addr::unchecked("operator"),
+            can_withdraw: true,
+            can_propose: false,
+        }]
+    );
+
+    // withdraws go to the owner unless told otherwise
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy
+        .withdraw(&mut app, "operator", None, None, None)
+        .unwrap();
+    assert_eq!(balance(&app, OWNER), 100);
+    assert_eq!(balance(&app, "operator"), 0);
+
+    let err = proxy
+        .propose_member(&mut app, "operator", "member")
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+
+    // operators never manage operators
+    let err = proxy
+        .grant_operator(&mut app, "operator", "other", true, true)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    let err = proxy.close(&mut app, "operator").unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    proxy.revoke_operator(&mut app, OWNER, "operator").unwrap();
+    assert!(proxy.operators(&app).unwrap().operators.is_empty());
+    let err = proxy
+        .withdraw(&mut app, "operator", None, None, None)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+}
+
+#[test]
+fn withdraw_to_invalid_receiver() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let err = proxy
+        .withdraw(&mut app, OWNER, Some(proxy.addr().as_str()), None, None)
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidReceiver, err.downcast().unwrap());
+
+    let err = proxy
+        .withdraw(
+            &mut app,
+            OWNER,
+            Some(distribution.addr().as_str()),
+            None,
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidReceiver, err.downcast().unwrap());
+
+    let err = proxy
+        .withdraw_split(
+            &mut app,
+            OWNER,
+            &[
+                ("alice", Decimal::percent(50)),
+                (proxy.addr().as_str(), Decimal::percent(50)),
+            ],
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidReceiver, err.downcast().unwrap());
+
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+        .unwrap();
+    assert_eq!(balance(&app, "receiver"), 90);
+}
+
+#[test]
+fn withdraw_with_receiver_msg() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    let treasury = ReceiverMock::instantiate(&mut app, false).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Withdraw {
+                receiver: None,
+                amount: None,
+                denom: None,
+                receiver_msg: Some(ReceiverMock::deposit_msg()),
+                ibc: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ReceiverMsgWithoutReceiver,
+        err.downcast().unwrap()
+    );
+
+    proxy
+        .withdraw_with_msg(
+            &mut app,
+            OWNER,
+            treasury.addr().as_str(),
+            ReceiverMock::deposit_msg(),
+        )
+        .unwrap();
+    assert_eq!(treasury.deposits(&app).unwrap(), coins(90, DENOM));
+    assert_eq!(balance(&app, treasury.addr()), 90);
+    assert_eq!(balance(&app, proxy.addr()), 0);
+}
+
+fn ibc_withdraw(channel_id: &str, to_address: &str, timeout_seconds: u64) -> IbcWithdraw {
+    IbcWithdraw {
+        channel_id: channel_id.to_owned(),
+        to_address: to_address.to_owned(),
+        timeout_seconds,
+    }
+}
+
+#[test]
+fn withdraw_over_ibc() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let err = proxy
+        .withdraw_ibc(&mut app, OWNER, ibc_withdraw(" ", "osmo1creator", 600))
+        .unwrap_err();
+    assert_eq!(ContractError::EmptyIbcChannel, err.downcast().unwrap());
+    let err = proxy
+        .withdraw_ibc(&mut app, OWNER, ibc_withdraw("channel-7", "", 600))
+        .unwrap_err();
+    assert_eq!(ContractError::EmptyIbcAddress, err.downcast().unwrap());
+    let err = proxy
+        .withdraw_ibc(
+            &mut app,
+            OWNER,
+            ibc_withdraw("channel-7", "osmo1creator", 0),
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::ZeroIbcTimeout, err.downcast().unwrap());
+    let err = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Withdraw {
+                receiver: Some(OWNER.to_owned()),
+                amount: None,
+                denom: None,
+                receiver_msg: None,
+                ibc: Some(ibc_withdraw("channel-7", "osmo1creator", 600)),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::IbcWithReceiver, err.downcast().unwrap());
+
+    // the default app has no IBC stack, its failure shows the transfer the reply sent
+    let timeout = app.block_info().time.plus_seconds(600);
+    let err = proxy
+        .withdraw_ibc(
+            &mut app,
+            OWNER,
+            ibc_withdraw("channel-7", "osmo1creator", 600),
+        )
+        .unwrap_err();
+    let err = err.root_cause().to_string();
+    assert!(err.starts_with("Cannot execute Ibc(Transfer"), "{err}");
+    for expected in [
+        r#"channel_id: "channel-7""#.to_owned(),
+        r#"to_address: "osmo1creator""#.to_owned(),
+        r#"amount: Coin { 90 "ORAI" }"#.to_owned(),
+        format!("{:?}", IbcTimeout::with_timestamp(timeout)),
+    ] {
+        assert!(err.contains(&expected), "{expected} not in {err}");
+    }
+    // nothing bank sent to the owner, reverted as a whole
+    assert_eq!(balance(&app, OWNER), 10);
+    assert_eq!(balance(&app, distribution.addr()), 90);
+}
+
+#[test]
+fn failing_receiver_reverts_withdraw() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+    let treasury = ReceiverMock::instantiate(&mut app, true).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    proxy
+        .withdraw_with_msg(
+            &mut app,
+            OWNER,
+            treasury.addr().as_str(),
+            ReceiverMock::deposit_msg(),
+        )
+        .unwrap_err();
+
+    // nothing got pulled out of the distribution contract either
+    assert_eq!(balance(&app, distribution.addr()), 90);
+    assert_eq!(balance(&app, proxy.addr()), 0);
+    assert_eq!(balance(&app, treasury.addr()), 0);
+    let history = proxy.withdrawal_history(&app, None, None).unwrap();
+    assert!(history.withdrawals.is_empty());
+}
+
+#[test]
+fn streamed_withdraw() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let err = proxy.claim_stream(&mut app, "keeper").unwrap_err();
+    assert_eq!(ContractError::NoActiveStream, err.downcast().unwrap());
+    let err = proxy
+        .withdraw_streamed(&mut app, OWNER, "receiver", 0)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidStreamDuration,
+        err.downcast().unwrap()
+    );
+    let err = proxy
+        .withdraw_streamed(&mut app, OWNER, "receiver", u64::MAX)
+        .unwrap_err();
+    assert_eq!(ContractError::TimeOverflow, err.downcast().unwrap());
+
+    proxy
+        .withdraw_streamed(&mut app, OWNER, "receiver", 100)
+        .unwrap();
+    let start = app.block_info().time.seconds();
+    assert_eq!(
+        proxy.stream(&app).unwrap(),
+        Some(StreamResponse {
+            receiver: Addr::unchecked("receiver"),
+            denom: DENOM.to_owned(),
+            total: Uint128::new(90),
+            claimed: Uint128::zero(),
+            claimable: Uint128::zero(),
+            start,
+            end: start + 100,
+        })
+    );
+    assert_eq!(balance(&app, proxy.addr()), 90);
+    assert_eq!(balance(&app, "receiver"), 0);
+
+    let err = proxy.claim_stream(&mut app, "keeper").unwrap_err();
+    assert_eq!(ContractError::NothingToWithdraw, err.downcast().unwrap());
+    let err = proxy
+        .withdraw_streamed(&mut app, OWNER, "other", 100)
+        .unwrap_err();
+    assert_eq!(ContractError::StreamActive, err.downcast().unwrap());
+
+    // anyone can claim for the receiver
+    app.update_block(|block| block.time = block.time.plus_seconds(30));
+    proxy.claim_stream(&mut app, "keeper").unwrap();
+    assert_eq!(balance(&app, "receiver"), 27);
+
+    // 90 * 63 / 100 = 56.7, rounded down
+    app.update_block(|block| block.time = block.time.plus_seconds(33));
+    let stream = proxy.stream(&app).unwrap().unwrap();
+    assert_eq!(stream.claimable, Uint128::new(29));
+    proxy.claim_stream(&mut app, "keeper").unwrap();
+    assert_eq!(balance(&app, "receiver"), 56);
+
+    // a regular withdraw leaves the streamed funds alone
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.withdraw_direct(&mut app, OWNER, None).unwrap_err();
+    proxy
+        .withdraw(&mut app, OWNER, Some("alice"), None, None)
+        .unwrap();
+    assert_eq!(balance(&app, "alice"), 90);
+    assert_eq!(balance(&app, proxy.addr()), 34);
+
+    // the last claim pays the exact rest
+    app.update_block(|block| block.time = block.time.plus_seconds(1000));
+    proxy.claim_stream(&mut app, "keeper").unwrap();
+    assert_eq!(balance(&app, "receiver"), 90);
+    assert_eq!(balance(&app, proxy.addr()), 0);
+    assert_eq!(proxy.stream(&app).unwrap(), None);
+}
+
+#[test]
+fn close_withdraws_outstanding_share() {
+    let mut app = app_with_donors(&[(DONOR, 1000), ("sponsor", 1000)]);
+    let membership = MembershipMock::instantiate(&mut app).unwrap();
+    let distribution = distribution::multitest::CodeId::store_code(&mut app)
+        .instantiate(
+            &mut app,
+            "distribution owner",
+            membership.addr(),
+            "Distribution",
+        )
+        .unwrap();
+    let proxy = instantiate_proxy(&mut app, &init_msg(distribution.addr()));
+    membership
+        .add_member(&mut app, proxy.addr().as_str())
+        .unwrap();
+
+    // the first withdraw registers the proxy's weight
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+        .unwrap();
+    assert_eq!(distribution.weight(&app, proxy.addr().as_str()).unwrap(), 1);
+
+    distribution
+        .distribute(&mut app, "sponsor", &coins(50, DENOM))
+        .unwrap();
+    let outstanding = distribution
+        .withdrawable(&app, proxy.addr().as_str(), DENOM)
+        .unwrap();
+    assert_ne!(outstanding, 0);
+
+    let before = balance(&app, OWNER);
+    proxy.close(&mut app, OWNER).unwrap();
+    assert_eq!(balance(&app, OWNER), before + outstanding);
+    assert_eq!(distribution.weight(&app, proxy.addr().as_str()).unwrap(), 0);
+    assert_eq!(distribution.total_weight(&app).unwrap(), 0);
+}
+
+#[test]
+fn close_zeroes_weight() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.close(&mut app, OWNER).unwrap();
+    assert_eq!(distribution.withdrawals(&app).unwrap(), vec![(10, -10)]);
+    assert_eq!(balance(&app, OWNER), 100);
+
+    // no weight, nothing to withdraw
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            weight: 0,
+            ..init_msg(distribution.addr())
+        },
+    );
+    proxy.close(&mut app, OWNER).unwrap();
+    assert_eq!(distribution.withdrawals(&app).unwrap(), vec![]);
+}
+
+#[test]
+fn reopen() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    let err = proxy.reopen(&mut app, OWNER).unwrap_err();
+    assert_eq!(ContractError::NotClosed, err.downcast().unwrap());
+
+    proxy.close(&mut app, OWNER).unwrap();
+    let err = proxy
+        .donate(&mut app, DONOR, &coins(100, DENOM))
+        .unwrap_err();
+    assert_eq!(ContractError::ContractClosed, err.downcast().unwrap());
+
+    let err = proxy.reopen(&mut app, DONOR).unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    proxy.reopen(&mut app, OWNER).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(balance(&app, DONOR), 900);
+}
+
+#[test]
+fn withdraw_of_unexpected_denom() {
+    let mut app = app_with_balances(&[
+        (DONOR, coins(1000, DENOM)),
+        ("sponsor", coins(100, "uother")),
+    ]);
+    let (proxy, distribution) = setup(&mut app);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    // mock pays back everything it holds, whatever the denom
+    app.send_tokens(
+        Addr::unchecked("sponsor"),
+        distribution.addr().clone(),
+        &coins(100, "uother"),
+    )
+    .unwrap();
+
+    let err = proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::UnexpectedDenom {
+            expected: DENOM.to_owned(),
+            got: "uother".to_owned()
+        },
+        err.downcast().unwrap()
+    );
+
+    // everything stays with the distribution contract
+    assert_eq!(balance(&app, distribution.addr()), 90);
+    assert_eq!(balance(&app, "receiver"), 0);
+    let other = app
+        .wrap()
+        .query_balance(distribution.addr(), "uother")
+        .unwrap();
+    assert_eq!(other.amount.u128(), 100);
+}
+
+#[test]
+fn update_policy() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    // anyone by default
+    proxy.update_weight(&mut app, DONOR).unwrap();
+
+    let err = proxy
+        .set_update_policy(&mut app, DONOR, UpdatePolicyMsg::Owner {})
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    proxy
+        .set_update_policy(&mut app, OWNER, UpdatePolicyMsg::Owner {})
+        .unwrap();
+    let err = proxy.update_weight(&mut app, DONOR).unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+    proxy.update_weight(&mut app, OWNER).unwrap();
+
+    proxy
+        .set_update_policy(
+            &mut app,
+            OWNER,
+            UpdatePolicyMsg::Keeper {
+                addr: "keeper".to_owned(),
+            },
+        )
+        .unwrap();
+    let err = proxy.update_weight(&mut app, OWNER).unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+    proxy.update_weight(&mut app, "keeper").unwrap();
+
+    proxy
+        .set_update_policy(&mut app, OWNER, UpdatePolicyMsg::Membership {})
+        .unwrap();
+    let err = proxy.update_weight(&mut app, "keeper").unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+    proxy.update_weight(&mut app, "membership").unwrap();
+
+    proxy
+        .set_update_policy(&mut app, OWNER, UpdatePolicyMsg::Anyone {})
+        .unwrap();
+    proxy.update_weight(&mut app, DONOR).unwrap();
+}
+
+#[test]
+fn update_weight_once_per_halftime() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(3600 * 24 * 30));
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::UpdateWeight {},
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("_contract_address", proxy.addr())
+            .add_attribute("performed", "yes")
+    ));
+
+    // the next block is not another halftime
+    app.update_block(|block| block.time = block.time.plus_seconds(5));
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::UpdateWeight {},
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("_contract_address", proxy.addr())
+            .add_attribute("performed", "no")
+    ));
+}
+
+#[test]
+fn configurable_decay() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let err = CodeId::store_code(&mut app)
+        .instantiate(
+            &mut app,
+            OWNER,
+            &InstantiateMsg {
+                decay: Some(Decimal::zero()),
+                ..init_msg(distribution.addr())
+            },
+            "Proxy",
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidDecay, err.downcast().unwrap());
+
+    let halftime = |app: &mut App| {
+        app.update_block(|block| block.time = block.time.plus_seconds(3600 * 24 * 30))
+    };
+    let weight_update = |old: u64, decay: &str, new: u64| {
+        Event::new("wasm-proxy-weight-update")
+            .add_attribute("old_weight", old.to_string())
+            .add_attribute("decay", decay)
+            .add_attribute("new_weight", new.to_string())
+    };
+
+    // default halving
+    let proxy = instantiate_proxy(&mut app, &init_msg(distribution.addr()));
+    halftime(&mut app);
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::UpdateWeight {},
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(&weight_update(10, "0.5", 5)));
+
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            decay: Some(Decimal::percent(25)),
+            ..init_msg(distribution.addr())
+        },
+    );
+    halftime(&mut app);
+    // 2.5 rounded down
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::UpdateWeight {},
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(&weight_update(10, "0.25", 8)));
+
+    let err = proxy
+        .set_decay(&mut app, DONOR, Decimal::one())
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    let err = proxy
+        .set_decay(&mut app, OWNER, Decimal::percent(150))
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidDecay, err.downcast().unwrap());
+
+    // full reset
+    proxy.set_decay(&mut app, OWNER, Decimal::one()).unwrap();
+    halftime(&mut app);
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::UpdateWeight {},
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(&weight_update(8, "1", 0)));
+}
+
+#[test]
+fn decay_for_every_elapsed_halftime() {
+    const HALFTIME: u64 = 3600 * 24 * 30;
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let weight_update = |old: u64, periods: u64, new: u64| {
+        Event::new("wasm-proxy-weight-update")
+            .add_attribute("old_weight", old.to_string())
+            .add_attribute("periods", periods.to_string())
+            .add_attribute("new_weight", new.to_string())
+    };
+    let update_weight = |app: &mut App, proxy: &Contract| {
+        app.execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::UpdateWeight {},
+            &[],
+        )
+        .unwrap()
+    };
+
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            weight: 1_000_000,
+            ..init_msg(distribution.addr())
+        },
+    );
+    app.update_block(|block| block.time = block.time.plus_seconds(HALFTIME + 100));
+    let resp = update_weight(&mut app, &proxy);
+    assert!(resp.has_event(&weight_update(1_000_000, 1, 500_000)));
+
+    // the 100 seconds into the next period are not lost
+    app.update_block(|block| block.time = block.time.plus_seconds(3 * HALFTIME - 100));
+    let resp = update_weight(&mut app, &proxy);
+    assert!(resp.has_event(&weight_update(500_000, 3, 62_500)));
+
+    // capped at 32 periods, the remaining 8 are applied by the next update
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            weight: u64::MAX,
+            ..init_msg(distribution.addr())
+        },
+    );
+    app.update_block(|block| block.time = block.time.plus_seconds(40 * HALFTIME));
+    let resp = update_weight(&mut app, &proxy);
+    assert!(resp.has_event(&weight_update(u64::MAX, 32, 1 << 32)));
+    let resp = update_weight(&mut app, &proxy);
+    assert!(resp.has_event(&weight_update(1 << 32, 8, 1 << 24)));
+}
+
+#[test]
+fn next_update() {
+    const HALFTIME: u64 = 3600 * 24 * 30;
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    let start = app.block_info().time;
+
+    app.update_block(|block| block.time = block.time.plus_seconds(HALFTIME - 10));
+    assert_eq!(
+        proxy.next_update(&app).unwrap(),
+        NextUpdateResponse {
+            last_updated: Expiration::AtTime(start),
+            halftime: Duration::Time(HALFTIME),
+            next_eligible_at: Expiration::AtTime(start.plus_seconds(HALFTIME)),
+            remaining: 10,
+        }
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(10));
+    assert_eq!(proxy.next_update(&app).unwrap().remaining, 0);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(10));
+    assert_eq!(proxy.next_update(&app).unwrap().remaining, 0);
+
+    // a block time before the last update can't underflow
+    app.update_block(|block| block.time = block.time.minus_seconds(HALFTIME + 100));
+    assert_eq!(proxy.next_update(&app).unwrap().remaining, HALFTIME + 90);
+}
+
+#[test]
+fn weight_change_events() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let time = app.block_info().time.seconds();
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Withdraw {
+                receiver: None,
+                amount: None,
+                denom: None,
+                receiver_msg: None,
+                ibc: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm-proxy-weight")
+            .add_attribute("old_weight", "10")
+            .add_attribute("new_weight", "1")
+            .add_attribute("reason", "withdraw")
+            .add_attribute("block_time", time.to_string())
+    ));
+
+    app.update_block(|block| block.time = block.time.plus_seconds(3600 * 24 * 30));
+    let time = app.block_info().time.seconds();
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::UpdateWeight {},
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm-proxy-weight")
+            .add_attribute("old_weight", "1")
+            .add_attribute("new_weight", "1")
+            .add_attribute("reason", "decay")
+            .add_attribute("block_time", time.to_string())
+    ));
+}
+
+#[test]
+fn withdraw_caps_weight() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            max_weight: Some(3),
+            ..init_msg(distribution.addr())
+        },
+    );
+    for _ in 0..5 {
+        proxy.donate(&mut app, DONOR, &coins(10, DENOM)).unwrap();
+    }
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Withdraw {
+                receiver: None,
+                amount: None,
+                denom: None,
+                receiver_msg: None,
+                ibc: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("_contract_address", proxy.addr())
+            .add_attribute("weight_capped", "3")
+    ));
+    assert!(resp.has_event(
+        &Event::new("wasm-proxy-weight")
+            .add_attribute("old_weight", "10")
+            .add_attribute("new_weight", "3")
+    ));
+    // the record keeps what was donated, not the capped weight
+    let history = proxy.withdrawal_history(&app, None, None).unwrap();
+    assert_eq!(history.withdrawals[0].epoch_donations, 5);
+
+    // below the cap nothing is clamped
+    proxy.donate(&mut app, DONOR, &coins(10, DENOM)).unwrap();
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(
+        distribution.withdrawals(&app).unwrap(),
+        vec![(10, -7), (3, -2)]
+    );
+}
+
+#[test]
+fn decay_redistributes_withdrawn_share() {
+    let mut app = app_with_donors(&[(DONOR, 10000)]);
+    let membership = MembershipMock::instantiate(&mut app).unwrap();
+    let distribution = distribution::multitest::CodeId::store_code(&mut app)
+        .instantiate(
+            &mut app,
+            "distribution owner",
+            membership.addr(),
+            "Distribution",
+        )
+        .unwrap();
+    let redistributing = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            decay_funds: DecayFunds::ReDistribute,
+            ..init_msg(distribution.addr())
+        },
+    );
+    let keeping = instantiate_proxy(&mut app, &init_msg(distribution.addr()));
+    for proxy in [&redistributing, &keeping] {
+        membership
+            .add_member(&mut app, proxy.addr().as_str())
+            .unwrap();
+    }
+
+    // both register a weight of 4
+    for proxy in [&redistributing, &keeping] {
+        for _ in 0..4 {
+            proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+        }
+        proxy
+            .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+            .unwrap();
+    }
+    assert_eq!(distribution.total_weight(&app).unwrap(), 8);
+
+    redistributing
+        .donate(&mut app, DONOR, &coins(100, DENOM))
+        .unwrap();
+    let share = distribution
+        .withdrawable(&app, redistributing.addr().as_str(), DENOM)
+        .unwrap();
+    let pending = distribution
+        .withdrawable(&app, keeping.addr().as_str(), DENOM)
+        .unwrap();
+
+    // 4 => 2, the share pulled back is split 2:4
+    app.update_block(|block| block.time = block.time.plus_seconds(3600 * 24 * 30));
+    redistributing.update_weight(&mut app, DONOR).unwrap();
+
+    assert_eq!(balance(&app, redistributing.addr()), 0);
+    assert_eq!(
+        distribution
+            .withdrawable(&app, keeping.addr().as_str(), DENOM)
+            .unwrap(),
+        pending + share * 4 / 6
+    );
+    assert_eq!(
+        distribution
+            .withdrawable(&app, redistributing.addr().as_str(), DENOM)
+            .unwrap(),
+        share * 2 / 6
+    );
+}
+
+#[test]
+fn set_halftime() {
+    const DAY: u64 = 3600 * 24;
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            weight: 1000,
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    let err = proxy
+        .set_halftime(&mut app, DONOR, Duration::Time(DAY))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    let err = proxy
+        .set_halftime(&mut app, OWNER, Duration::Time(0))
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidHalftime, err.downcast().unwrap());
+    let err = proxy
+        .set_halftime(&mut app, OWNER, Duration::Time(11 * 365 * DAY))
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidHalftime, err.downcast().unwrap());
+
+    // 10 of the 30 days passed, with 3 day halftimes that's 3 periods due at once
+    app.update_block(|block| block.time = block.time.plus_seconds(10 * DAY));
+    proxy.update_weight(&mut app, DONOR).unwrap();
+    assert_eq!(proxy.next_update(&app).unwrap().remaining, 20 * DAY);
+    proxy
+        .set_halftime(&mut app, OWNER, Duration::Time(3 * DAY))
+        .unwrap();
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::UpdateWeight {},
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm-proxy-weight-update")
+            .add_attribute("old_weight", "1000")
+            .add_attribute("periods", "3")
+            .add_attribute("new_weight", "125")
+    ));
+    // the day into the fourth period still counts
+    assert_eq!(proxy.next_update(&app).unwrap().remaining, 2 * DAY);
+}
+
+#[test]
+fn halftime_in_blocks() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            weight: 1000,
+            halftime: Duration::Height(10),
+            ..init_msg(distribution.addr())
+        },
+    );
+    let weight_update = |periods: &str, new: &str| {
+        Event::new("wasm-proxy-weight-update")
+            .add_attribute("periods", periods)
+            .add_attribute("new_weight", new)
+    };
+
+    // time alone doesn't count
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(3600 * 24 * 365);
+        block.height += 9;
+    });
+    assert_eq!(proxy.next_update(&app).unwrap().remaining, 1);
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::UpdateWeight {},
+            &[],
+        )
+        .unwrap();
+    assert!(!resp.has_event(&Event::new("wasm-proxy-weight-update")));
+
+    app.update_block(|block| block.height += 21);
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::UpdateWeight {},
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(&weight_update("3", "125")));
+
+    // back to time, the period starts over
+    proxy
+        .set_halftime(&mut app, OWNER, Duration::Time(100))
+        .unwrap();
+    let start = app.block_info().time;
+    assert_eq!(
+        proxy.next_update(&app).unwrap().next_eligible_at,
+        Expiration::AtTime(start.plus_seconds(100))
+    );
+    let err = proxy
+        .set_halftime(&mut app, OWNER, Duration::Height(0))
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidHalftime, err.downcast().unwrap());
+}
+
+fn legacy_msg(distribution: &Addr, halftime: u64) -> LegacyInstantiateMsg {
+    LegacyInstantiateMsg {
+        owner: OWNER.to_owned(),
+        weight: 10,
+        denom: DENOM.to_owned(),
+        direct_part: Decimal::percent(10),
+        distribution_contract: distribution.to_string(),
+        membership_contract: "membership".to_owned(),
+        halftime,
+    }
+}
+
+#[test]
+fn migrate_legacy_halftime() {
+    let mut app = mock_app();
+    let proxy = instantiate_legacy(
+        &mut app,
+        OWNER,
+        &legacy_msg(&Addr::unchecked("distribution"), 3600),
+    )
+    .unwrap();
+    // legacy proxies only kept whole seconds
+    let start = Timestamp::from_seconds(app.block_info().time.seconds());
+
+    let code_id = CodeId::store_code(&mut app);
+    proxy.migrate(&mut app, OWNER, code_id).unwrap();
+    assert_eq!(
+        proxy.next_update(&app).unwrap(),
+        NextUpdateResponse {
+            last_updated: Expiration::AtTime(start),
+            halftime: Duration::Time(3600),
+            next_eligible_at: Expiration::AtTime(start.plus_seconds(3600)),
+            remaining: 3600,
+        }
+    );
+
+    // already converted, migrating again keeps it as is
+    proxy.migrate(&mut app, OWNER, code_id).unwrap();
+    assert_eq!(
+        proxy.next_update(&app).unwrap().halftime,
+        Duration::Time(3600)
+    );
+}
+
+#[test]
+fn migrate_from_baseline() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy =
+        instantiate_legacy(&mut app, OWNER, &legacy_msg(distribution.addr(), 3600)).unwrap();
+
+    let code_id = CodeId::store_code(&mut app);
+    proxy.migrate(&mut app, OWNER, code_id).unwrap();
+    assert_eq!(
+        proxy.config(&app).unwrap(),
+        ConfigResponse {
+            accepted_denoms: vec![DENOM.to_owned()],
+            direct_part: Decimal::percent(10),
+            distribution_contracts: vec![(distribution.addr().clone(), Decimal::one())],
+            membership_contract: Addr::unchecked("membership"),
+            is_closed: false,
+        }
+    );
+    assert_eq!(proxy.owner(&app).unwrap(), Some(Addr::unchecked(OWNER)));
+    assert_eq!(proxy.weight(&app).unwrap(), 10);
+
+    // the counters started at the migration
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    let history = proxy.donation_history(&app, None, None).unwrap();
+    assert_eq!(history.donations[0].id, 0);
+    let stats = proxy.stats(&app).unwrap();
+    assert_eq!(stats.unique_donors, 1);
+    assert_eq!(stats.total_donated.u128(), 100);
+
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(balance(&app, OWNER), 100);
+    let history = proxy.withdrawal_history(&app, None, None).unwrap();
+    assert_eq!(history.withdrawals[0].id, 0);
+}
+
+#[test]
+fn proxy_state_queries() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+    let instantiated_at = app.block_info().time;
+
+    assert_eq!(
+        proxy.config(&app).unwrap(),
+        ConfigResponse {
+            accepted_denoms: vec![DENOM.to_owned()],
+            direct_part: Decimal::percent(10),
+            distribution_contracts: vec![(distribution.addr().clone(), Decimal::one())],
+            membership_contract: Addr::unchecked("membership"),
+            is_closed: false,
+        }
+    );
+    assert_eq!(proxy.owner(&app).unwrap(), Some(Addr::unchecked(OWNER)));
+    assert_eq!(
+        proxy.halftime(&app).unwrap(),
+        Duration::Time(3600 * 24 * 30)
+    );
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(proxy.weight(&app).unwrap(), 10);
+    assert_eq!(proxy.donations(&app).unwrap(), 2);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(3600 * 24 * 30));
+    proxy.update_weight(&mut app, DONOR).unwrap();
+    assert_eq!(
+        proxy.last_updated(&app).unwrap(),
+        Expiration::AtTime(instantiated_at.plus_seconds(3600 * 24 * 30))
+    );
+
+    proxy.close(&mut app, OWNER).unwrap();
+    assert!(proxy.config(&app).unwrap().is_closed);
+}
+
+#[test]
+fn weight_history() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    let start = app.block_info().height;
+
+    // 10 => 2
+    app.update_block(|block| block.height += 5);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+
+    // 2 => 1
+    app.update_block(|block| {
+        block.height += 5;
+        block.time = block.time.plus_seconds(3600 * 24 * 30);
+    });
+    proxy.update_weight(&mut app, DONOR).unwrap();
+
+    // 1 => 3
+    app.update_block(|block| block.height += 5);
+    for _ in 0..3 {
+        proxy.donate(&mut app, DONOR, &coins(10, DENOM)).unwrap();
+    }
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+
+    assert_eq!(proxy.weight_at(&app, start - 1).unwrap(), None);
+    assert_eq!(proxy.weight_at(&app, start).unwrap(), Some(10));
+    assert_eq!(proxy.weight_at(&app, start + 4).unwrap(), Some(10));
+    assert_eq!(proxy.weight_at(&app, start + 5).unwrap(), Some(2));
+    assert_eq!(proxy.weight_at(&app, start + 12).unwrap(), Some(1));
+    assert_eq!(proxy.weight_at(&app, start + 100).unwrap(), Some(3));
+
+    assert_eq!(
+        proxy.weight_history(&app, None, Some(2)).unwrap(),
+        vec![(start + 15, 3), (start + 10, 1)]
+    );
+    assert_eq!(
+        proxy.weight_history(&app, Some(start + 10), None).unwrap(),
+        vec![(start + 5, 2), (start, 10)]
+    );
+}
+
+fn transfer_to(new_owner: &str, expiry: Option<Expiration>) -> Action {
+    Action::TransferOwnership {
+        new_owner: new_owner.to_owned(),
+        expiry,
+    }
+}
+
+#[test]
+fn ownership_transfer() {
+    let mut app = mock_app();
+    let (proxy, _) = setup(&mut app);
+
+    let err = proxy
+        .update_ownership(&mut app, "new_owner", transfer_to("new_owner", None))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    proxy
+        .update_ownership(&mut app, OWNER, transfer_to("new_owner", None))
+        .unwrap();
+    assert_eq!(
+        proxy.ownership(&app).unwrap(),
+        Ownership {
+            owner: Some(Addr::unchecked(OWNER)),
+            pending_owner: Some(Addr::unchecked("new_owner")),
+            pending_expiry: None,
+        }
+    );
+
+    // still the old owner until accepted
+    let err = proxy
+        .set_min_donation(&mut app, "new_owner", 10)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    proxy.set_min_donation(&mut app, OWNER, 10).unwrap();
+
+    let err = proxy
+        .update_ownership(&mut app, "someone", Action::AcceptOwnership)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotPendingOwner),
+        err.downcast().unwrap()
+    );
+
+    let resp = proxy
+        .update_ownership(&mut app, "new_owner", Action::AcceptOwnership)
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "update_ownership")
+            .add_attribute("owner", "new_owner")
+            .add_attribute("pending_owner", "none")
+    ));
+    assert_eq!(
+        proxy.ownership(&app).unwrap().owner,
+        Some(Addr::unchecked("new_owner"))
+    );
+    proxy.set_min_donation(&mut app, "new_owner", 20).unwrap();
+    let err = proxy.set_min_donation(&mut app, OWNER, 30).unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    let err = proxy
+        .update_ownership(&mut app, "new_owner", Action::AcceptOwnership)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::TransferNotFound),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn ownership_transfer_expiry() {
+    let mut app = mock_app();
+    let (proxy, _) = setup(&mut app);
+    let now = app.block_info().time;
+    let height = app.block_info().height;
+
+    let err = proxy
+        .update_ownership(
+            &mut app,
+            OWNER,
+            transfer_to("new_owner", Some(Expiration::AtTime(now))),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidOwnershipExpiry,
+        err.downcast().unwrap()
+    );
+
+    let expiry = Expiration::AtTime(now.plus_seconds(100));
+    proxy
+        .update_ownership(&mut app, OWNER, transfer_to("new_owner", Some(expiry)))
+        .unwrap();
+    assert_eq!(proxy.ownership(&app).unwrap().pending_expiry, Some(expiry));
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+    let err = proxy
+        .update_ownership(&mut app, "new_owner", Action::AcceptOwnership)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::TransferExpired),
+        err.downcast().unwrap()
+    );
+    proxy.set_min_donation(&mut app, OWNER, 10).unwrap();
+
+    // the owner can propose again after an expired transfer, block heights work too
+    let expiry = Expiration::AtHeight(height + 10);
+    proxy
+        .update_ownership(&mut app, OWNER, transfer_to("new_owner", Some(expiry)))
+        .unwrap();
+    proxy
+        .update_ownership(&mut app, "new_owner", Action::AcceptOwnership)
+        .unwrap();
+    proxy.set_min_donation(&mut app, "new_owner", 10).unwrap();
+}
+
+#[test]
+fn cancel_ownership_transfer() {
+    let mut app = mock_app();
+    let (proxy, _) = setup(&mut app);
+
+    let err = proxy
+        .cancel_ownership_transfer(&mut app, OWNER)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::TransferNotFound),
+        err.downcast().unwrap()
+    );
+
+    proxy
+        .update_ownership(&mut app, OWNER, transfer_to("new_owner", None))
+        .unwrap();
+    let err = proxy
+        .cancel_ownership_transfer(&mut app, "new_owner")
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    proxy.cancel_ownership_transfer(&mut app, OWNER).unwrap();
+    assert_eq!(proxy.ownership(&app).unwrap().pending_owner, None);
+    let err = proxy
+        .update_ownership(&mut app, "new_owner", Action::AcceptOwnership)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::TransferNotFound),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn renounce_ownership() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+    proxy
+        .update_ownership(&mut app, OWNER, transfer_to("new_owner", None))
+        .unwrap();
+
+    let err = proxy.renounce_ownership(&mut app, DONOR, true).unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    let err = proxy
+        .renounce_ownership(&mut app, OWNER, false)
+        .unwrap_err();
+    assert_eq!(ContractError::RenounceNotConfirmed, err.downcast().unwrap());
+    // would skip the checks above
+    let err = proxy
+        .update_ownership(&mut app, OWNER, Action::RenounceOwnership)
+        .unwrap_err();
+    assert_eq!(ContractError::RenounceNotConfirmed, err.downcast().unwrap());
+
+    proxy.renounce_ownership(&mut app, OWNER, true).unwrap();
+    assert_eq!(
+        proxy.ownership(&app).unwrap(),
+        Ownership {
+            owner: None,
+            pending_owner: None,
+            pending_expiry: None,
+        }
+    );
+
+    // donations still reach the distribution contract, the direct part the former owner
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(balance(&app, distribution.addr()), 90);
+    assert_eq!(balance(&app, OWNER), 10);
+
+    let err = proxy
+        .withdraw(&mut app, OWNER, None, None, None)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+    let err = proxy.set_min_donation(&mut app, OWNER, 10).unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NoOwner),
+        err.downcast().unwrap()
+    );
+    let err = proxy.close(&mut app, OWNER).unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NoOwner),
+        err.downcast().unwrap()
+    );
+    let err = proxy
+        .update_ownership(&mut app, "new_owner", Action::AcceptOwnership)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::TransferNotFound),
+        err.downcast().unwrap()
+    );
+    let err = proxy.renounce_ownership(&mut app, OWNER, true).unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NoOwner),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn renounce_revokes_operators() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    proxy
+        .grant_operator(&mut app, OWNER, "operator", true, true)
+        .unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.renounce_ownership(&mut app, OWNER, true).unwrap();
+
+    let err = proxy
+        .withdraw(&mut app, "operator", None, None, None)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized, err.downcast().unwrap());
+}
+
+#[test]
+fn migrate_from_previous_version() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    // before the migration only the direct beneficiary is readable, the owner is in the old item
+    let msg = InstantiateMsg {
+        direct_beneficiary: Some(OWNER.to_owned()),
+        ..init_msg(distribution.addr())
+    };
+    let proxy = instantiate_with_version(&mut app, OWNER, "0.1.0", &msg).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(proxy.contract_version(&app).unwrap().version, "0.1.0");
+
+    let code_id = CodeId::store_code(&mut app);
+    proxy.migrate(&mut app, OWNER, code_id).unwrap();
+    assert_eq!(
+        proxy.contract_version(&app).unwrap(),
+        ContractVersion {
+            contract: "proxy".to_owned(),
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+        }
+    );
+    assert_eq!(proxy.total_donated(&app).unwrap().total.u128(), 100);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(distribution.withdrawals(&app).unwrap(), vec![(10, -8)]);
+}
+
+#[test]
+fn contract_version_across_migration() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let code_id = CodeId::store_code(&mut app);
+    let current = env!("CARGO_PKG_VERSION");
+
+    let resp = app
+        .execute(
+            Addr::unchecked(OWNER),
+            WasmMsg::Instantiate {
+                admin: None,
+                code_id: code_id.into(),
+                msg: to_json_binary(&init_msg(distribution.addr())).unwrap(),
+                funds: vec![],
+                label: "Proxy".to_owned(),
+            }
+            .into(),
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("contract_name", "proxy")
+            .add_attribute("contract_version", current)
+    ));
+
+    let proxy =
+        instantiate_with_version(&mut app, OWNER, "0.3.0", &init_msg(distribution.addr())).unwrap();
+    assert_eq!(
+        proxy.contract_version(&app).unwrap(),
+        ContractVersion {
+            contract: "proxy".to_owned(),
+            version: "0.3.0".to_owned(),
+        }
+    );
+
+    let resp = proxy.migrate(&mut app, OWNER, code_id).unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "migrate")
+            .add_attribute("contract_name", "proxy")
+            .add_attribute("contract_version", current)
+            .add_attribute("previous_version", "0.3.0")
+    ));
+    assert_eq!(
+        proxy.contract_version(&app).unwrap(),
+        ContractVersion {
+            contract: "proxy".to_owned(),
+            version: current.to_owned(),
+        }
+    );
+}
+
+#[test]
+fn migrate_moves_owner_to_cw_ownable() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy =
+        instantiate_with_version(&mut app, OWNER, "0.2.0", &init_msg(distribution.addr())).unwrap();
+    assert!(proxy.ownership(&app).is_err());
+
+    let code_id = CodeId::store_code(&mut app);
+    proxy.migrate(&mut app, OWNER, code_id).unwrap();
+    assert_eq!(
+        proxy.ownership(&app).unwrap(),
+        Ownership {
+            owner: Some(Addr::unchecked(OWNER)),
+            pending_owner: None,
+            pending_expiry: None,
+        }
+    );
+
+    proxy.set_min_donation(&mut app, OWNER, 10).unwrap();
+    proxy
+        .update_ownership(&mut app, OWNER, transfer_to("new_owner", None))
+        .unwrap();
+    proxy
+        .update_ownership(&mut app, "new_owner", Action::AcceptOwnership)
+        .unwrap();
+    proxy.set_min_donation(&mut app, "new_owner", 20).unwrap();
+}
+
+#[test]
+fn migrate_seeds_lifetime_donations() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy =
+        instantiate_with_version(&mut app, OWNER, "0.3.0", &init_msg(distribution.addr())).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert!(proxy.stats(&app).is_err());
+
+    let code_id = CodeId::store_code(&mut app);
+    proxy.migrate(&mut app, OWNER, code_id).unwrap();
+    let stats = proxy.stats(&app).unwrap();
+    assert_eq!(stats.donations, 2);
+    assert_eq!(stats.lifetime_donations, 2);
+
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    let stats = proxy.stats(&app).unwrap();
+    assert_eq!(stats.donations, 1);
+    assert_eq!(stats.lifetime_donations, 3);
+}
+
+#[test]
+fn migrate_gathers_state() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy =
+        instantiate_with_version(&mut app, OWNER, "0.4.0", &init_msg(distribution.addr())).unwrap();
+    proxy
+        .set_halftime(&mut app, OWNER, Duration::Height(1000))
+        .unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(50, DENOM)).unwrap();
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(30, DENOM)).unwrap();
+    assert!(proxy.status(&app).is_err());
+
+    let code_id = CodeId::store_code(&mut app);
+    proxy.migrate(&mut app, OWNER, code_id).unwrap();
+    let status = proxy.status(&app).unwrap();
+    assert_eq!(status.weight, 2);
+    assert_eq!(status.donations, 1);
+    assert_eq!(status.lifetime_donations, 3);
+    assert_eq!(status.total_donated.u128(), 180);
+    assert_eq!(status.halftime, Duration::Height(1000));
+    assert_eq!(
+        status.last_updated,
+        Expiration::AtHeight(app.block_info().height)
+    );
+
+    // the separate items are gone
+    for key in [
+        "weight",
+        "donations",
+        "lifetime_donations",
+        "total_donated",
+        "halftime",
+        "last_updated",
+    ] {
+        let raw = app
+            .wrap()
+            .query_wasm_raw(proxy.addr(), key.as_bytes())
+            .unwrap();
+        assert_eq!(raw, None, "{key}");
+    }
+
+    proxy.donate(&mut app, DONOR, &coins(20, DENOM)).unwrap();
+    let stats = proxy.stats(&app).unwrap();
+    assert_eq!(stats.donations, 2);
+    assert_eq!(stats.lifetime_donations, 4);
+    assert_eq!(stats.total_donated.u128(), 200);
+}
+
+#[test]
+fn migrate_rebuilds_donor_index() {
+    let mut app = app_with_donors(&[("donor1", 1000), ("donor2", 1000), ("donor3", 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy =
+        instantiate_with_version(&mut app, OWNER, "0.5.0", &init_msg(distribution.addr())).unwrap();
+    proxy
+        .donate(&mut app, "donor1", &coins(100, DENOM))
+        .unwrap();
+    proxy
+        .donate(&mut app, "donor2", &coins(300, DENOM))
+        .unwrap();
+    proxy
+        .donate(&mut app, "donor3", &coins(200, DENOM))
+        .unwrap();
+
+    let code_id = CodeId::store_code(&mut app);
+    proxy.migrate(&mut app, OWNER, code_id).unwrap();
+    assert_eq!(
+        top(proxy.top_donors(&app, None).unwrap()),
+        [
+            ("donor2".to_owned(), 300),
+            ("donor3".to_owned(), 200),
+            ("donor1".to_owned(), 100)
+        ]
+    );
+
+    proxy
+        .donate(&mut app, "donor1", &coins(250, DENOM))
+        .unwrap();
+    assert_eq!(
+        top(proxy.top_donors(&app, None).unwrap()),
+        [
+            ("donor1".to_owned(), 350),
+            ("donor2".to_owned(), 300),
+            ("donor3".to_owned(), 200)
+        ]
+    );
+}
+
+#[test]
+fn migrate_refuses_downgrade() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy =
+        instantiate_with_version(&mut app, OWNER, "9.0.0", &init_msg(distribution.addr())).unwrap();
+
+    let code_id = CodeId::store_code(&mut app);
+    let err = proxy.migrate(&mut app, OWNER, code_id).unwrap_err();
+    assert_eq!(
+        ContractError::Downgrade {
+            stored: "9.0.0".to_owned(),
+            current: env!("CARGO_PKG_VERSION").to_owned(),
+        },
+        err.downcast().unwrap()
+    );
+    assert_eq!(proxy.contract_version(&app).unwrap().version, "9.0.0");
+}
+
+#[test]
+fn sudo_set_direct_part() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+
+    let err = proxy
+        .sudo(
+            &mut app,
+            &SudoMsg::SetDirectPart {
+                value: Decimal::percent(101),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InalidDirectPart, err.downcast().unwrap());
+
+    let resp = proxy
+        .sudo(
+            &mut app,
+            &SudoMsg::SetDirectPart {
+                value: Decimal::percent(30),
+            },
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "set_direct_part")
+            .add_attribute("origin", "sudo")
+            .add_attribute("new_direct_part", "0.3")
+    ));
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(balance(&app, OWNER), 30);
+    assert_eq!(balance(&app, distribution.addr()), 70);
+}
+
+#[test]
+fn sudo_direct_part_with_protocol_fee() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            protocol_fee: Decimal::percent(20),
+            treasury: Some("treasury".to_owned()),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    let err = proxy
+        .sudo(
+            &mut app,
+            &SudoMsg::SetDirectPart {
+                value: Decimal::percent(90),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidProtocolFee, err.downcast().unwrap());
+}
+
+#[test]
+fn sudo_set_halftime() {
+    let mut app = mock_app();
+    let (proxy, _) = setup(&mut app);
+
+    let err = proxy
+        .sudo(
+            &mut app,
+            &SudoMsg::SetHalftime {
+                value: Duration::Time(0),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidHalftime, err.downcast().unwrap());
+
+    let resp = proxy
+        .sudo(
+            &mut app,
+            &SudoMsg::SetHalftime {
+                value: Duration::Height(100),
+            },
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "set_halftime")
+            .add_attribute("origin", "sudo")
+    ));
+    assert_eq!(
+        proxy.next_update(&app).unwrap().halftime,
+        Duration::Height(100)
+    );
+}
+
+#[test]
+fn sudo_force_close() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+
+    proxy.sudo(&mut app, &SudoMsg::ForceClose {}).unwrap();
+    let err = proxy
+        .donate(&mut app, DONOR, &coins(100, DENOM))
+        .unwrap_err();
+    assert_eq!(ContractError::ContractClosed, err.downcast().unwrap());
+    // no final withdraw for the owner
+    assert_eq!(distribution.withdrawals(&app).unwrap(), vec![]);
+
+    let err = proxy.reopen(&mut app, OWNER).unwrap_err();
+    assert_eq!(ContractError::ClosedByGovernance, err.downcast().unwrap());
+}
+
+#[test]
+fn set_direct_part() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let err = proxy
+        .set_direct_part(&mut app, DONOR, Decimal::zero())
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    let err = proxy
+        .set_direct_part(&mut app, OWNER, Decimal::percent(150))
+        .unwrap_err();
+    assert_eq!(ContractError::InalidDirectPart, err.downcast().unwrap());
+
+    // earlier donations stay split the old way
+    proxy
+        .set_direct_part(&mut app, OWNER, Decimal::zero())
+        .unwrap();
+    assert_eq!(balance(&app, OWNER), 10);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(balance(&app, OWNER), 10);
+    assert_eq!(balance(&app, distribution.addr()), 190);
+
+    proxy
+        .set_direct_part(&mut app, OWNER, Decimal::percent(100))
+        .unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(balance(&app, OWNER), 110);
+    assert_eq!(balance(&app, distribution.addr()), 190);
+}
+
+#[test]
+fn close_sweeps_balances() {
+    let mut app = app_with_balances(&[
+        (DONOR, coins(1000, DENOM)),
+        ("sponsor", vec![coin(50, DENOM), coin(30, "uother")]),
+    ]);
+    let (proxy, distribution) = setup(&mut app);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    // stranded on the proxy
+    app.send_tokens(
+        Addr::unchecked("sponsor"),
+        proxy.addr().clone(),
+        &[coin(50, DENOM), coin(30, "uother")],
+    )
+    .unwrap();
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Close { reason: None },
+            &[],
+        )
+        .unwrap();
+    assert!(
+        resp.has_event(&Event::new("wasm").add_attribute("swept", format!("50{},30uother", DENOM)))
+    );
+
+    // direct part, final withdraw and the swept coins
+    assert_eq!(balance(&app, OWNER), 10 + 90 + 50);
+    assert_eq!(
+        app.wrap()
+            .query_balance(OWNER, "uother")
+            .unwrap()
+            .amount
+            .u128(),
+        30
+    );
+    assert_eq!(balance(&app, proxy.addr()), 0);
+    assert_eq!(distribution.withdrawals(&app).unwrap(), vec![(10, -10)]);
+}
+
+#[test]
+fn close_without_balance_sends_nothing() {
+    let mut app = mock_app();
+    let (proxy, _) = setup(&mut app);
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Close { reason: None },
+            &[],
+        )
+        .unwrap();
+    assert!(!resp
+        .events
+        .iter()
+        .any(|event| event.attributes.iter().any(|attr| attr.key == "swept")));
+}
+
+#[test]
+fn pause() {
+    let mut app = app_with_donors(&[(DONOR, 1000), (OWNER, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let err = proxy.pause(&mut app, DONOR).unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    let err = proxy.unpause(&mut app, OWNER).unwrap_err();
+    assert_eq!(ContractError::NotPaused, err.downcast().unwrap());
+
+    proxy.pause(&mut app, OWNER).unwrap();
+    assert!(proxy.paused(&app).unwrap());
+    let err = proxy.pause(&mut app, OWNER).unwrap_err();
+    assert_eq!(ContractError::Paused, err.downcast().unwrap());
+
+    let msgs = vec![
+        ExecMsg::Donate {
+            memo: None,
+            referrer: None,
+        },
+        ExecMsg::DonateFor {
+            on_behalf_of: DONOR.to_owned(),
+        },
+        ExecMsg::Withdraw {
+            receiver: None,
+            amount: None,
+            denom: None,
+            receiver_msg: None,
+            ibc: None,
+        },
+        ExecMsg::WithdrawSplit {
+            receivers: vec![(OWNER.to_owned(), Decimal::one())],
+            amount: None,
+            denom: None,
+        },
+        ExecMsg::WithdrawDirect { receiver: None },
+        ExecMsg::WithdrawAll { receiver: None },
+        ExecMsg::WithdrawStreamed {
+            receiver: OWNER.to_owned(),
+            duration_seconds: 100,
+        },
+        ExecMsg::ClaimStream {},
+        ExecMsg::ProposeWithdraw {
+            receiver: None,
+            amount: None,
+        },
+        ExecMsg::ApproveWithdraw { id: 0 },
+        ExecMsg::Close { reason: None },
+        ExecMsg::Reopen {},
+        ExecMsg::ProposeMember {
+            addr: "member".to_owned(),
+        },
+        ExecMsg::UpdateWeight {},
+        ExecMsg::Receive(Cw20ReceiveMsg {
+            sender: DONOR.to_owned(),
+            amount: Uint128::new(100),
+            msg: to_json_binary(&Cw20HookMsg::Donate { memo: None }).unwrap(),
+        }),
+        ExecMsg::SetMinDonation {
+            min_donation: Uint128::new(10),
+        },
+        ExecMsg::SetDirectBeneficiary { addr: None },
+        ExecMsg::Block {
+            addr: DONOR.to_owned(),
+        },
+        ExecMsg::Unblock {
+            addr: DONOR.to_owned(),
+        },
+        ExecMsg::FundMatching {},
+        ExecMsg::WithdrawMatching {},
+        ExecMsg::SetHook { addr: None },
+        ExecMsg::SetWithdrawCooldown { cooldown: 10 },
+        ExecMsg::GrantOperator {
+            addr: "operator".to_owned(),
+            can_withdraw: true,
+            can_propose: true,
+        },
+        ExecMsg::RevokeOperator {
+            addr: "operator".to_owned(),
+        },
+        ExecMsg::Crank {},
+        ExecMsg::SetAutoWithdrawThreshold { threshold: None },
+        ExecMsg::SetKeeperFee { keeper_fee_bps: 10 },
+        ExecMsg::SetHalftime {
+            halftime: Duration::Time(100),
+        },
+        ExecMsg::SetDirectPart {
+            value: Decimal::percent(20),
+        },
+        ExecMsg::SetDecay {
+            decay: Decimal::percent(20),
+        },
+        ExecMsg::SetUpdatePolicy {
+            policy: UpdatePolicyMsg::Owner {},
+        },
+        ExecMsg::UpdateOwnership(Action::AcceptOwnership),
+        ExecMsg::CancelOwnershipTransfer {},
+        ExecMsg::RenounceOwnership { confirm: true },
+    ];
+    for msg in msgs {
+        let err = app
+            .execute_contract(Addr::unchecked(OWNER), proxy.addr().clone(), &msg, &[])
+            .unwrap_err();
+        assert_eq!(
+            ContractError::Paused,
+            err.downcast().unwrap(),
+            "{:?} went through while paused",
+            msg
+        );
+    }
+    // queries keep working
+    assert_eq!(proxy.total_donated(&app).unwrap().total.u128(), 100);
+
+    proxy.unpause(&mut app, OWNER).unwrap();
+    assert!(!proxy.paused(&app).unwrap());
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    proxy.set_min_donation(&mut app, OWNER, 10).unwrap();
+}
+
+#[test]
+fn membership_force_close() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let err = proxy.close(&mut app, DONOR).unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked("membership"),
+            proxy.addr().clone(),
+            &ExecMsg::Close { reason: None },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "close")
+            .add_attribute("closed_by", "membership")
+    ));
+    // the owner still gets the share earned so far
+    assert_eq!(distribution.withdrawals(&app).unwrap(), vec![(10, -10)]);
+    assert_eq!(balance(&app, OWNER), 100);
+
+    let err = proxy.reopen(&mut app, OWNER).unwrap_err();
+    assert_eq!(ContractError::ClosedByMembership, err.downcast().unwrap());
+    let err = proxy.close(&mut app, OWNER).unwrap_err();
+    assert_eq!(ContractError::ContractClosed, err.downcast().unwrap());
+}
+
+#[test]
+fn instantiator_force_close() {
+    let mut app = mock_app();
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            membership_contract: "newmembership".to_owned(),
+            instantiated_by: Some("membership".to_owned()),
+            ..init_msg(distribution.addr())
+        },
+    );
+
+    // the contract that spawned it keeps the membership hooks
+    proxy.close(&mut app, "membership").unwrap();
+    let err = proxy.reopen(&mut app, OWNER).unwrap_err();
+    assert_eq!(ContractError::ClosedByMembership, err.downcast().unwrap());
+}
+
+#[test]
+fn owner_close_attributes() {
+    let mut app = mock_app();
+    let (proxy, _) = setup(&mut app);
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::Close { reason: None },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "close")
+            .add_attribute("closed_by", "owner")
+    ));
+
+    // the membership contract can still take over an owner close
+    proxy.close(&mut app, "membership").unwrap();
+    let err = proxy.reopen(&mut app, OWNER).unwrap_err();
+    assert_eq!(ContractError::ClosedByMembership, err.downcast().unwrap());
+}
+
+#[test]
+fn set_distribution_contract() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, old_distribution) = setup(&mut app);
+    let new_distribution = DistributionMock::instantiate(&mut app).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let err = proxy
+        .set_distribution_contract(&mut app, DONOR, new_distribution.addr())
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    let err = proxy
+        .set_distribution_contract(&mut app, OWNER, proxy.addr())
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidDistributionContract,
+        err.downcast().unwrap()
+    );
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            proxy.addr().clone(),
+            &ExecMsg::SetDistributionContract {
+                addr: new_distribution.addr().to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(resp.has_event(
+        &Event::new("wasm")
+            .add_attribute("old_distribution", old_distribution.addr().as_str())
+            .add_attribute("new_distribution", new_distribution.addr().as_str())
+    ));
+    // the old contract paid out before the switch
+    assert_eq!(old_distribution.withdrawals(&app).unwrap(), vec![(10, -9)]);
+    assert_eq!(balance(&app, old_distribution.addr()), 0);
+    assert_eq!(balance(&app, OWNER), 100);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(balance(&app, old_distribution.addr()), 0);
+    assert_eq!(balance(&app, new_distribution.addr()), 90);
+
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(old_distribution.withdrawals(&app).unwrap(), vec![(10, -9)]);
+    assert_eq!(new_distribution.withdrawals(&app).unwrap(), vec![(1, 0)]);
+    assert_eq!(balance(&app, OWNER), 200);
+}
+
+#[test]
+fn closed_proxy_is_frozen() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            goal: Some(Uint128::new(100)),
+            ..init_msg(distribution.addr())
+        },
+    );
+    // reaching the goal closes the proxy without a final withdraw
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(3600 * 24 * 30));
+
+    let err = proxy.propose_member(&mut app, OWNER, "member").unwrap_err();
+    assert_eq!(ContractError::ContractClosed, err.downcast().unwrap());
+    let err = proxy.update_weight(&mut app, DONOR).unwrap_err();
+    assert_eq!(ContractError::ContractClosed, err.downcast().unwrap());
+
+    // the owner can still drain what the distribution owes, the halftime passed
+    // doesn't decay the weight first, the withdraw only moves it to the donations counted
+    let weight = proxy.weight(&app).unwrap();
+    assert_eq!(weight, 10);
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(balance(&app, OWNER), 100);
+    assert_eq!(distribution.withdrawals(&app).unwrap(), vec![(weight, -9)]);
+    assert_eq!(proxy.weight(&app).unwrap(), 1);
+
+    // and again, as often as the owner likes
+    app.update_block(|block| block.time = block.time.plus_seconds(3600 * 24 * 30));
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(
+        distribution.withdrawals(&app).unwrap(),
+        vec![(weight, -9), (1, -1)]
+    );
+    assert_eq!(proxy.weight(&app).unwrap(), 0);
+}
+
+#[test]
+fn close_reason() {
+    let mut app = mock_app();
+    let (proxy, _) = setup(&mut app);
+    let now = app.block_info().time.seconds();
+    let open = proxy.status(&app).unwrap();
+
+    let err = proxy
+        .close_with_reason(&mut app, OWNER, Some(&"x".repeat(257)))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::CloseReasonTooLong { max: 256, len: 257 },
+        err.downcast().unwrap()
+    );
+
+    proxy
+        .close_with_reason(&mut app, OWNER, Some("taking a break"))
+        .unwrap();
+    assert_eq!(
+        proxy.status(&app).unwrap(),
+        StatusResponse {
+            is_closed: true,
+            reason: Some("taking a break".to_owned()),
+            closed_at: Some(now),
+            paused: false,
+            weight: 0, // closing zeroes the weight
+            ..open.clone()
+        }
+    );
+
+    proxy.reopen(&mut app, OWNER).unwrap();
+    assert_eq!(
+        proxy.status(&app).unwrap(),
+        StatusResponse {
+            is_closed: false,
+            reason: None,
+            closed_at: None,
+            paused: false,
+            weight: 0,
+            ..open
+        }
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+    proxy
+        .close_with_reason(&mut app, OWNER, Some("retired"))
+        .unwrap();
+    let status = proxy.status(&app).unwrap();
+    assert_eq!(status.reason, Some("retired".to_owned()));
+    assert_eq!(status.closed_at, Some(now + 100));
+
+    // the owner left no note, the membership contract writes its own
+    proxy.close(&mut app, "membership").unwrap();
+    assert_eq!(
+        proxy.status(&app).unwrap().reason,
+        Some("removed by the membership contract".to_owned())
+    );
+}
+
+#[test]
+fn balances_split_reserved_funds() {
+    let mut app = app_with_balances(&[
+        (DONOR, coins(1000, DENOM)),
+        (OWNER, coins(1000, DENOM)),
+        ("sender", coins(30, "ATOM")),
+    ]);
+    let (proxy, _) = setup(&mut app);
+    let balance_of = |app: &App, denom: &str| -> DenomBalance {
+        let balances = proxy.balances(app).unwrap();
+        for balance in &balances {
+            assert_eq!(balance.spendable, balance.total_balance - balance.reserved);
+        }
+        balances
+            .into_iter()
+            .find(|balance| balance.denom == denom)
+            .unwrap()
+    };
+
+    proxy
+        .fund_matching(&mut app, OWNER, &coins(50, DENOM))
+        .unwrap();
+    app.send_tokens(
+        Addr::unchecked("sender"),
+        proxy.addr().clone(),
+        &coins(30, "ATOM"),
+    )
+    .unwrap();
+    assert_eq!(
+        balance_of(&app, DENOM),
+        DenomBalance {
+            denom: DENOM.to_owned(),
+            total_balance: Uint128::new(50),
+            reserved: Uint128::new(50),
+            spendable: Uint128::zero(),
+        }
+    );
+    assert_eq!(balance_of(&app, "ATOM").spendable.u128(), 30);
+
+    // 3 * 90% leaves 1 as dust, the match comes out of the pool
+    proxy.donate(&mut app, DONOR, &coins(3, DENOM)).unwrap();
+    let orai = balance_of(&app, DENOM);
+    assert_eq!(orai.total_balance, orai.reserved);
+    assert_eq!(orai.total_balance.u128(), 48);
+
+    // between donate and withdraw, direct funds show up as spendable
+    app.send_tokens(
+        Addr::unchecked(DONOR),
+        proxy.addr().clone(),
+        &coins(20, DENOM),
+    )
+    .unwrap();
+    assert_eq!(balance_of(&app, DENOM).spendable.u128(), 20);
+
+    // WithdrawDirect sends exactly what is spendable
+    proxy
+        .withdraw_direct(&mut app, OWNER, Some("direct"))
+        .unwrap();
+    assert_eq!(balance(&app, "direct"), 20);
+    assert_eq!(balance_of(&app, DENOM).spendable, Uint128::zero());
+
+    // the withdrawn share only passes through, reservations stay
+    proxy
+        .withdraw(&mut app, OWNER, Some("receiver"), None, None)
+        .unwrap();
+    let orai = balance_of(&app, DENOM);
+    assert_eq!(orai.reserved.u128(), 48);
+    assert_eq!(orai.spendable, Uint128::zero());
+}
+
+#[test]
+fn status_snapshot() {
+    let mut app = app_with_donors(&[(DONOR, 1000), ("donor2", 1000)]);
+    let (proxy, _) = setup(&mut app);
+    let instantiated_at = app.block_info().time;
+
+    let initial = StatusResponse {
+        owner: Some(Addr::unchecked(OWNER)),
+        weight: 10,
+        donations: 0,
+        lifetime_donations: 0,
+        total_donated: Uint128::zero(),
+        unique_donors: 0,
+        halftime: Duration::Time(3600 * 24 * 30),
+        last_updated: Expiration::AtTime(instantiated_at),
+        is_closed: false,
+        reason: None,
+        closed_at: None,
+        paused: false,
+        withdrawal_pending: false,
+    };
+    assert_eq!(proxy.status(&app).unwrap(), initial);
+
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.donate(&mut app, "donor2", &coins(50, DENOM)).unwrap();
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    assert_eq!(
+        proxy.status(&app).unwrap(),
+        StatusResponse {
+            donations: 3,
+            lifetime_donations: 3,
+            total_donated: Uint128::new(250),
+            unique_donors: 2,
+            ..initial.clone()
+        }
+    );
+
+    proxy.withdraw(&mut app, OWNER, None, None, None).unwrap();
+    assert_eq!(
+        proxy.status(&app).unwrap(),
+        StatusResponse {
+            weight: 3,
+            donations: 0,
+            lifetime_donations: 3,
+            total_donated: Uint128::new(250),
+            unique_donors: 2,
+            ..initial.clone()
+        }
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(3600 * 24 * 30));
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+    proxy.close(&mut app, OWNER).unwrap();
+    assert_eq!(
+        proxy.status(&app).unwrap(),
+        StatusResponse {
+            // closing withdraws what's left and zeroes the weight
+            weight: 0,
+            donations: 0,
+            lifetime_donations: 4,
+            total_donated: Uint128::new(350),
+            unique_donors: 2,
+            last_updated: Expiration::AtTime(instantiated_at.plus_seconds(3600 * 24 * 30)),
+            is_closed: true,
+            reason: None,
+            closed_at: Some(app.block_info().time.seconds()),
+            ..initial
+        }
+    );
+}
+
+#[test]
+fn goal_close_reason() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let distribution = DistributionMock::instantiate(&mut app).unwrap();
+    let proxy = instantiate_proxy(
+        &mut app,
+        &InstantiateMsg {
+            goal: Some(Uint128::new(100)),
+            ..init_msg(distribution.addr())
+        },
+    );
+    proxy.donate(&mut app, DONOR, &coins(100, DENOM)).unwrap();
+
+    let status = proxy.status(&app).unwrap();
+    assert!(status.is_closed);
+    assert_eq!(status.reason, Some("goal reached".to_owned()));
+}
+
+#[test]
+fn receipt_nft_minted_to_donor() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, _) = setup(&mut app);
+    let receipts = instantiate_cw721(&mut app, proxy.addr());
+
+    let err = proxy
+        .set_receipt_nft(&mut app, DONOR, Some((&receipts, 100)))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Ownership(OwnershipError::NotOwner),
+        err.downcast().unwrap()
+    );
+    proxy
+        .set_receipt_nft(&mut app, OWNER, Some((&receipts, 100)))
+        .unwrap();
+
+    let donate_msg = ExecMsg::Donate {
+        memo: None,
+        referrer: None,
+    };
+    // below min_amount => no receipt
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &donate_msg,
+            &coins(50, DENOM),
+        )
+        .unwrap();
+    assert!(resp
+        .events
+        .iter()
+        .flat_map(|ev| &ev.attributes)
+        .all(|attr| attr.key != "token_id"));
+
+    // second donation, so the token gets id 1
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &donate_msg,
+            &coins(100, DENOM),
+        )
+        .unwrap();
+    let wasm = resp.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+    assert!(wasm
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "token_id" && attr.value == "1"));
+
+    let owner: cw721::msg::OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &receipts,
+            &cw721_base::msg::QueryMsg::OwnerOf {
+                token_id: "1".to_owned(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(owner.owner, DONOR);
+    let tokens: cw721::msg::NumTokensResponse = app
+        .wrap()
+        .query_wasm_smart(&receipts, &cw721_base::msg::QueryMsg::NumTokens {})
+        .unwrap();
+    assert_eq!(tokens.count, 1);
+}
+
+#[test]
+fn failed_receipt_mint_keeps_the_donation() {
+    let mut app = app_with_donors(&[(DONOR, 1000)]);
+    let (proxy, distribution) = setup(&mut app);
+    // the proxy isn't the minter, every mint fails
+    let receipts = instantiate_cw721(&mut app, &Addr::unchecked(OWNER));
+    proxy
+        .set_receipt_nft(&mut app, OWNER, Some((&receipts, 100)))
+        .unwrap();
+
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &coins(100, DENOM),
+        )
+        .unwrap();
+
+    let receipt_failed = resp
+        .events
+        .iter()
+        .flat_map(|ev| &ev.attributes)
+        .any(|attr| attr.key == "receipt_failed");
+    assert!(receipt_failed);
+    assert_eq!(balance(&app, distribution.addr()), 90);
+    assert_eq!(proxy.donor(&app, DONOR).unwrap().total.u128(), 100);
+
+    // unset => no more mints
+    proxy.set_receipt_nft(&mut app, OWNER, None).unwrap();
+    let resp = app
+        .execute_contract(
+            Addr::unchecked(DONOR),
+            proxy.addr().clone(),
+            &ExecMsg::Donate {
+                memo: None,
+                referrer: None,
+            },
+            &coins(100, DENOM),
+        )
+        .unwrap();
+    assert!(resp
+        .events
+        .iter()
+        .flat_map(|ev| &ev.attributes)
+        .all(|attr| attr.key != "token_id" && attr.key != "receipt_failed"));
+}
@@ -1,26 +1,263 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, Uint128};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Empty, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::{Duration, Expiration};
+
+use crate::msg::{DecayFunds, IbcWithdraw, ProxyMetadata};
 
 #[cw_serde]
 pub struct Config {
-    pub denom: String,
+    pub accepted_denoms: Vec<String>,
     pub direct_part: Decimal,
-    pub distribution_contract: Addr,
+    pub distribution_contracts: Vec<(Addr, Decimal)>, // weights sum up to 1
     pub membership_contract: Addr,
     pub is_closed: bool,
+    pub cw20_address: Option<Addr>,
+    pub min_donation: Uint128,            // zero means no minimum
+    pub direct_beneficiary: Option<Addr>, // owner receives the direct part if not set, set on renounce
+    pub goal: Option<Uint128>,            // proxy closes itself once reached
+    pub protocol_fee: Decimal,
+    pub treasury: Option<Addr>, // always set when protocol_fee is not zero
+    pub tiers: Vec<(Uint128, String)>, // (threshold, name), ascending by threshold
+    pub bucket_width: u64,      // seconds covered by a single DAILY_STATS bucket
+    pub matching_ratio: Decimal, // matched per donated token, while the pool lasts
+    pub referral_bps: u64,      // referrer's cut of the direct part
+    pub burn_part: Decimal,     // taken next to direct_part, destroyed on every donation
+    pub hook: Option<Addr>,     // notified with ProxyHookMsg on every donation
+    pub withdraw_cooldown: u64, // seconds between owner withdraws, zero disables it
+    pub auto_withdraw_threshold: Option<Uint128>, // Crank needs more than this waiting
+    pub keeper_fee_bps: u64,    // Crank caller's cut of the withdrawn amount
+    pub update_policy: UpdatePolicy, // who may call UpdateWeight
+    pub decay: Decimal,         // part of the weight lost every halftime
+    pub max_weight: Option<u64>, // withdraw never sets the weight above it
+    pub decay_funds: DecayFunds,
+    pub close_reason: Option<CloseReason>, // set while is_closed
+    #[serde(default)]
+    pub close_note: Option<String>, // why it was closed, shown to supporters
+    #[serde(default)]
+    pub closed_at: Option<u64>, // block time of the close, set while is_closed
+    #[serde(default)]
+    pub approvers: Vec<Addr>, // withdraws go through ProposeWithdraw once set
+    #[serde(default)]
+    pub threshold: u32, // approvals a withdraw proposal needs, zero without approvers
+    #[serde(default)]
+    pub proposal_duration: u64, // seconds a withdraw proposal can be approved for
+    #[serde(default)]
+    pub metadata: Option<ProxyMetadata>,
+    #[serde(default)]
+    pub instantiated_by: Option<Addr>, // allowed membership hooks even once membership_contract changes
+    #[serde(default)]
+    pub receipt_nft: Option<ReceiptConfig>, // donors of at least min_amount get a receipt minted
+    #[serde(default)]
+    pub spend_limit: Option<(Uint128, u64)>, // (amount, window seconds) the owner is paid per denom
+    #[serde(default)]
+    pub skip_contract_check: bool, // from instantiate, a later distribution contract isn't checked either
 }
 
+// the proxy has to be the minter of the cw721 contract, token ids are donation ids
 #[cw_serde]
-pub struct WithdrawalData {
+pub struct ReceiptConfig {
+    pub cw721_addr: Addr,
+    pub min_amount: Uint128,
+}
+
+// what donations, withdraws and weight updates change, loaded and saved as a whole
+#[cw_serde]
+pub struct State {
+    pub weight: u64,
+    pub epoch_donations: u64, // since the last withdraw, they make the new weight
+    pub lifetime_donations: u64, // never reset
+    pub total_donated: Uint128, // lifetime, not reset on withdraw
+    pub halftime: Duration,
+    pub last_updated: Expiration, // block time or height of the last decay, in the same unit as halftime
+}
+
+#[cw_serde]
+pub enum CloseReason {
+    Owner,
+    Goal,
+    Membership, // can't be reopened by the owner
+    Governance, // SudoMsg::ForceClose, can't be reopened by the owner either
+}
+
+#[cw_serde]
+pub enum UpdatePolicy {
+    Owner,
+    Keeper(Addr),
+    Membership,
+    Anyone,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct DonorInfo {
+    pub total: Uint128,
+    pub count: u64,
+    pub tier: Option<String>, // highest tier reached by total
+    #[serde(default)]
+    pub last_donated: Option<u64>, // block time of the latest donation
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct RemoteDonorInfo {
+    pub total: Uint128,
+    pub count: u64,
+    pub last_donated: u64, // block time
+}
+
+pub struct DonorIndexes<'a> {
+    // u128 keys are big-endian, so they range in amount order
+    pub amount: MultiIndex<'a, u128, DonorInfo, &'a Addr>,
+}
+
+impl IndexList<DonorInfo> for DonorIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<DonorInfo>> + '_> {
+        let indexes: Vec<&dyn Index<DonorInfo>> = vec![&self.amount];
+        Box::new(indexes.into_iter())
+    }
+}
+
+fn donor_amount(_pk: &[u8], donor: &DonorInfo) -> u128 {
+    donor.total.u128()
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ReferrerInfo {
+    pub count: u64,
+    pub total: Uint128,  // donated through the referrer
+    pub earned: Uint128, // paid to the referrer
+}
+
+#[cw_serde]
+pub struct WithdrawProposal {
+    pub receiver: Addr,
+    pub amount: Option<Uint128>, // whole withdrawable amount if None
+    pub approvals: Vec<Addr>,
+    pub expires: u64, // block time, can't be approved from then on
+}
+
+#[cw_serde]
+pub struct OperatorPerms {
+    pub can_withdraw: bool,
+    pub can_propose: bool,
+}
+
+#[cw_serde]
+pub struct DonationRecord {
+    pub donor: Addr,
+    pub amount: Uint128,
+    pub denom: String, // token contract address for cw20 donations
+    pub memo: Option<String>,
+    pub time: u64,
+}
+
+#[cw_serde]
+pub struct WithdrawalRecord {
+    pub receiver: Addr,
+    pub amount: Uint128, // actually sent or streamed, after the cap and the keeper fee
+    pub denom: String,
+    pub block_time: u64,
+    pub epoch_donations: u64, // donations counted in the epoch the withdraw closed
+}
+
+// paid out to the owner in a denom since start, restarted once a whole window has passed
+#[cw_serde]
+pub struct SpendWindow {
+    pub start: u64,
+    pub spent: Uint128,
+}
+
+#[cw_serde]
+pub struct Stream {
     pub receiver: Addr,
+    pub denom: String,
+    pub total: Uint128,
+    pub claimed: Uint128,
+    pub start: u64,
+    pub end: u64,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct BucketStats {
+    pub count: u64,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct WithdrawalData {
+    pub receivers: Vec<(Addr, Decimal)>, // shares sum up to 1
     pub amount: Option<Uint128>,
+    pub denom: String,
+    // one reply per distribution contract withdrawn from, in a payload the replies still to come after it
+    pub pending_replies: u32,
+    // reported by the distribution contracts so far, only PENDING_WITHDRAWAL keeps the running total
+    pub received: Uint128,
+    pub previous_donations: u64, // epoch donations before the withdraw, kept in its record
+    pub new_weight: u64,         // saved by the reply once every distribution contract succeeded
+    pub keeper: Option<Addr>,    // set by Crank, paid the keeper fee
+    pub receiver_msg: Option<Binary>, // executed on the only receiver instead of a bank send
+    pub stream_duration: Option<u64>, // seconds the payout is vested over, paid at once if None
+    // what the denom grew by is paid out, a denom outside accepted_denoms growing means a misbehaving distribution
+    pub balances_before: Vec<Coin>,
+    #[serde(default)]
+    pub new_distribution: Option<Addr>, // replaces the distribution contracts once this withdraw succeeds
+    #[serde(default)]
+    pub target: Option<Addr>, // distribution contract withdrawn from, only set in a payload
+    #[serde(default)]
+    pub ibc: Option<IbcWithdraw>, // paid over IBC instead of to the receiver
 }
 
-pub const OWNER: Item<Addr> = Item::new("owner");
-pub const WEIGHT: Item<u64> = Item::new("weight");
-pub const DONATIONS: Item<u64> = Item::new("donations");
+// every execute message but Pause and Unpause is refused while set, queries keep working
+pub const PAUSED: Item<bool> = Item::new("paused");
+pub const STATE: Item<State> = Item::new("state");
+// the weight by the block height it was set at, the last change in a block wins
+pub const WEIGHT_HISTORY: Map<u64, u64> = Map::new("weight_history");
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const HALFTIME: Item<u64> = Item::new("halftime");
-pub const LAST_UPDATED: Item<u64> = Item::new("last_updated");
+// cumulative contribution of every donor
+// indexed by total so the leaderboard can be paged, saving or removing a donor keeps the index in sync
+pub const DONORS: IndexedMap<&Addr, DonorInfo, DonorIndexes> = IndexedMap::new(
+    "donors",
+    DonorIndexes {
+        amount: MultiIndex::new(donor_amount, "donors", "donors__amount"),
+    },
+);
+// (chain, donor) for donations made over IBC, the hook sender is the one in DONORS
+pub const REMOTE_DONORS: Map<(&str, &str), RemoteDonorInfo> = Map::new("remote_donors");
+pub const UNIQUE_DONORS: Item<u64> = Item::new("unique_donors"); // never reset, unlike the epoch donations
+pub const DONATION_HISTORY: Map<u64, DonationRecord> = Map::new("donation_history"); // append only
+pub const NEXT_DONATION_ID: Item<u64> = Item::new("next_donation_id");
+pub const WITHDRAWAL_HISTORY: Map<u64, WithdrawalRecord> = Map::new("withdrawal_history"); // one record per receiver paid
+pub const NEXT_WITHDRAWAL_ID: Item<u64> = Item::new("next_withdrawal_id");
+// removed once executed, expired ones are kept
+pub const WITHDRAW_PROPOSALS: Map<u64, WithdrawProposal> = Map::new("withdraw_proposals");
+pub const NEXT_PROPOSAL_ID: Item<u64> = Item::new("next_proposal_id");
+// keyed by block time / bucket_width
+pub const DAILY_STATS: Map<u64, BucketStats> = Map::new("daily_stats");
+// rounding leftovers kept on the proxy per denom (token address for cw20)
+// until they are forwarded with the next donation
+pub const DUST: Map<&str, Uint128> = Map::new("dust");
+// owner funds matching donations in the first accepted denom
+pub const MATCHING_POOL: Item<Uint128> = Item::new("matching_pool");
+pub const REFERRERS: Map<&Addr, ReferrerInfo> = Map::new("referrers");
+// donor, amount, time
+pub const LARGEST_DONATION: Item<(Addr, Uint128, u64)> = Item::new("largest_donation");
+// owner funds left on the proxy by a capped withdraw, paid with the next one
+pub const RETAINED: Map<&str, Uint128> = Map::new("retained");
+// block time of the last owner withdraw
+pub const LAST_WITHDRAWN: Item<u64> = Item::new("last_withdrawn");
+// every withdraw reply gets its WithdrawalData in the submessage payload, this only keeps
+// the received total across the replies, queries and the guard against a second withdraw
 pub const PENDING_WITHDRAWAL: Item<WithdrawalData> = Item::new("pending_withdrawal");
+// distribution contracts whose decay withdraw reply is still to come, in the order they were sent
+pub const DECAY_TARGETS: Item<Vec<Addr>> = Item::new("decay_targets");
+// candidate => proxy created for them by the membership contract on our proposal
+pub const PROPOSED_MEMBERS: Map<&Addr, Addr> = Map::new("proposed_members");
+// withdrawn funds kept on the proxy and vested to the receiver, removed once fully claimed
+pub const STREAM: Item<Stream> = Item::new("stream");
+pub const OPERATORS: Map<&Addr, OperatorPerms> = Map::new("operators"); // act for the owner within their perms
+pub const BLOCKLIST: Map<&Addr, Empty> = Map::new("blocklist"); // donors refused by the owner
+pub const WEIGHTED_TARGETS: Map<&Addr, Empty> = Map::new("weighted_targets"); // distribution contracts we have been distributing to
+pub const SPEND_WINDOWS: Map<&str, SpendWindow> = Map::new("spend_windows"); // denom => its window